@@ -0,0 +1,484 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Exporting the arithmetic tables of a field for use outside of generated Rust code
+
+use std::{fmt, fs, io, path::Path};
+
+use crate::{tables, Error, Field};
+
+/// Largest degree for which tables with one entry per field element can be exported
+pub const MAX_EXPORT_DEGREE: u64 = 24;
+
+/// A table that can be exported
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ExportTable {
+    /// The multiplication table used by the generated code, as 4d array
+    /// `[nparts][nparts][256][256]`. `nparts` is the number of bytes of an element, entry
+    /// `[l][r][i][j]` is the product of `i << 8 * l` and `j << 8 * r`.
+    Mul,
+    /// The inversion table used by the generated code. The inverse of 0 is set to 0.
+    Inv,
+    /// Discrete logarithms to the base of [`Field::generator`]. The logarithm of 0 is set to 0.
+    Log,
+    /// Powers of [`Field::generator`], with `2^p - 1` entries
+    Exp,
+    /// The nibble tables used by `g2p::simd`, as 3d array `[2^p][2][16]`. Entry `[c][0][n]` is
+    /// the product of `c` and `n`, entry `[c][1][n]` the product of `c` and `n << 4`. Only
+    /// available for p <= 8.
+    Nibble,
+}
+
+impl ExportTable {
+    /// All tables, in the order used by [`TableExport`]
+    pub const ALL: [ExportTable; 5] = [
+        ExportTable::Mul,
+        ExportTable::Inv,
+        ExportTable::Log,
+        ExportTable::Exp,
+        ExportTable::Nibble,
+    ];
+
+    /// The lower case name of the table
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportTable::Mul => "mul",
+            ExportTable::Inv => "inv",
+            ExportTable::Log => "log",
+            ExportTable::Exp => "exp",
+            ExportTable::Nibble => "nibble",
+        }
+    }
+
+    /// Check if the table can be exported for a field of size 2^p
+    pub fn is_supported(self, p: u64) -> bool {
+        match self {
+            ExportTable::Mul => true,
+            ExportTable::Inv | ExportTable::Log | ExportTable::Exp => p <= MAX_EXPORT_DEGREE,
+            ExportTable::Nibble => p <= 8,
+        }
+    }
+
+    fn dimensions(self, p: u64) -> Vec<usize> {
+        let field_size = 1_usize << p;
+        let nparts = tables::ceil_log256(field_size);
+        match self {
+            ExportTable::Mul => vec![nparts, nparts, 256, 256],
+            ExportTable::Inv | ExportTable::Log => vec![field_size],
+            ExportTable::Exp => vec![field_size - 1],
+            ExportTable::Nibble => vec![field_size, 2, 16],
+        }
+    }
+
+    fn values(self, field: &Field) -> Vec<u32> {
+        match self {
+            ExportTable::Mul => tables::mul_table(field.modulus),
+            ExportTable::Inv => tables::inv_table(field.modulus),
+            ExportTable::Log => tables::log_table(field.modulus, field.generator),
+            ExportTable::Exp => tables::exp_table(field.modulus, field.generator),
+            ExportTable::Nibble => tables::nibble_table(field.modulus),
+        }
+    }
+}
+
+impl fmt::Display for ExportTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The format of exported tables
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ExportFormat {
+    /// A C header with `static const` arrays and `#define`s for the field parameters
+    C,
+    /// A Rust module with `pub static` arrays and `pub const`s for the field parameters
+    Rust,
+    /// The tables concatenated in the requested order, entries are stored little endian
+    Binary,
+}
+
+/// The integer type used for table entries
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ElementType {
+    /// `uint8_t` in C, `u8` in Rust
+    U8,
+    /// `uint16_t` in C, `u16` in Rust
+    U16,
+    /// `uint32_t` in C, `u32` in Rust
+    U32,
+}
+
+impl ElementType {
+    /// The smallest type that holds elements of GF(2^p), which is also used by the generated code
+    pub fn for_degree(p: u64) -> Self {
+        match p {
+            0..=8 => ElementType::U8,
+            9..=16 => ElementType::U16,
+            _ => ElementType::U32,
+        }
+    }
+
+    /// The number of bits of the type
+    pub fn bits(self) -> u64 {
+        match self {
+            ElementType::U8 => 8,
+            ElementType::U16 => 16,
+            ElementType::U32 => 32,
+        }
+    }
+
+    fn c_name(self) -> &'static str {
+        match self {
+            ElementType::U8 => "uint8_t",
+            ElementType::U16 => "uint16_t",
+            ElementType::U32 => "uint32_t",
+        }
+    }
+
+    fn rust_name(self) -> &'static str {
+        match self {
+            ElementType::U8 => "u8",
+            ElementType::U16 => "u16",
+            ElementType::U32 => "u32",
+        }
+    }
+}
+
+/// Export of the tables of a [`Field`] as source code or binary data
+///
+/// The tables are the same ones used by the code generated for the field, so C code using the
+/// exported tables computes exactly the same results as the Rust side.
+///
+/// ```rust
+/// # use g2build::{ElementType, ExportFormat, ExportTable, Field};
+/// let field = Field::new("GF16", 4).unwrap();
+/// let export = field
+///     .export_tables(&[ExportTable::Inv, ExportTable::Exp])
+///     .unwrap()
+///     .format(ExportFormat::C)
+///     .element_type(ElementType::U16)
+///     .unwrap();
+///
+/// let mut header = Vec::new();
+/// export.write(&mut header).unwrap();
+/// let header = String::from_utf8(header).unwrap();
+/// assert!(header.contains("static const uint16_t GF16_INV_TABLE[16] = {"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TableExport<'a> {
+    field: &'a Field,
+    tables: Vec<ExportTable>,
+    format: ExportFormat,
+    element_type: ElementType,
+}
+
+impl<'a> TableExport<'a> {
+    pub(crate) fn new(field: &'a Field, tables: &[ExportTable]) -> Result<Self, Error> {
+        if let Some(&table) = tables.iter().find(|t| !t.is_supported(field.p)) {
+            return Err(Error::UnsupportedTable { table, p: field.p });
+        }
+
+        let mut unique = Vec::with_capacity(tables.len());
+        for &table in tables {
+            if !unique.contains(&table) {
+                unique.push(table);
+            }
+        }
+
+        Ok(TableExport {
+            field,
+            tables: unique,
+            format: ExportFormat::C,
+            element_type: ElementType::for_degree(field.p),
+        })
+    }
+
+    /// Set the output format, the default is [`ExportFormat::C`]
+    pub fn format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set the type of the table entries
+    ///
+    /// The default is the smallest type holding an element, which is also the type used by the
+    /// generated code. Larger types can be used to match existing code.
+    pub fn element_type(mut self, element_type: ElementType) -> Result<Self, Error> {
+        if element_type.bits() < self.field.p {
+            return Err(Error::ElementTypeTooSmall {
+                p: self.field.p,
+                element_type,
+            });
+        }
+        self.element_type = element_type;
+        Ok(self)
+    }
+
+    /// Write the exported tables
+    pub fn write<W: io::Write + ?Sized>(&self, out: &mut W) -> io::Result<()> {
+        match self.format {
+            ExportFormat::C => self.write_c(out),
+            ExportFormat::Rust => self.write_rust(out),
+            ExportFormat::Binary => {
+                let width = (self.element_type.bits() / 8) as usize;
+                for &table in &self.tables {
+                    out.write_all(&tables::table_bytes(&table.values(self.field), width))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Write the exported tables to a file
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = io::BufWriter::new(fs::File::create(path)?);
+        self.write(&mut out)?;
+        io::Write::flush(&mut out)
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "{}: GF(2^{}) with modulus {:#x} and generator {:#x}, generated by g2build",
+            self.field.name, self.field.p, self.field.modulus.0, self.field.generator.0
+        )
+    }
+
+    fn write_c<W: io::Write + ?Sized>(&self, out: &mut W) -> io::Result<()> {
+        let prefix = self.field.name.to_uppercase();
+        let ty = self.element_type.c_name();
+
+        writeln!(out, "/* {} */", self.description())?;
+        writeln!(out, "#ifndef {}_TABLES_H", prefix)?;
+        writeln!(out, "#define {}_TABLES_H", prefix)?;
+        writeln!(out)?;
+        writeln!(out, "#include <stdint.h>")?;
+        writeln!(out)?;
+        writeln!(out, "#define {}_DEGREE {}", prefix, self.field.p)?;
+        writeln!(
+            out,
+            "#define {}_MODULUS {:#x}u",
+            prefix, self.field.modulus.0
+        )?;
+        writeln!(
+            out,
+            "#define {}_GENERATOR {:#x}u",
+            prefix, self.field.generator.0
+        )?;
+        for &table in &self.tables {
+            let dims = table.dimensions(self.field.p);
+            let dims_str: String = dims.iter().map(|d| format!("[{}]", d)).collect();
+            writeln!(out)?;
+            write!(
+                out,
+                "static const {} {}_{}_TABLE{} = ",
+                ty,
+                prefix,
+                table.name().to_uppercase(),
+                dims_str
+            )?;
+            self.write_array(out, &table.values(self.field), &dims, ('{', '}'), 0)?;
+            writeln!(out, ";")?;
+        }
+        writeln!(out)?;
+        writeln!(out, "#endif /* {}_TABLES_H */", prefix)
+    }
+
+    fn write_rust<W: io::Write + ?Sized>(&self, out: &mut W) -> io::Result<()> {
+        let ty = self.element_type.rust_name();
+
+        writeln!(out, "// {}", self.description())?;
+        writeln!(out)?;
+        writeln!(out, "pub const DEGREE: u32 = {};", self.field.p)?;
+        writeln!(out, "pub const MODULUS: u64 = {:#x};", self.field.modulus.0)?;
+        writeln!(
+            out,
+            "pub const GENERATOR: {} = {:#x};",
+            ty, self.field.generator.0
+        )?;
+        for &table in &self.tables {
+            let dims = table.dimensions(self.field.p);
+            let array_ty = dims
+                .iter()
+                .rev()
+                .fold(ty.to_string(), |inner, d| format!("[{}; {}]", inner, d));
+            writeln!(out)?;
+            write!(
+                out,
+                "pub static {}_TABLE: {} = ",
+                table.name().to_uppercase(),
+                array_ty
+            )?;
+            self.write_array(out, &table.values(self.field), &dims, ('[', ']'), 0)?;
+            writeln!(out, ";")?;
+        }
+        Ok(())
+    }
+
+    /// Write a nested array literal, with 16 entries per line in the innermost arrays
+    fn write_array<W: io::Write + ?Sized>(
+        &self,
+        out: &mut W,
+        values: &[u32],
+        dims: &[usize],
+        (open, close): (char, char),
+        depth: usize,
+    ) -> io::Result<()> {
+        let indent = "    ".repeat(depth + 1);
+        let digits = ((self.field.p + 3) / 4) as usize;
+
+        writeln!(out, "{}", open)?;
+        if dims.len() == 1 {
+            for row in values.chunks(16) {
+                write!(out, "{}", indent)?;
+                for (i, v) in row.iter().enumerate() {
+                    let sep = if i == 0 { "" } else { " " };
+                    write!(out, "{}{:#0w$x},", sep, v, w = digits + 2)?;
+                }
+                writeln!(out)?;
+            }
+        } else {
+            let chunk = dims[1..].iter().product();
+            for part in values.chunks(chunk) {
+                write!(out, "{}", indent)?;
+                self.write_array(out, part, &dims[1..], (open, close), depth + 1)?;
+                writeln!(out, ",")?;
+            }
+        }
+        write!(out, "{}{}", "    ".repeat(depth), close)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use g2poly::G2Poly;
+
+    fn export_string(export: &TableExport) -> String {
+        let mut out = Vec::new();
+        export.write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_export_c() {
+        let field = Field::new("gf4", 2).unwrap();
+        let export = field.export_tables(&[ExportTable::Inv]).unwrap();
+        assert_eq!(
+            export_string(&export),
+            "/* gf4: GF(2^2) with modulus 0x7 and generator 0x2, generated by g2build */
+#ifndef GF4_TABLES_H
+#define GF4_TABLES_H
+
+#include <stdint.h>
+
+#define GF4_DEGREE 2
+#define GF4_MODULUS 0x7u
+#define GF4_GENERATOR 0x2u
+
+static const uint8_t GF4_INV_TABLE[4] = {
+    0x0, 0x1, 0x3, 0x2,
+};
+
+#endif /* GF4_TABLES_H */
+"
+        );
+
+        let export = field
+            .export_tables(&[ExportTable::Nibble])
+            .unwrap()
+            .element_type(ElementType::U32)
+            .unwrap();
+        let header = export_string(&export);
+        assert!(header.contains(
+            "static const uint32_t GF4_NIBBLE_TABLE[4][2][16] = {
+    {
+        {
+            0x0, 0x0,"
+        ));
+    }
+
+    #[test]
+    fn test_export_rust() {
+        let field = Field::new("GF8", 3).unwrap();
+        let export = field
+            .export_tables(&[ExportTable::Exp, ExportTable::Mul])
+            .unwrap()
+            .format(ExportFormat::Rust);
+        let module = export_string(&export);
+        assert!(module.contains("pub const GENERATOR: u8 = 0x2;\n"));
+        assert!(module.contains(
+            "pub static EXP_TABLE: [u8; 7] = [\n    0x1, 0x2, 0x4, 0x3, 0x6, 0x7, 0x5,\n];\n"
+        ));
+        assert!(module
+            .contains("pub static MUL_TABLE: [[[[u8; 256]; 256]; 1]; 1] = [\n    [\n        [\n"));
+        // Same entries as the table of the generated code
+        let entries: Vec<u32> = module
+            .split("MUL_TABLE")
+            .nth(1)
+            .unwrap()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter_map(|v| v.strip_prefix("0x"))
+            .map(|v| u32::from_str_radix(v, 16).unwrap())
+            .collect();
+        assert_eq!(entries, tables::mul_table(field.modulus));
+    }
+
+    #[test]
+    fn test_export_binary() {
+        let field = Field::with_modulus("GF256", 8, G2Poly(0b1_0001_1011)).unwrap();
+        let export = field
+            .export_tables(&[ExportTable::Inv, ExportTable::Log, ExportTable::Exp])
+            .unwrap()
+            .format(ExportFormat::Binary)
+            .element_type(ElementType::U16)
+            .unwrap();
+        let mut out = Vec::new();
+        export.write(&mut out).unwrap();
+        assert_eq!(out.len(), 2 * (256 + 256 + 255));
+        assert_eq!(out[2 * 0x53..2 * 0x53 + 2], [0xca, 0]);
+        // log(3) = 1 and exp(1) = 3
+        assert_eq!(out[512 + 2 * 3..512 + 2 * 3 + 2], [1, 0]);
+        assert_eq!(out[1024 + 2..1024 + 4], [3, 0]);
+    }
+
+    #[test]
+    fn test_export_duplicates() {
+        let field = Field::new("gf4", 2).unwrap();
+        let once = field
+            .export_tables(&[ExportTable::Inv, ExportTable::Exp])
+            .unwrap();
+        let twice = field
+            .export_tables(&[ExportTable::Inv, ExportTable::Exp, ExportTable::Inv])
+            .unwrap();
+        let header = export_string(&twice);
+        assert_eq!(header.matches("GF4_INV_TABLE").count(), 1);
+        assert_eq!(header, export_string(&once));
+    }
+
+    #[test]
+    fn test_export_errors() {
+        let field = Field::new("GF1024", 10).unwrap();
+        assert_eq!(
+            field.export_tables(&[ExportTable::Nibble]).unwrap_err(),
+            Error::UnsupportedTable {
+                table: ExportTable::Nibble,
+                p: 10
+            }
+        );
+        assert_eq!(
+            field
+                .export_tables(&[ExportTable::Inv])
+                .unwrap()
+                .element_type(ElementType::U8)
+                .unwrap_err(),
+            Error::ElementTypeTooSmall {
+                p: 10,
+                element_type: ElementType::U8
+            }
+        );
+    }
+}
@@ -0,0 +1,1026 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Code generator for finite field types
+//!
+//! This crate contains the code generator used by the [`g2p!`](https://docs.rs/g2p) macro. It is
+//! a regular library, so it can also be called from a build script. Generating the tables for
+//! large fields is slow, and the macro repeats that work every time the invoking crate is
+//! compiled. A build script only runs when it changed, and it can store the tables as binary
+//! files which are loaded with `include_bytes!` instead of being parsed by rustc.
+//!
+//! The generated code is the same as the output of `g2p!` and refers to the `g2p` crate, so that
+//! crate is still needed as a regular dependency.
+//!
+//! # Example
+//! In `build.rs`:
+//! ```no_run
+//! g2build::Field::new("GF65536", 16)
+//!     .unwrap()
+//!     .table_storage(g2build::TableStorage::Binary)
+//!     .write_to_out_dir("gf65536.rs")
+//!     .unwrap();
+//! ```
+//!
+//! In the crate itself:
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/gf65536.rs"));
+//! ```
+//!
+//! # Exporting tables
+//! The tables used by the generated code can also be exported as C header, Rust module or raw
+//! binary data, see [`TableExport`]. This keeps code in other languages bit-exact with the Rust
+//! side.
+//! ```no_run
+//! g2build::Field::new("GF256", 8)
+//!     .unwrap()
+//!     .export_tables(&[g2build::ExportTable::Mul, g2build::ExportTable::Inv])
+//!     .unwrap()
+//!     .format(g2build::ExportFormat::C)
+//!     .write_to("gf256_tables.h")
+//!     .unwrap();
+//! ```
+
+use std::{
+    env, error, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use g2poly::G2Poly;
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::quote;
+
+mod export;
+mod tables;
+
+pub use export::{ElementType, ExportFormat, ExportTable, TableExport, MAX_EXPORT_DEGREE};
+use tables::ceil_log256;
+
+/// Errors that can occur when describing a field
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The name of the type is not a valid identifier
+    InvalidName(String),
+    /// Fields are only supported for 0 < p <= 32
+    UnsupportedDegree(u64),
+    /// The degree of the modulus does not match p
+    ModulusDegree {
+        /// The requested field size exponent
+        p: u64,
+        /// The modulus with mismatched degree
+        modulus: G2Poly,
+    },
+    /// The modulus is not irreducible, so it does not generate a field
+    ReducibleModulus(G2Poly),
+    /// The table can not be exported for fields of this size
+    UnsupportedTable {
+        /// The requested table
+        table: ExportTable,
+        /// The field size exponent
+        p: u64,
+    },
+    /// The element type of exported tables can not hold the field elements
+    ElementTypeTooSmall {
+        /// The field size exponent
+        p: u64,
+        /// The requested element type
+        element_type: ElementType,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidName(name) => write!(f, "'{}' is not a valid identifier", name),
+            Error::UnsupportedDegree(0) => write!(f, "p must be > 0"),
+            Error::UnsupportedDegree(_) => write!(f, "p > 32 is not implemented right now"),
+            Error::ModulusDegree { p, modulus } => {
+                write!(f, "Modulus {} does not have degree {}", modulus, p)
+            }
+            Error::ReducibleModulus(modulus) => {
+                write!(f, "Modulus {} is not irreducible", modulus)
+            }
+            Error::UnsupportedTable { table, p } => {
+                write!(f, "The {} table can not be exported for p = {}", table, p)
+            }
+            Error::ElementTypeTooSmall { p, element_type } => write!(
+                f,
+                "{:?} can not hold elements of a field with p = {}",
+                element_type, p
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Where the generated code takes the precomputed tables from
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TableStorage {
+    /// The tables are array literals in the generated source. This is what `g2p!` uses.
+    Source,
+    /// The tables are written to binary files next to the generated source and loaded with
+    /// `include_bytes!`. Only available when writing to a directory.
+    Binary,
+    /// The tables are computed on first use and stored on the heap or in a buffer provided by the
+    /// user. This keeps large tables out of the binary, see `g2p::lazy`.
+    Lazy,
+}
+
+/// Description of a finite field type GF(2^p) to generate
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Field {
+    name: String,
+    p: u64,
+    modulus: G2Poly,
+    generator: G2Poly,
+    storage: TableStorage,
+    constant_time: bool,
+}
+
+impl Field {
+    /// Describe the field GF(2^p) using the smallest irreducible polynomial of degree p as
+    /// modulus.
+    ///
+    /// ```rust
+    /// # use g2build::Field;
+    /// # use g2poly::G2Poly;
+    /// let field = Field::new("GF16", 4).unwrap();
+    /// assert_eq!(field.modulus(), G2Poly(0b10011));
+    /// ```
+    pub fn new(name: &str, p: u64) -> Result<Self, Error> {
+        check_degree(p)?;
+        Field::with_modulus(name, p, find_modulus_poly(p))
+    }
+
+    /// Describe the field GF(2^p), reducing with the given modulus
+    ///
+    /// The modulus must be an irreducible polynomial of degree p.
+    ///
+    /// ```rust
+    /// # use g2build::{Error, Field};
+    /// # use g2poly::G2Poly;
+    /// assert!(Field::with_modulus("GF256", 8, G2Poly(0b1_0001_1011)).is_ok());
+    /// assert_eq!(
+    ///     Field::with_modulus("GF256", 8, G2Poly(0b1_0000_0001)),
+    ///     Err(Error::ReducibleModulus(G2Poly(0b1_0000_0001))),
+    /// );
+    /// ```
+    pub fn with_modulus(name: &str, p: u64, modulus: G2Poly) -> Result<Self, Error> {
+        if !is_identifier(name) {
+            return Err(Error::InvalidName(name.to_string()));
+        }
+        check_degree(p)?;
+        if modulus.degree() != Some(p) {
+            return Err(Error::ModulusDegree { p, modulus });
+        }
+        if !modulus.is_irreducible() {
+            return Err(Error::ReducibleModulus(modulus));
+        }
+
+        Ok(Field {
+            name: name.to_string(),
+            p,
+            modulus,
+            generator: find_generator(modulus),
+            storage: TableStorage::Source,
+            constant_time: false,
+        })
+    }
+
+    /// Set how the tables are stored when writing the field to a directory
+    pub fn table_storage(mut self, storage: TableStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Generate constant time arithmetic
+    ///
+    /// Multiplication is implemented using bitwise operations instead of table lookups, and
+    /// inversion uses exponentiation with a fixed sequence of multiplications. The generated type
+    /// also implements `ConstantTimeEq` and `ConditionallySelectable` from the `subtle` crate,
+    /// which requires the `subtle` feature of `g2p`.
+    ///
+    /// Constant time fields do not use any tables, so the [`TableStorage`] is ignored.
+    pub fn constant_time(mut self, constant_time: bool) -> Self {
+        self.constant_time = constant_time;
+        self
+    }
+
+    /// The name of the generated type
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The power of 2 specifying the field size
+    pub fn p(&self) -> u64 {
+        self.p
+    }
+
+    /// The reduction polynomial
+    pub fn modulus(&self) -> G2Poly {
+        self.modulus
+    }
+
+    /// The generator of the multiplicative group, exported as `GaloisField::GENERATOR`
+    pub fn generator(&self) -> G2Poly {
+        self.generator
+    }
+
+    /// Generate the code for the field type
+    ///
+    /// Since no files are written, [`TableStorage::Binary`] falls back to embedding the tables in
+    /// the source.
+    pub fn generate(&self) -> TokenStream {
+        let tables = match self.storage {
+            TableStorage::Lazy => Tables::Lazy,
+            TableStorage::Source | TableStorage::Binary => Tables::Inline,
+        };
+        generate_field(self, &tables)
+    }
+
+    /// Write the code for the field type to `dir/file_name`
+    ///
+    /// With [`TableStorage::Binary`], the tables are written next to it, using the file name
+    /// without extension and the suffixes `_mul.bin` and `_inv.bin`. Returns the path of the
+    /// generated source file, which can be used with `include!`.
+    pub fn write_to<P: AsRef<Path>>(&self, dir: P, file_name: &str) -> io::Result<PathBuf> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let dir = fs::canonicalize(dir)?;
+
+        let tables = match self.storage {
+            _ if self.constant_time => Tables::Inline,
+            TableStorage::Source => Tables::Inline,
+            TableStorage::Lazy => Tables::Lazy,
+            TableStorage::Binary => {
+                let stem = Path::new(file_name)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "Invalid file name")
+                    })?;
+                let width = self.repr_bytes();
+
+                let mul = dir.join(format!("{}_mul.bin", stem));
+                let inv = dir.join(format!("{}_inv.bin", stem));
+                fs::write(
+                    &mul,
+                    tables::table_bytes(&tables::mul_table(self.modulus), width),
+                )?;
+                fs::write(
+                    &inv,
+                    tables::table_bytes(&tables::inv_table(self.modulus), width),
+                )?;
+                Tables::Files {
+                    mul: path_str(&mul)?,
+                    inv: path_str(&inv)?,
+                    width,
+                }
+            }
+        };
+
+        let path = dir.join(file_name);
+        fs::write(&path, generate_field(self, &tables).to_string())?;
+        Ok(path)
+    }
+
+    /// Export the arithmetic tables of the field, for example as C header
+    ///
+    /// See [`TableExport`] for the available formats. Tables with one entry per element are
+    /// limited to p <= [`MAX_EXPORT_DEGREE`], nibble tables to p <= 8. Tables requested more than
+    /// once are only exported once.
+    pub fn export_tables(&self, tables: &[ExportTable]) -> Result<TableExport<'_>, Error> {
+        TableExport::new(self, tables)
+    }
+
+    /// Write the code for the field type to `file_name` in the `OUT_DIR` of a build script
+    ///
+    /// See [`write_to`](Field::write_to) for details.
+    pub fn write_to_out_dir(&self, file_name: &str) -> io::Result<PathBuf> {
+        let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "OUT_DIR is not set, are you running from a build script?",
+            )
+        })?;
+        self.write_to(out_dir, file_name)
+    }
+
+    fn repr_bytes(&self) -> usize {
+        match self.p {
+            1..=8 => 1,
+            9..=16 => 2,
+            _ => 4,
+        }
+    }
+}
+
+fn check_degree(p: u64) -> Result<(), Error> {
+    match p {
+        1..=32 => Ok(()),
+        _ => Err(Error::UnsupportedDegree(p)),
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => (),
+        _ => return false,
+    }
+    name != "_" && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn path_str(path: &Path) -> io::Result<String> {
+    path.to_str().map(|s| s.to_string()).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Path {} is not valid UTF-8", path.display()),
+        )
+    })
+}
+
+fn find_modulus_poly(p: u64) -> G2Poly {
+    assert!(p < 64);
+
+    let start = (1 << p) + 1;
+    let end = (1_u64 << (p + 1)).wrapping_sub(1);
+
+    for m in start..=end {
+        let p = G2Poly(m);
+        if p.is_irreducible() {
+            return p;
+        }
+    }
+
+    unreachable!("There are irreducible polynomial for any degree!")
+}
+
+fn find_generator(m: G2Poly) -> G2Poly {
+    let max = m.degree().expect("Modulus must have positive degree");
+
+    for g in 1..(2 << max) {
+        let g = G2Poly(g);
+        if g.is_generator(m) {
+            return g;
+        }
+    }
+
+    unreachable!("There must be a generator element")
+}
+
+/// Where the generated code finds its tables
+enum Tables {
+    /// Tables are static arrays in the generated code
+    Inline,
+    /// Tables are loaded from files with `include_bytes!`, entries are `width` bytes little endian
+    Files {
+        mul: String,
+        inv: String,
+        width: usize,
+    },
+    /// Tables are computed at runtime by `g2p::lazy::LazyTables`
+    Lazy,
+}
+
+fn generate_field(field: &Field, tables: &Tables) -> TokenStream {
+    let ident = Ident::new(&field.name, Span::call_site());
+    let ident_name = &field.name;
+    let modulus = field.modulus;
+    let generator = field.generator;
+    let p = field.p;
+    let field_size = 1_usize << p;
+    let mask = (1_u64 << p).wrapping_sub(1);
+
+    let ty = match p {
+        1..=8 => quote!(u8),
+        9..=16 => quote!(u16),
+        _ => quote!(u32),
+    };
+
+    let mod_name = Ident::new(&format!("{}_mod", ident_name), Span::call_site());
+
+    let struct_def = quote![
+        #[derive(Clone, Copy, Eq, PartialEq, Hash)]
+        #[repr(transparent)]
+        pub struct #ident(pub #ty);
+    ];
+
+    let struct_impl = quote![
+        impl #ident {
+            pub const MASK: #ty = #mask as #ty;
+
+            /// Precompute the multiplication with `c`
+            pub fn multiplier(c: Self) -> ::g2p::multiplier::Multiplier<Self> {
+                ::g2p::multiplier::Multiplier::new(c)
+            }
+        }
+    ];
+
+    let from = quote![
+        impl ::core::convert::From<#ident> for #ty {
+            fn from(v: #ident) -> #ty {
+                v.0
+            }
+        }
+    ];
+
+    let into = quote![
+        impl ::core::convert::From<#ty> for #ident {
+            fn from(v: #ty) -> #ident {
+                #ident(v & #ident::MASK)
+            }
+        }
+    ];
+
+    let tmpl = format!("{{}}_{}", ident_name);
+    let debug = quote![
+        impl ::core::fmt::Debug for #ident {
+            fn fmt<'a>(&self, f: &mut ::core::fmt::Formatter<'a>) -> ::core::fmt::Result {
+                write!(f, #tmpl, self.0)
+            }
+        }
+    ];
+    let display = quote![
+        impl ::core::fmt::Display for #ident {
+            fn fmt<'a>(&self, f: &mut ::core::fmt::Formatter<'a>) -> ::core::fmt::Result {
+                write!(f, #tmpl, self.0)
+            }
+        }
+    ];
+    let add = quote![
+        impl ::core::ops::Add for #ident {
+            type Output = Self;
+
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 ^ rhs.0)
+            }
+        }
+        impl ::core::ops::AddAssign for #ident {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+    ];
+    let sum = quote![
+        impl ::core::iter::Sum for #ident {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(<Self as ::g2p::GaloisField>::ZERO, ::core::ops::Add::add)
+            }
+        }
+    ];
+    let sub = quote![
+        impl ::core::ops::Sub for #ident {
+            type Output = Self;
+
+
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 ^ rhs.0)
+            }
+        }
+        impl ::core::ops::SubAssign for #ident {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+        impl ::core::ops::Neg for #ident {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                self
+            }
+        }
+    ];
+    let gen = generator.0;
+    let modulus_val = modulus.0;
+    // Fields with byte sized elements use the vectorized slice operations. The constant time
+    // variant keeps the generic implementation, the portable fallback uses table lookups.
+    let (byte_element, slice_ops) = if p <= 8 && !field.constant_time {
+        let byte_element = quote![
+            // SAFETY: The type is a transparent wrapper around u8
+            unsafe impl ::g2p::simd::ByteElement for #ident {}
+        ];
+        let slice_ops = quote![
+            fn mul_slice(dst: &mut [Self], src: &[Self], c: Self) {
+                let tables = ::g2p::simd::NibbleTables::from_fn(|x| (c * #ident(x & #mask as u8)).0);
+                ::g2p::simd::mul_slice(&tables, dst, src)
+            }
+
+            fn mul_add_slice(dst: &mut [Self], src: &[Self], c: Self) {
+                let tables = ::g2p::simd::NibbleTables::from_fn(|x| (c * #ident(x & #mask as u8)).0);
+                ::g2p::simd::mul_add_slice(&tables, dst, src)
+            }
+        ];
+        (byte_element, slice_ops)
+    } else {
+        (quote![], quote![])
+    };
+    let (tables, mul, div, inverse) = if field.constant_time {
+        generate_constant_time_impl(field, ident.clone(), ty.clone())
+    } else {
+        generate_mul_impl(field, ident.clone(), ty.clone(), tables)
+    };
+    let multiplier_tables = if field.constant_time {
+        // Table lookups depending on the constant would leak it, so just store the constant.
+        quote![
+            impl ::g2p::multiplier::MultiplierTables for #ident {
+                type Tables = Self;
+
+                fn mul_tables(c: Self) -> Self {
+                    c
+                }
+
+                fn mul_with(c: &Self, x: Self) -> Self {
+                    *c * x
+                }
+            }
+        ]
+    } else if p <= 8 {
+        quote![
+            impl ::g2p::multiplier::MultiplierTables for #ident {
+                type Tables = ::g2p::multiplier::ByteTables;
+
+                fn mul_tables(c: Self) -> Self::Tables {
+                    ::g2p::multiplier::ByteTables::from_fn(|x| (c * #ident(x & #mask as u8)).0)
+                }
+
+                fn mul_with(tables: &Self::Tables, x: Self) -> Self {
+                    Self(tables.row[x.0 as usize])
+                }
+
+                fn mul_slice_with(tables: &Self::Tables, dst: &mut [Self], src: &[Self]) {
+                    ::g2p::simd::mul_slice(&tables.nibbles, dst, src)
+                }
+
+                fn mul_add_slice_with(tables: &Self::Tables, dst: &mut [Self], src: &[Self]) {
+                    ::g2p::simd::mul_add_slice(&tables.nibbles, dst, src)
+                }
+            }
+        ]
+    } else {
+        let nparts = ((p + 7) / 8) as usize;
+        quote![
+            impl ::g2p::multiplier::MultiplierTables for #ident {
+                type Tables = ::g2p::multiplier::WideTables<#ty, #nparts>;
+
+                fn mul_tables(c: Self) -> Self::Tables {
+                    ::g2p::multiplier::WideTables::from_fn(|v| (c * #ident(v as #ty)).0)
+                }
+
+                fn mul_with(tables: &Self::Tables, x: Self) -> Self {
+                    Self(tables.mul((x.0 & #mask as #ty) as usize) as #ty)
+                }
+            }
+        ]
+    };
+    let p_u32 = p as u32;
+    // Square roots and the trace are linear maps, so they are evaluated bit by bit. The trace is
+    // the parity of the bits selected by a mask, which is branch free in both variants. The
+    // constant time variant calculates square roots with a fixed chain of squarings instead of
+    // the lookup table. With the lookup table, a square root costs less than a multiplication,
+    // so the Frobenius map goes backwards if that is shorter.
+    let trace_mask = Literal::u32_unsuffixed(tables::trace_mask(modulus));
+    let trace = quote![
+        fn trace(self) -> ::g2p::GF2 {
+            ::g2p::GF2(((self.0 & #trace_mask).count_ones() & 1) as u8)
+        }
+    ];
+    let (linear_maps, linear_ops) = if field.constant_time {
+        let linear_ops = quote![
+            // The loop is empty for p = 1
+            #[allow(clippy::reversed_empty_ranges)]
+            fn sqrt(self) -> Self {
+                let mut res = self;
+                for _ in 1..#p_u32 {
+                    res = res * res;
+                }
+                res
+            }
+
+            #trace
+        ];
+        (quote![], linear_ops)
+    } else {
+        let sqrt_map = tables::sqrt_map(modulus)
+            .into_iter()
+            .map(Literal::u32_unsuffixed);
+        let p_usize = p as usize;
+        let linear_maps = quote![
+            const SQRT_MAP: [#ty; #p_usize] = [#(#sqrt_map),*];
+        ];
+        // Fields with p <= 2 never need more than one squaring
+        let frobenius = if p > 2 {
+            quote![
+                fn frobenius(self, k: u32) -> Self {
+                    let k = k % #p_u32;
+                    let mut res = self;
+                    if k <= #p_u32 / 2 {
+                        for _ in 0..k {
+                            res = res * res;
+                        }
+                    } else {
+                        for _ in k..#p_u32 {
+                            res = res.sqrt();
+                        }
+                    }
+                    res
+                }
+            ]
+        } else {
+            quote![]
+        };
+        let linear_ops = quote![
+            fn sqrt(self) -> Self {
+                let mut res = 0;
+                for (i, &root) in SQRT_MAP.iter().enumerate() {
+                    res ^= root & ((self.0 >> i) & 1).wrapping_neg();
+                }
+                Self(res)
+            }
+
+            #frobenius
+
+            #trace
+        ];
+        (linear_maps, linear_ops)
+    };
+    let galois_trait_impl = quote![
+        impl ::g2p::GaloisField for #ident {
+            const SIZE: usize = #field_size;
+            const MODULUS: ::g2p::G2Poly = ::g2p::G2Poly(#modulus_val);
+            const ZERO: Self = Self(0);
+            const ONE: Self = Self(1);
+            const GENERATOR: Self = Self(#gen as #ty);
+
+            type Repr = #ty;
+            const DEGREE: u32 = #p_u32;
+
+            fn to_repr(self) -> #ty {
+                self.0 & #mask as #ty
+            }
+
+            fn from_repr(repr: #ty) -> Option<Self> {
+                if repr & !(#mask as #ty) == 0 {
+                    Some(Self(repr))
+                } else {
+                    None
+                }
+            }
+
+            fn is_zero(self) -> bool {
+                (self.0 & #mask as #ty) == 0
+            }
+
+            #inverse
+
+            #linear_ops
+
+            #slice_ops
+        }
+    ];
+
+    let product = quote![
+        impl ::core::iter::Product for #ident {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(<Self as ::g2p::GaloisField>::ONE, ::core::ops::Mul::mul)
+            }
+        }
+    ];
+
+    quote![
+        #struct_def
+
+        #[allow(non_snake_case)]
+        mod #mod_name {
+            use super::#ident;
+            #struct_impl
+            #tables
+            #linear_maps
+            #from
+            #into
+            #debug
+            #display
+            #add
+            #sum
+            #sub
+            #mul
+            #product
+            #div
+            #galois_trait_impl
+            #byte_element
+            #multiplier_tables
+            ::g2p::__impl_rand!(#ident, #ty);
+        }
+    ]
+}
+
+fn generate_mul_impl(
+    field: &Field,
+    ident: Ident,
+    ty: TokenStream,
+    tables: &Tables,
+) -> (TokenStream, TokenStream, TokenStream, TokenStream) {
+    let ident_name = &field.name;
+    let modulus = field.modulus;
+    let generator = field.generator;
+    let field_size = 1_usize << field.p;
+    let mask = (1_u64 << field.p).wrapping_sub(1);
+    let nparts = ceil_log256(field_size);
+
+    let table_defs = match tables {
+        Tables::Inline => {
+            let mul_table = tables::generate_mul_table_string(modulus);
+            let inv_table = tables::generate_inv_table_string(modulus);
+
+            // Faster generation than using quote
+            let mul_table_string: TokenStream = mul_table.parse().unwrap();
+            let inv_table_string: TokenStream = inv_table.parse().unwrap();
+
+            // NB: We generate static arrays, as they are guaranteed to have a fixed location in
+            //     memory. Using const would mean the compiler is free to create copies on the
+            //     stack etc. Since The arrays are quite large, this could lead to stack overflows.
+            quote! {
+                pub static MUL_TABLE: [[[[#ty; 256]; 256]; #nparts]; #nparts] = #mul_table_string;
+                pub static INV_TABLE: [#ty; #field_size] = #inv_table_string;
+            }
+        }
+        Tables::Files { mul, inv, width } => {
+            let mul_len = nparts * nparts * 256 * 256 * width;
+            let inv_len = field_size * width;
+            quote! {
+                pub static MUL_TABLE: &[u8; #mul_len] = include_bytes!(#mul);
+                pub static INV_TABLE: &[u8; #inv_len] = include_bytes!(#inv);
+
+                #[inline(always)]
+                #[allow(clippy::identity_op)]
+                fn mul_entry(idx: usize) -> #ty {
+                    let mut bytes = [0; #width];
+                    bytes.copy_from_slice(&MUL_TABLE[idx * #width..(idx + 1) * #width]);
+                    <#ty>::from_le_bytes(bytes)
+                }
+
+                #[inline(always)]
+                #[allow(clippy::identity_op)]
+                fn inv_entry(idx: usize) -> #ty {
+                    let mut bytes = [0; #width];
+                    bytes.copy_from_slice(&INV_TABLE[idx * #width..(idx + 1) * #width]);
+                    <#ty>::from_le_bytes(bytes)
+                }
+            }
+        }
+        Tables::Lazy => {
+            let modulus_val = modulus.0;
+            let gen = generator.0;
+            let table_len = nparts * nparts * 256 * 256 + field_size;
+            quote! {
+                pub static TABLES: ::g2p::lazy::LazyTables<#ty> = ::g2p::lazy::LazyTables::new(
+                    ::g2p::G2Poly(#modulus_val),
+                    ::g2p::G2Poly(#gen),
+                );
+
+                impl #ident {
+                    pub const TABLE_LEN: usize = #table_len;
+
+                    pub fn init_tables(
+                        buffer: &'static mut [#ty],
+                    ) -> ::core::result::Result<(), ::g2p::lazy::InitError> {
+                        TABLES.init_with(buffer)
+                    }
+                }
+            }
+        }
+    };
+
+    let load_tables = match tables {
+        Tables::Lazy => quote![let tables = TABLES.get();],
+        Tables::Inline | Tables::Files { .. } => quote![],
+    };
+
+    let mut mul_ops = Vec::with_capacity(nparts * nparts);
+    for left in 0..nparts {
+        for right in 0..nparts {
+            let l = quote![(((self.0 & #mask as #ty) >> (8*#left)) & 255) as usize];
+            let r = quote![(((rhs.0 & #mask as #ty) >> (8*#right)) & 255) as usize];
+            let entry = match tables {
+                Tables::Inline => quote![MUL_TABLE[#left][#right][#l][#r]],
+                Tables::Files { .. } => {
+                    let base = (left * nparts + right) * 256 * 256;
+                    quote![mul_entry(#base + #l * 256 + #r)]
+                }
+                Tables::Lazy => {
+                    let base = (left * nparts + right) * 256 * 256;
+                    quote![tables[#base + #l * 256 + #r]]
+                }
+            };
+            mul_ops.push(quote![#ident(#entry)]);
+        }
+    }
+
+    let mul = quote![
+        impl ::core::ops::Mul for #ident {
+            type Output = Self;
+            // The first byte of each operand is shifted by zero
+            #[allow(clippy::identity_op, clippy::erasing_op)]
+            fn mul(self, rhs: Self) -> Self {
+                #load_tables
+                #(#mul_ops)+*
+            }
+        }
+        impl ::core::ops::MulAssign for #ident {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+    ];
+
+    let err_msg = format!("Division by 0 in {}", ident_name);
+    let inv = match tables {
+        Tables::Inline => quote![INV_TABLE[(self.0 & #mask as #ty) as usize]],
+        Tables::Files { .. } => quote![inv_entry((self.0 & #mask as #ty) as usize)],
+        Tables::Lazy => {
+            let inv_offset = nparts * nparts * 256 * 256;
+            quote![TABLES.get()[#inv_offset + (self.0 & #mask as #ty) as usize]]
+        }
+    };
+    let inverse = quote![
+        fn inverse(self) -> Self {
+            Self(#inv)
+        }
+    ];
+
+    let div = quote![
+        impl ::core::ops::Div for #ident {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self {
+                if (rhs.0 & #mask as #ty) == 0 {
+                    panic!(#err_msg);
+                }
+                self * ::g2p::GaloisField::inverse(rhs)
+            }
+        }
+        impl ::core::ops::DivAssign for #ident {
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+    ];
+
+    (table_defs, mul, div, inverse)
+}
+
+fn generate_constant_time_impl(
+    field: &Field,
+    ident: Ident,
+    ty: TokenStream,
+) -> (TokenStream, TokenStream, TokenStream, TokenStream) {
+    let p = field.p as usize;
+    let top = p - 1;
+    let mask = (1_u64 << p).wrapping_sub(1);
+    // x^p is reduced to the lower terms of the modulus
+    let reduction = field.modulus.0 & mask;
+
+    // The inverse is calculated using Fermat's little theorem as x^(2^p - 2), by repeatedly
+    // squaring and multiplying with x. This maps 0 to 0.
+    //
+    // NB: All selections go through `subtle`, which prevents the compiler from turning the masks
+    //     back into branches on secret data.
+    let ct_traits = quote! {
+        impl ::g2p::subtle::ConstantTimeEq for #ident {
+            fn ct_eq(&self, other: &Self) -> ::g2p::subtle::Choice {
+                ::g2p::subtle::ConstantTimeEq::ct_eq(&(self.0 & #ident::MASK), &(other.0 & #ident::MASK))
+            }
+        }
+        impl ::g2p::subtle::ConditionallySelectable for #ident {
+            fn conditional_select(a: &Self, b: &Self, choice: ::g2p::subtle::Choice) -> Self {
+                Self(<#ty as ::g2p::subtle::ConditionallySelectable>::conditional_select(&a.0, &b.0, choice))
+            }
+        }
+
+        impl #ident {
+            // The loop is empty for p <= 2
+            #[allow(clippy::reversed_empty_ranges)]
+            fn ct_inv(self) -> Self {
+                let mut res = self;
+                for _ in 1..#top {
+                    res = res * res * self;
+                }
+                res * res
+            }
+        }
+    };
+
+    let mul = quote![
+        impl ::core::ops::Mul for #ident {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                use ::g2p::subtle::{Choice, ConditionallySelectable};
+
+                let mut a = self.0 & #ident::MASK;
+                let b = rhs.0 & #ident::MASK;
+                let mut res: #ty = 0;
+                for i in 0..#p {
+                    let bit = Choice::from(((b >> i) & 1) as u8);
+                    res ^= <#ty>::conditional_select(&0, &a, bit);
+
+                    let carry = Choice::from(((a >> #top) & 1) as u8);
+                    a = ((a << 1) & #ident::MASK) ^ <#ty>::conditional_select(&0, &(#reduction as #ty), carry);
+                }
+                Self(res)
+            }
+        }
+        impl ::core::ops::MulAssign for #ident {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+    ];
+
+    let err_msg = format!("Division by 0 in {}", field.name);
+    let div = quote![
+        impl ::core::ops::Div for #ident {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self {
+                if (rhs.0 & #mask as #ty) == 0 {
+                    panic!(#err_msg);
+                }
+                self * rhs.ct_inv()
+            }
+        }
+        impl ::core::ops::DivAssign for #ident {
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+    ];
+
+    let inverse = quote![
+        fn inverse(self) -> Self {
+            self.ct_inv()
+        }
+    ];
+
+    (ct_traits, mul, div, inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_settings() {
+        let field = Field::new("GF8", 3).unwrap();
+        assert_eq!(field.name(), "GF8");
+        assert_eq!(field.p(), 3);
+        assert_eq!(field.modulus(), G2Poly(0b1011));
+        assert_eq!(field.generator(), G2Poly(0b10));
+    }
+
+    #[test]
+    fn test_field_errors() {
+        assert_eq!(
+            Field::new("1GF", 3),
+            Err(Error::InvalidName("1GF".to_string()))
+        );
+        assert_eq!(Field::new("GF", 0), Err(Error::UnsupportedDegree(0)));
+        assert_eq!(Field::new("GF", 33), Err(Error::UnsupportedDegree(33)));
+        assert_eq!(
+            Field::with_modulus("GF", 4, G2Poly(0b1011)),
+            Err(Error::ModulusDegree {
+                p: 4,
+                modulus: G2Poly(0b1011)
+            })
+        );
+        assert_eq!(
+            Field::with_modulus("GF", 2, G2Poly(0b101)),
+            Err(Error::ReducibleModulus(G2Poly(0b101)))
+        );
+    }
+
+    #[test]
+    fn test_write_binary_tables() {
+        let dir = env::temp_dir().join(format!("g2build-test-{}", std::process::id()));
+        let field = Field::with_modulus("GF256", 8, G2Poly(0b1_0001_1011))
+            .unwrap()
+            .table_storage(TableStorage::Binary);
+
+        let path = field.write_to(&dir, "gf256.rs").unwrap();
+        let source = fs::read_to_string(&path).unwrap();
+        let mul = fs::read(dir.join("gf256_mul.bin")).unwrap();
+        let inv = fs::read(dir.join("gf256_inv.bin")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(source.contains("include_bytes"));
+        assert_eq!(mul.len(), 256 * 256);
+        assert_eq!(mul[0x53 * 256 + 0xca], 1);
+        assert_eq!(inv.len(), 256);
+        assert_eq!(inv[0x53], 0xca);
+    }
+}
@@ -0,0 +1,346 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generation of the precomputed arithmetic tables
+
+use g2poly::{extended_gcd, G2Poly};
+
+/// Calculate the log base 256, rounded up
+///
+/// Given a number n, calculate the log base 256, rounded up. This can be though of as the number
+/// of bytes needed to represent this number.
+pub(crate) fn ceil_log256(mut n: usize) -> usize {
+    if n == 0 {
+        return 0;
+    }
+
+    let mut c = 1;
+    while n > 256 {
+        c += 1;
+        // NB: This is the rounding up part. If n is a proper power of 256, adding 255 will not
+        // change the result. In the other cases, this ensures that we round up in the division.
+        n = (n + 255) >> 8;
+    }
+    c
+}
+
+/// Generate the multiplication table
+///
+/// The table uses the associativity of multiplication `(a + b) * (c + d) == a*c + a*d + b*c + b*d`
+/// to reduce table size.
+///
+/// The input is split into bit chunks e.g. for a GF_1024 number we take the lower 8 bit and the
+/// remaining 2 and calculate the multiplications for each separately. Then we can cheaply add them
+/// together to get the the result with requiring a full 1024 * 1024 input.
+///
+/// The result is the flattened 4d array `[left][right][i][j]`, where `i` is the `left`-th byte of
+/// the first operand and `j` the `right`-th byte of the second operand.
+pub(crate) fn mul_table(modulus: G2Poly) -> Vec<u32> {
+    assert!(modulus.is_irreducible());
+
+    let field_size = 1
+        << modulus
+            .degree()
+            .expect("Irreducible polynomial has positive degree");
+    let nparts = ceil_log256(field_size as usize);
+
+    let mut mul_table = Vec::with_capacity(nparts * nparts * 256 * 256);
+    for left in 0..nparts {
+        for right in 0..nparts {
+            for i in 0..256 {
+                let i = i << (8 * left);
+                for j in 0..256 {
+                    let j = j << (8 * right);
+                    let v = if i < field_size && j < field_size {
+                        G2Poly(i) * G2Poly(j) % modulus
+                    } else {
+                        G2Poly(0)
+                    };
+
+                    mul_table.push(v.0 as u32);
+                }
+            }
+        }
+    }
+    mul_table
+}
+
+/// Generate the inversion table
+///
+/// The entry at index `i` is the multiplicative inverse of `i`. The inverse of 0 is set to 0.
+pub(crate) fn inv_table(modulus: G2Poly) -> Vec<u32> {
+    assert!(modulus.is_irreducible());
+
+    let field_size = 1
+        << modulus
+            .degree()
+            .expect("Irreducible polynomial has positive degree");
+    let mut inv_table = vec![0; field_size as usize];
+    // Inverse table is small enough to compute directly
+    for i in 1..field_size {
+        if inv_table[i as usize] != 0 {
+            // Already computed inverse
+            continue;
+        }
+
+        let a = G2Poly(i);
+
+        // Returns (gcd, x, y) such that gcd(a, m) == a * x + y * m
+        // Since we know that gcd(a, m) == 1 and that we operate modulo m, y * m === 0 mod m
+        // So we have 1 === a * x mod m
+
+        let (_gcd, x, _y) = extended_gcd(a, modulus);
+        inv_table[i as usize] = x.0 as u32;
+        inv_table[x.0 as usize] = i as u32;
+    }
+    inv_table
+}
+
+/// Generate the exponential table
+///
+/// The entry at index `i` is `generator^i`, for `0 <= i < 2^p - 1`.
+pub(crate) fn exp_table(modulus: G2Poly, generator: G2Poly) -> Vec<u32> {
+    let field_size = 1_u64
+        << modulus
+            .degree()
+            .expect("Irreducible polynomial has positive degree");
+    let mut exp_table = Vec::with_capacity(field_size as usize - 1);
+    let mut x = G2Poly(1);
+    for _ in 0..field_size - 1 {
+        exp_table.push(x.0 as u32);
+        x = x * generator % modulus;
+    }
+    exp_table
+}
+
+/// Generate the logarithm table
+///
+/// The entry at index `i` is the discrete logarithm of `i` to the base `generator`. The logarithm
+/// of 0 is set to 0.
+pub(crate) fn log_table(modulus: G2Poly, generator: G2Poly) -> Vec<u32> {
+    let exp_table = exp_table(modulus, generator);
+    let mut log_table = vec![0; exp_table.len() + 1];
+    for (i, &x) in exp_table.iter().enumerate() {
+        log_table[x as usize] = i as u32;
+    }
+    log_table
+}
+
+/// Generate the nibble tables used by `g2p::simd`
+///
+/// The result is the flattened 3d array `[c][hi][n]`, where the entry is the product of `c` and
+/// `n` for `hi == 0`, and the product of `c` and `n << 4` for `hi == 1`. Bits outside the field
+/// are ignored, like in the generated code. Only fields with p <= 8 have nibble tables.
+pub(crate) fn nibble_table(modulus: G2Poly) -> Vec<u32> {
+    let p = modulus
+        .degree()
+        .expect("Irreducible polynomial has positive degree");
+    assert!(p <= 8);
+    let mask = (1 << p) - 1;
+
+    let mut nibble_table = Vec::with_capacity((1 << p) * 2 * 16);
+    for c in 0..1 << p {
+        for shift in [0, 4] {
+            for n in 0..16_u64 {
+                let v = G2Poly(c) * G2Poly((n << shift) & mask) % modulus;
+                nibble_table.push(v.0 as u32);
+            }
+        }
+    }
+    nibble_table
+}
+
+/// Generate the square root map
+///
+/// Taking the square root is linear, so it is described by the roots of the powers `x^i`. Entry
+/// `i` is the root of `x^i`, calculated as `x^(i * 2^(p - 1))`.
+pub(crate) fn sqrt_map(modulus: G2Poly) -> Vec<u32> {
+    let p = modulus
+        .degree()
+        .expect("Irreducible polynomial has positive degree");
+    (0..p)
+        .map(|i| G2Poly(1 << i).pow_mod(1 << (p - 1), modulus).0 as u32)
+        .collect()
+}
+
+/// Generate the trace mask
+///
+/// The trace is linear, so it is described by the traces of the powers `x^i`. Bit `i` of the
+/// result is the trace of `x^i`, so the trace of a value is the parity of the value masked with
+/// the result.
+pub(crate) fn trace_mask(modulus: G2Poly) -> u32 {
+    let p = modulus
+        .degree()
+        .expect("Irreducible polynomial has positive degree");
+    let mut mask = 0;
+    for i in 0..p {
+        // Tr(y) = y + y^2 + y^4 + ... + y^(2^(p - 1))
+        let mut y = G2Poly(1 << i) % modulus;
+        let mut trace = G2Poly(0);
+        for _ in 0..p {
+            trace = trace + y;
+            y = y * y % modulus;
+        }
+        debug_assert!(trace.0 <= 1, "Trace is an element of GF(2)");
+        mask |= (trace.0 as u32) << i;
+    }
+    mask
+}
+
+/// Generate multiplication array
+///
+/// Generate a string representing the 4d multiplication array from [`mul_table`].
+pub(crate) fn generate_mul_table_string(modulus: G2Poly) -> String {
+    let table = mul_table(modulus);
+
+    let parts = table
+        .chunks(256 * 256)
+        .map(|part| {
+            let rows = part
+                .chunks(256)
+                .map(|row| {
+                    let row = row.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+                    format!("[{}]", row.join(","))
+                })
+                .collect::<Vec<_>>();
+            format!("[{}]", rows.join(","))
+        })
+        .collect::<Vec<_>>();
+
+    let field_size = 1
+        << modulus
+            .degree()
+            .expect("Irreducible polynomial has positive degree");
+    let nparts = ceil_log256(field_size);
+    let left_parts = parts
+        .chunks(nparts)
+        .map(|right_parts| format!("[{}]", right_parts.join(",")))
+        .collect::<Vec<_>>();
+
+    format!("[{}]", left_parts.join(","))
+}
+
+pub(crate) fn generate_inv_table_string(modulus: G2Poly) -> String {
+    let inv_table = inv_table(modulus);
+
+    use std::fmt::Write;
+    let mut res = String::with_capacity(3 * inv_table.len());
+    write!(&mut res, "[").unwrap();
+    for v in inv_table {
+        write!(&mut res, "{},", v).unwrap();
+    }
+    write!(&mut res, "]").unwrap();
+    res
+}
+
+/// Serialize a table as little endian values of `width` bytes each
+pub(crate) fn table_bytes(table: &[u32], width: usize) -> Vec<u8> {
+    let mut res = Vec::with_capacity(table.len() * width);
+    for v in table {
+        res.extend_from_slice(&v.to_le_bytes()[..width]);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_mul_table() {
+        let m = G2Poly(0b111);
+
+        assert_eq!(
+            include_str!("../tests/mul_table.txt").trim(),
+            generate_mul_table_string(m)
+        );
+    }
+
+    #[test]
+    fn test_generate_inv_table_string() {
+        let m = G2Poly(0b1_0001_1011);
+
+        assert_eq!(
+            include_str!("../tests/inv_table.txt").trim(),
+            generate_inv_table_string(m)
+        );
+    }
+
+    #[test]
+    fn test_ceil_log256() {
+        assert_eq!(0, ceil_log256(0));
+        assert_eq!(1, ceil_log256(1));
+        assert_eq!(1, ceil_log256(256));
+        assert_eq!(2, ceil_log256(257));
+        assert_eq!(2, ceil_log256(65536));
+        assert_eq!(3, ceil_log256(65537));
+        assert_eq!(3, ceil_log256(131072));
+        assert_eq!(3, ceil_log256(16777216));
+        assert_eq!(4, ceil_log256(16777217));
+    }
+
+    #[test]
+    fn test_sqrt_map() {
+        // x^2 = x + 1 mod x^2 + x + 1, so x is the root of x + 1
+        assert_eq!(vec![1, 3], sqrt_map(G2Poly(0b111)));
+
+        let m = G2Poly(0b1_0001_1011);
+        for (i, &r) in sqrt_map(m).iter().enumerate() {
+            assert_eq!(G2Poly(1 << i), G2Poly(r as u64) * G2Poly(r as u64) % m);
+        }
+    }
+
+    #[test]
+    fn test_trace_mask() {
+        // Tr(1) = 0 for even degree, Tr(x) = 1 in GF(4)
+        assert_eq!(0b10, trace_mask(G2Poly(0b111)));
+        // Tr(1) = 1 for odd degree
+        assert_eq!(1, trace_mask(G2Poly(0b1011)) & 1);
+        // Rijndael field: only x^5 and x^7 have trace 1
+        assert_eq!(0b1010_0000, trace_mask(G2Poly(0b1_0001_1011)));
+    }
+
+    #[test]
+    fn test_log_exp_table() {
+        let m = G2Poly(0b1_0001_1011);
+        let exp = exp_table(m, G2Poly(3));
+        assert_eq!(255, exp.len());
+        assert_eq!([1, 3, 5, 15, 17, 51], exp[..6]);
+
+        let log = log_table(m, G2Poly(3));
+        assert_eq!(256, log.len());
+        assert_eq!(0, log[0]);
+        for (i, &x) in exp.iter().enumerate() {
+            assert_eq!(i as u32, log[x as usize]);
+        }
+    }
+
+    #[test]
+    fn test_nibble_table() {
+        let m = G2Poly(0b1_0001_1011);
+        let table = nibble_table(m);
+        assert_eq!(256 * 2 * 16, table.len());
+        let mul = mul_table(m);
+        for c in 0..256 {
+            for x in 0..256 {
+                let lo = table[c * 32 + (x & 0xf)];
+                let hi = table[c * 32 + 16 + (x >> 4)];
+                assert_eq!(mul[c * 256 + x], lo ^ hi);
+            }
+        }
+
+        // Bits above p are masked
+        let table = nibble_table(G2Poly(0b111));
+        assert_eq!([0, 1, 2, 3, 0, 1, 2, 3], table[32..40]);
+        assert!(table[48..64].iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    fn test_table_bytes() {
+        assert_eq!(vec![1, 2, 3], table_bytes(&[1, 2, 3], 1));
+        assert_eq!(vec![1, 0, 0x34, 0x12], table_bytes(&[1, 0x1234], 2));
+    }
+}
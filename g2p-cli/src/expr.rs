@@ -0,0 +1,209 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Evaluation of arithmetic expressions in GF(2^p)
+//!
+//! The grammar is
+//! ```text
+//! expr    = term (('+' | '-') term)*
+//! term    = factor (('*' | '/') factor)*
+//! factor  = '-' factor | atom ('^' ['-'] number)?
+//! atom    = number | 'x' | 'inv' '(' expr ')' | '(' expr ')'
+//! ```
+//! Numbers are the integer representation of an element, `x` is the element represented by 2.
+
+use g2poly::{extended_gcd, G2Poly};
+
+use crate::poly::parse_number;
+
+/// Evaluate `input` in the field GF(2)[x] / `modulus`
+pub fn eval(input: &str, modulus: G2Poly) -> Result<G2Poly, String> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        pos: 0,
+        modulus,
+    };
+    let value = parser.expr()?;
+    parser.skip_whitespace();
+    if parser.pos != parser.input.len() {
+        return Err(parser.error("Unexpected input"));
+    }
+    Ok(value)
+}
+
+/// Calculate the inverse modulo an irreducible `modulus`
+pub fn inverse(a: G2Poly, modulus: G2Poly) -> Option<G2Poly> {
+    if a == G2Poly::ZERO {
+        return None;
+    }
+    let (_gcd, s, _t) = extended_gcd(a, modulus);
+    Some(s % modulus)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    modulus: G2Poly,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, msg: &str) -> String {
+        format!("{} at position {}", msg, self.pos + 1)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .input
+            .get(self.pos)
+            .map_or(false, u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume `c` if it is the next non-whitespace character
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{}'", c as char)))
+        }
+    }
+
+    fn expr(&mut self) -> Result<G2Poly, String> {
+        let mut value = self.term()?;
+        while self.eat(b'+') || self.eat(b'-') {
+            value = value + self.term()?;
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<G2Poly, String> {
+        let mut value = self.factor()?;
+        loop {
+            if self.eat(b'*') {
+                value = (value * self.factor()?) % self.modulus;
+            } else if self.eat(b'/') {
+                let pos = self.pos;
+                let divisor = self.factor()?;
+                let inv = inverse(divisor, self.modulus).ok_or_else(|| {
+                    self.pos = pos;
+                    self.error("Division by zero")
+                })?;
+                value = (value * inv) % self.modulus;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<G2Poly, String> {
+        // Negation does not change the value
+        if self.eat(b'-') {
+            return self.factor();
+        }
+        let base = self.atom()?;
+        if !self.eat(b'^') {
+            return Ok(base);
+        }
+
+        let negative = self.eat(b'-');
+        self.skip_whitespace();
+        let exp = parse_number(&self.word())?;
+        let base = if negative {
+            inverse(base, self.modulus).ok_or_else(|| self.error("Zero has no inverse"))?
+        } else {
+            base
+        };
+        Ok(base.pow_mod(exp, self.modulus))
+    }
+
+    fn atom(&mut self) -> Result<G2Poly, String> {
+        if self.eat(b'(') {
+            let value = self.expr()?;
+            self.expect(b')')?;
+            return Ok(value);
+        }
+
+        self.skip_whitespace();
+        let start = self.pos;
+        let word = self.word();
+        match word.as_str() {
+            "" => Err(self.error("Expected a value")),
+            "x" => Ok(G2Poly::X % self.modulus),
+            "inv" => {
+                self.expect(b'(')?;
+                let value = self.expr()?;
+                self.expect(b')')?;
+                inverse(value, self.modulus).ok_or_else(|| self.error("Zero has no inverse"))
+            }
+            _ => {
+                let value = G2Poly(parse_number(&word)?);
+                if value.degree() >= self.modulus.degree() {
+                    self.pos = start;
+                    return Err(self.error(&format!("{} is not an element of the field", word)));
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    /// Read a run of alphanumeric characters and underscores
+    fn word(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .input
+            .get(self.pos)
+            .map_or(false, |&c| c.is_ascii_alphanumeric() || c == b'_')
+        {
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.input[start..self.pos]).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AES: G2Poly = G2Poly(0x11b);
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(eval("0x53 * 0xca", AES), Ok(G2Poly(1)));
+        assert_eq!(eval("0x53 + 0xca", AES), Ok(G2Poly(0x53 ^ 0xca)));
+        assert_eq!(eval("0x53 - 0xca", AES), eval("0x53 + 0xca", AES));
+        assert_eq!(eval("inv(0x53)", AES), Ok(G2Poly(0xca)));
+        assert_eq!(eval("1 / 0x53", AES), Ok(G2Poly(0xca)));
+        assert_eq!(eval("0x53^-1", AES), Ok(G2Poly(0xca)));
+        assert_eq!(eval("x^8", AES), Ok(G2Poly(0x1b)));
+        assert_eq!(eval("3^255", AES), Ok(G2Poly(1)));
+        assert_eq!(eval("-(2 + 3) * 4", AES), Ok(G2Poly(4)));
+        // Precedence: * before +, ^ before *
+        assert_eq!(eval("1 + 2 * 3", AES), Ok(G2Poly(1 ^ 6)));
+        assert_eq!(eval("2 * 2^2", AES), Ok(G2Poly(8)));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(eval("1 / 0", AES).unwrap_err().contains("Division by zero"));
+        assert!(eval("0x100", AES).unwrap_err().contains("not an element"));
+        assert!(eval("(1 + 2", AES).unwrap_err().contains("Expected ')'"));
+        assert!(eval("1 2", AES).unwrap_err().contains("Unexpected input"));
+        assert!(eval("", AES).unwrap_err().contains("Expected a value"));
+        assert!(eval("0^-1", AES).is_err());
+    }
+}
@@ -0,0 +1,498 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Command line tool to explore polynomials over GF(2) and the fields GF(2^p)
+
+use std::{
+    convert::TryInto,
+    env, fmt,
+    io::{self, Write},
+    process,
+};
+
+use g2build::{ElementType, ExportFormat, ExportTable, Field};
+use g2poly::G2Poly;
+
+mod expr;
+mod poly;
+mod tables;
+
+use poly::{format_poly, parse_number, parse_poly};
+
+const USAGE: &str = "\
+Usage: g2p <command> [arguments]
+
+Commands:
+  check <poly>                      Test if a polynomial is irreducible and primitive
+  factor <poly>                     Factor a polynomial into irreducible polynomials
+  list <degree> [--primitive] [--limit <n>]
+                                    List the irreducible polynomials of a degree
+  generator <modulus>               Find the smallest generator of GF(2)[x] / modulus
+  tables <modulus> [options] [mul|inv|log|exp|nibble]...
+                                    Print the arithmetic tables of GF(2)[x] / modulus
+      --format <text|c|rust|binary> Output format, c, rust and binary export the tables
+                                    used by g2p generated code
+      --type <u8|u16|u32>           Element type of exported tables
+      --name <name>                 Name of the field in exported tables, default GF<size>
+      --generator <g>               Base of log and exp tables in text output
+  eval <modulus> <expression>       Evaluate an expression in GF(2)[x] / modulus
+  help                              Show this message
+
+Polynomials are given as numbers (283, 0x11b, 0b100011011) or as terms (x^8 + x^4 + x^3 + x + 1).
+Expressions use the integer representation of elements, x, + - * / ^ and inv(...).";
+
+/// Errors reported to the user
+#[derive(Debug)]
+enum Error {
+    /// The arguments are invalid, print the usage
+    Usage(String),
+    /// The command failed
+    Failed(String),
+    /// Writing the output failed
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+            Error::Failed(msg) => write!(f, "{}", msg),
+            Error::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<String> for Error {
+    fn from(msg: String) -> Self {
+        Error::Failed(msg)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match run(&args, &mut out) {
+        Ok(()) => {}
+        // The reader went away, e.g. `g2p list 20 | head`
+        Err(Error::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(match err {
+                Error::Usage(_) => 2,
+                _ => 1,
+            });
+        }
+    }
+}
+
+fn run<W: Write>(args: &[String], out: &mut W) -> Result<(), Error> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Err(Error::Usage("Missing command".to_string())),
+    };
+    match (command, rest) {
+        ("check", [poly]) => check(out, parse_poly(poly)?),
+        ("factor", [poly]) => factor(out, parse_poly(poly)?),
+        ("list", [degree, options @ ..]) => list(out, degree, options),
+        ("generator", [modulus]) => generator(out, field_modulus(modulus, 63)?),
+        ("tables", [modulus, options @ ..]) => tables(out, modulus, options),
+        ("eval", [modulus, expression @ ..]) if !expression.is_empty() => {
+            let modulus = field_modulus(modulus, 63)?;
+            let value = expr::eval(&expression.join(" "), modulus)?;
+            writeln!(out, "{}", format_element(value, modulus))?;
+            Ok(())
+        }
+        ("help", []) | ("--help", []) | ("-h", []) => {
+            writeln!(out, "{}", USAGE)?;
+            Ok(())
+        }
+        ("check", _)
+        | ("factor", _)
+        | ("list", _)
+        | ("generator", _)
+        | ("tables", _)
+        | ("eval", _) => Err(Error::Usage(format!(
+            "Wrong number of arguments for '{}'",
+            command
+        ))),
+        _ => Err(Error::Usage(format!("Unknown command '{}'", command))),
+    }
+}
+
+/// Parse a modulus, which must be irreducible with degree between 1 and `max_degree`
+fn field_modulus(s: &str, max_degree: u64) -> Result<G2Poly, Error> {
+    let modulus = parse_poly(s)?;
+    if !modulus.is_irreducible() || modulus.degree() == Some(0) {
+        return Err(Error::Failed(format!(
+            "{} is not irreducible",
+            format_poly(modulus)
+        )));
+    }
+    match modulus.degree() {
+        Some(p) if p <= max_degree => Ok(modulus),
+        _ => Err(Error::Failed(format!(
+            "Degree of the modulus must be at most {}",
+            max_degree
+        ))),
+    }
+}
+
+/// Format an element as `0x53 = x^6 + x^4 + x + 1`
+fn format_element(value: G2Poly, modulus: G2Poly) -> String {
+    let digits = ((modulus.degree().unwrap_or(0) + 3) / 4).max(1) as usize;
+    format!("{:#0w$x} = {}", value.0, format_poly(value), w = digits + 2)
+}
+
+fn check<W: Write>(out: &mut W, f: G2Poly) -> Result<(), Error> {
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    writeln!(out, "{} ({:#x})", format_poly(f), f.0)?;
+    writeln!(out, "degree: {}", f.degree().map_or(-1, |d| d as i64))?;
+    // Units are neither irreducible nor reducible
+    let irreducible = f.degree().map_or(false, |d| d > 0) && f.is_irreducible();
+    writeln!(out, "irreducible: {}", yes_no(irreducible))?;
+    writeln!(out, "primitive: {}", yes_no(poly::is_primitive(f)))?;
+    Ok(())
+}
+
+fn factor<W: Write>(out: &mut W, f: G2Poly) -> Result<(), Error> {
+    if f == G2Poly::ZERO {
+        return Err(Error::Failed("Can not factor 0".to_string()));
+    }
+    let factors = poly::factor(f);
+    if factors.is_empty() {
+        writeln!(out, "1")?;
+    }
+    for (q, k) in factors {
+        if k == 1 {
+            writeln!(out, "{} ({:#x})", format_poly(q), q.0)?;
+        } else {
+            writeln!(out, "({})^{} ({:#x})", format_poly(q), k, q.0)?;
+        }
+    }
+    Ok(())
+}
+
+fn list<W: Write>(out: &mut W, degree: &str, options: &[String]) -> Result<(), Error> {
+    let degree = parse_number(degree)?;
+    if degree == 0 || degree > 63 {
+        return Err(Error::Failed("Degree must be between 1 and 63".to_string()));
+    }
+
+    let mut primitive = false;
+    let mut limit = u64::MAX;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--primitive" => primitive = true,
+            "--limit" => {
+                let n = options
+                    .next()
+                    .ok_or_else(|| Error::Usage("Missing value for --limit".to_string()))?;
+                limit = parse_number(n)?;
+            }
+            _ => return Err(Error::Usage(format!("Unknown option '{}'", option))),
+        }
+    }
+
+    let factors = poly::group_order_factors(degree);
+    let first = 1_u64 << degree;
+    let polys = (first..=(first | (first - 1)))
+        .map(G2Poly)
+        .filter(|f| f.is_irreducible())
+        .filter(|&f| !primitive || poly::is_generator(G2Poly::X, f, &factors));
+    for f in polys.take(limit.try_into().unwrap_or(usize::MAX)) {
+        writeln!(out, "{:#x} {}", f.0, format_poly(f))?;
+    }
+    Ok(())
+}
+
+fn generator<W: Write>(out: &mut W, modulus: G2Poly) -> Result<(), Error> {
+    let g = poly::find_generator(modulus).expect("Multiplicative group is cyclic");
+    writeln!(out, "{}", format_element(g, modulus))?;
+    Ok(())
+}
+
+fn tables<W: Write>(out: &mut W, modulus: &str, options: &[String]) -> Result<(), Error> {
+    let modulus = field_modulus(modulus, tables::MAX_EXPORT_DEGREE)?;
+    let p = modulus.degree().expect("Modulus is irreducible");
+
+    let mut generator = None;
+    let mut format = None;
+    let mut element_type = None;
+    let mut name = format!("GF{}", 1_u64 << p);
+    let mut selected = Vec::new();
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let mut value = || {
+            options
+                .next()
+                .ok_or_else(|| Error::Usage(format!("Missing value for {}", option)))
+        };
+        match option.as_str() {
+            "--generator" => {
+                let g = parse_poly(value()?)?;
+                if !poly::is_generator(g, modulus, &poly::group_order_factors(p))
+                    || g.degree() >= modulus.degree()
+                {
+                    return Err(Error::Failed(format!(
+                        "{} is not a generator of the field",
+                        format_poly(g)
+                    )));
+                }
+                generator = Some(g);
+            }
+            "--format" => {
+                let f = value()?;
+                format = match f.as_str() {
+                    "text" => None,
+                    "c" => Some(ExportFormat::C),
+                    "rust" => Some(ExportFormat::Rust),
+                    "binary" => Some(ExportFormat::Binary),
+                    _ => return Err(Error::Usage(format!("Unknown format '{}'", f))),
+                };
+            }
+            "--type" => {
+                let t = value()?;
+                element_type = Some(match t.as_str() {
+                    "u8" => ElementType::U8,
+                    "u16" => ElementType::U16,
+                    "u32" => ElementType::U32,
+                    _ => return Err(Error::Usage(format!("Unknown type '{}'", t))),
+                });
+            }
+            "--name" => name = value()?.clone(),
+            _ => {
+                let table = tables::table_from_name(option)
+                    .ok_or_else(|| Error::Usage(format!("Unknown table '{}'", option)))?;
+                selected.push(table);
+            }
+        }
+    }
+
+    if let Some(format) = format {
+        if generator.is_some() {
+            return Err(Error::Usage(
+                "--generator is only supported for text output".to_string(),
+            ));
+        }
+        if selected.is_empty() {
+            selected = ExportTable::ALL
+                .iter()
+                .copied()
+                .filter(|t| t.is_supported(p))
+                .collect();
+        }
+        let field = Field::with_modulus(&name, p, modulus).map_err(|e| e.to_string())?;
+        let mut export = field
+            .export_tables(&selected)
+            .map_err(|e| e.to_string())?
+            .format(format);
+        if let Some(element_type) = element_type {
+            export = export
+                .element_type(element_type)
+                .map_err(|e| e.to_string())?;
+        }
+        export.write(out)?;
+        return Ok(());
+    }
+
+    if p > tables::MAX_DEGREE {
+        return Err(Error::Failed(format!(
+            "Tables are only printed for degree up to {}, use --format to export them",
+            tables::MAX_DEGREE
+        )));
+    }
+    if selected.is_empty() {
+        selected = vec![ExportTable::Inv, ExportTable::Log, ExportTable::Exp];
+        if p <= tables::MAX_MUL_DEGREE {
+            selected.insert(0, ExportTable::Mul);
+        }
+    }
+    let wide = |&&t: &&ExportTable| t == ExportTable::Mul || t == ExportTable::Nibble;
+    if let Some(table) = selected.iter().find(wide) {
+        if p > tables::MAX_MUL_DEGREE {
+            return Err(Error::Failed(format!(
+                "The {} table is only printed for degree up to {}",
+                table,
+                tables::MAX_MUL_DEGREE
+            )));
+        }
+    }
+
+    let generator = match generator {
+        Some(g) => g,
+        None => poly::find_generator(modulus).expect("Multiplicative group is cyclic"),
+    };
+    for (i, &table) in selected.iter().enumerate() {
+        if selected.len() > 1 {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            writeln!(out, "# {}", table.name())?;
+        }
+        tables::write_table(out, table, modulus, generator)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_str(args: &[&str]) -> Result<String, String> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        let mut out = Vec::new();
+        run(&args, &mut out).map_err(|e| e.to_string())?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(
+            run_str(&["check", "0x11d"]).unwrap(),
+            "x^8 + x^4 + x^3 + x^2 + 1 (0x11d)\ndegree: 8\nirreducible: yes\nprimitive: yes\n"
+        );
+        let out = run_str(&["check", "x^8 + x^4 + x^3 + x + 1"]).unwrap();
+        assert!(out.contains("irreducible: yes\nprimitive: no"));
+        assert_eq!(
+            run_str(&["check", "1"]).unwrap(),
+            "1 (0x1)\ndegree: 0\nirreducible: no\nprimitive: no\n"
+        );
+    }
+
+    #[test]
+    fn test_factor() {
+        assert_eq!(
+            run_str(&["factor", "x^5 + x^4 + x^3 + 1"]).unwrap(),
+            "(x + 1)^2 (0x3)\nx^3 + x^2 + 1 (0xd)\n"
+        );
+        assert_eq!(run_str(&["factor", "1"]).unwrap(), "1\n");
+        assert!(run_str(&["factor", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_list() {
+        assert_eq!(
+            run_str(&["list", "4"]).unwrap(),
+            "0x13 x^4 + x + 1\n0x19 x^4 + x^3 + 1\n0x1f x^4 + x^3 + x^2 + x + 1\n"
+        );
+        assert_eq!(
+            run_str(&["list", "4", "--primitive"])
+                .unwrap()
+                .lines()
+                .count(),
+            2
+        );
+        assert_eq!(run_str(&["list", "8"]).unwrap().lines().count(), 30);
+        assert_eq!(
+            run_str(&["list", "8", "--primitive", "--limit", "5"])
+                .unwrap()
+                .lines()
+                .count(),
+            5
+        );
+        assert!(run_str(&["list", "0"]).is_err());
+        assert!(run_str(&["list", "4", "--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_generator_and_eval() {
+        assert_eq!(run_str(&["generator", "0x11b"]).unwrap(), "0x03 = x + 1\n");
+        assert_eq!(
+            run_str(&["eval", "0x11b", "0x53", "*", "0xca"]).unwrap(),
+            "0x01 = 1\n"
+        );
+        assert_eq!(
+            run_str(&["eval", "x^4 + x + 1", "x^4"]).unwrap(),
+            "0x3 = x + 1\n"
+        );
+        assert!(run_str(&["eval", "0x11a", "1"])
+            .unwrap_err()
+            .contains("not irreducible"));
+        assert!(run_str(&["generator", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_tables() {
+        let out = run_str(&["tables", "0b111"]).unwrap();
+        assert!(out.starts_with("# mul\n*  0 1 2 3\n"));
+        assert!(out.contains("# inv\n0: 0 1 3 2\n"));
+        assert!(out.contains("# exp\n0: 1 2 3\n"));
+
+        assert_eq!(
+            run_str(&["tables", "0x11b", "--generator", "3", "exp"])
+                .unwrap()
+                .lines()
+                .next(),
+            Some("00: 01 03 05 0f 11 33 55 ff 1a 2e 72 96 a1 f8 13 35")
+        );
+        assert!(run_str(&["tables", "0x11b", "--generator", "2"]).is_err());
+        assert!(run_str(&["tables", "0x1002b", "mul"]).is_err());
+        assert!(run_str(&["tables", "0x1002b", "nibble"]).is_err());
+    }
+
+    #[test]
+    fn test_export_tables() {
+        let header = run_str(&["tables", "0x11b", "--format", "c", "--name", "aes"]).unwrap();
+        assert!(header.starts_with("/* aes: GF(2^8) with modulus 0x11b and generator 0x3"));
+        for table in ["MUL", "INV", "LOG", "EXP", "NIBBLE"] {
+            assert!(header.contains(&format!("static const uint8_t AES_{}_TABLE", table)));
+        }
+
+        let module = run_str(&[
+            "tables", "0x1002b", "--format", "rust", "--type", "u32", "inv", "mul",
+        ])
+        .unwrap();
+        assert!(module.contains("pub static INV_TABLE: [u32; 65536] = ["));
+        assert!(module.contains("pub static MUL_TABLE: [[[[u32; 256]; 256]; 2]; 2] = ["));
+
+        let mut out = Vec::new();
+        let args: Vec<String> = [
+            "tables", "0x11b", "--format", "binary", "--type", "u16", "inv",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        run(&args, &mut out).unwrap();
+        assert_eq!(out.len(), 512);
+        assert_eq!(out[2 * 0x53..2 * 0x53 + 2], [0xca, 0]);
+
+        // Larger fields are exported, but not printed
+        let mut out = Vec::new();
+        let args: Vec<String> = ["tables", "0x20009", "--format", "binary", "inv"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        run(&args, &mut out).unwrap();
+        assert_eq!(out.len(), 4 << 17);
+
+        let err = |args: &[&str]| run_str(args).unwrap_err();
+        assert!(err(&["tables", "0x1002b", "--format", "c", "--type", "u8"]).contains("U8"));
+        assert!(err(&["tables", "0x1002b", "--format", "c", "nibble"]).contains("nibble"));
+        assert!(err(&["tables", "7", "--format", "c", "--generator", "2"]).contains("text"));
+        assert!(err(&["tables", "7", "--format", "pdf"]).contains("Unknown format"));
+        assert!(err(&["tables", "7", "--format", "c", "--name", "1x"]).contains("identifier"));
+        assert!(err(&["tables", "7", "--type"]).contains("Missing value"));
+        assert!(err(&["tables", "0x20009", "inv"]).contains("--format"));
+        assert!(err(&["tables", "0x2000009", "--format", "c", "inv"]).contains("p = 25"));
+        assert!(err(&["tables", "0x200002001", "--format", "c"]).contains("at most 32"));
+    }
+
+    #[test]
+    fn test_usage() {
+        assert!(run_str(&[]).unwrap_err().contains("Usage"));
+        assert!(run_str(&["bogus"]).unwrap_err().contains("Unknown command"));
+        assert!(run_str(&["check"]).unwrap_err().contains("Wrong number"));
+        assert!(run_str(&["help"]).unwrap().starts_with("Usage"));
+    }
+}
@@ -0,0 +1,346 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parsing, factoring and primitivity of polynomials over GF(2)
+
+use g2poly::{gcd, G2Poly};
+
+/// Parse a number as decimal, `0x` hexadecimal or `0b` binary
+pub fn parse_number(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (digits, radix) = if let Some(hex) = s.strip_prefix("0x") {
+        (hex, 16)
+    } else if let Some(bin) = s.strip_prefix("0b") {
+        (bin, 2)
+    } else {
+        (s, 10)
+    };
+    u64::from_str_radix(&digits.replace('_', ""), radix)
+        .map_err(|_| format!("'{}' is not a valid number", s))
+}
+
+/// Parse a polynomial given as a number or in the form `x^8 + x^4 + x^3 + x + 1`
+pub fn parse_poly(s: &str) -> Result<G2Poly, String> {
+    if !s.contains('x') || s.trim_start().starts_with("0x") {
+        return parse_number(s).map(G2Poly);
+    }
+
+    let mut bits = 0_u64;
+    for term in s.split('+') {
+        let term: String = term.chars().filter(|c| !c.is_whitespace()).collect();
+        let exp = match term.as_str() {
+            "1" => 0,
+            "x" => 1,
+            t => t
+                .strip_prefix("x^")
+                .and_then(|e| e.parse::<u32>().ok())
+                .filter(|&e| e < 64)
+                .ok_or_else(|| format!("'{}' is not a valid term", term))?,
+        };
+        // Terms appearing twice cancel
+        bits ^= 1 << exp;
+    }
+    Ok(G2Poly(bits))
+}
+
+/// Format a polynomial as `x^8 + x^4 + x^3 + x + 1`
+pub fn format_poly(p: G2Poly) -> String {
+    if p == G2Poly::ZERO {
+        return "0".to_string();
+    }
+    (0..64)
+        .rev()
+        .filter(|&i| (p.0 >> i) & 1 == 1)
+        .map(|i| match i {
+            0 => "1".to_string(),
+            1 => "x".to_string(),
+            _ => format!("x^{}", i),
+        })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// Factor a polynomial into irreducible factors with multiplicities
+///
+/// Uses distinct degree factorization, followed by Cantor-Zassenhaus to split products of factors
+/// with the same degree. Constants have no factors.
+pub fn factor(f: G2Poly) -> Vec<(G2Poly, u32)> {
+    let mut factors = Vec::new();
+    if f == G2Poly::ZERO {
+        return factors;
+    }
+
+    let mut rest = f;
+    let mut d = 1;
+    while let Some(n) = rest.degree().filter(|&n| n > 0) {
+        // All factors of degree < d are removed, so rest is irreducible if it has no factor of
+        // degree <= n / 2
+        if 2 * d > n {
+            factors.push((rest, 1));
+            break;
+        }
+
+        // x^(2^d) - x is the product of all irreducible polynomials with degree dividing d
+        let h = G2Poly::X.pow_mod(1 << d, rest) + G2Poly::X % rest;
+        let g = gcd(rest, h);
+        if g != G2Poly::UNIT {
+            for q in split_equal_degree(g, d) {
+                let mut k = 0;
+                while rest % q == G2Poly::ZERO {
+                    rest = rest / q;
+                    k += 1;
+                }
+                factors.push((q, k));
+            }
+        }
+        d += 1;
+    }
+    factors.sort_by_key(|&(q, _)| (q.degree(), q));
+    factors
+}
+
+/// Split a product of distinct irreducible polynomials of degree `d`
+fn split_equal_degree(g: G2Poly, d: u64) -> Vec<G2Poly> {
+    let n = g.degree().expect("Factor is not zero");
+    if n == d {
+        return vec![g];
+    }
+
+    // The trace a + a^2 + ... + a^(2^(d - 1)) is 0 or 1 modulo each factor. Some a leads to
+    // different values for two factors, splitting g.
+    for a in 2..(1 << n) {
+        let mut t = G2Poly(a);
+        let mut sum = t;
+        for _ in 1..d {
+            t = t * t % g;
+            sum = sum + t;
+        }
+        let s = gcd(g, sum);
+        if s != G2Poly::UNIT && s != g {
+            let mut res = split_equal_degree(s, d);
+            res.extend(split_equal_degree(g / s, d));
+            return res;
+        }
+    }
+    unreachable!("A product of distinct irreducible factors can always be split")
+}
+
+/// Calculate the distinct prime factors of n
+pub fn prime_factors(n: u64) -> Vec<u64> {
+    let mut primes = Vec::new();
+    let mut n = n;
+    for p in 2..1000 {
+        if n % p == 0 {
+            primes.push(p);
+            while n % p == 0 {
+                n /= p;
+            }
+        }
+    }
+    let mut stack = vec![n];
+    while let Some(m) = stack.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            if !primes.contains(&m) {
+                primes.push(m);
+            }
+            continue;
+        }
+        let d = pollard_rho(m);
+        stack.push(d);
+        stack.push(m / d);
+    }
+    primes.sort_unstable();
+    primes
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((u128::from(a) * u128::from(b)) % u128::from(m)) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut res = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = mul_mod(res, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    res
+}
+
+/// Deterministic Miller-Rabin test for 64 bit numbers
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = BASES.iter().find(|&&p| n % p == 0) {
+        return n == p;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    BASES.iter().all(|&a| {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Find a non-trivial divisor of a composite number without small factors
+fn pollard_rho(n: u64) -> u64 {
+    for c in 1_u64.. {
+        let f = |x: u64| ((u128::from(mul_mod(x, x, n)) + u128::from(c)) % u128::from(n)) as u64;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd_u64(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!()
+}
+
+fn gcd_u64(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+/// Check if `g` generates the multiplicative group of GF(2)[x] / `modulus`
+///
+/// Unlike [`G2Poly::is_generator`], this checks g^((2^p - 1) / q) != 1 for the prime factors q of
+/// the group order, which is fast for all degrees.
+pub fn is_generator(g: G2Poly, modulus: G2Poly, order_factors: &[u64]) -> bool {
+    let p = modulus.degree().expect("Modulus is not zero");
+    let order = u64::MAX >> (64 - p);
+    let g = g % modulus;
+    g != G2Poly::ZERO
+        && order_factors
+            .iter()
+            .all(|&q| g.pow_mod(order / q, modulus) != G2Poly::UNIT)
+}
+
+/// Prime factors of the order 2^p - 1 of the multiplicative group of GF(2^p)
+pub fn group_order_factors(p: u64) -> Vec<u64> {
+    prime_factors(u64::MAX >> (64 - p))
+}
+
+/// Check if a polynomial is primitive, i.e. irreducible with x generating the multiplicative group
+pub fn is_primitive(f: G2Poly) -> bool {
+    match f.degree() {
+        Some(p) if p > 0 && f.is_irreducible() => {
+            is_generator(G2Poly::X, f, &group_order_factors(p))
+        }
+        _ => false,
+    }
+}
+
+/// Find the smallest generator of GF(2)[x] / `modulus`
+pub fn find_generator(modulus: G2Poly) -> Option<G2Poly> {
+    let p = modulus.degree().filter(|&p| p > 0)?;
+    let factors = group_order_factors(p);
+    (1..(1_u64 << p))
+        .map(G2Poly)
+        .find(|&g| is_generator(g, modulus, &factors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(parse_poly("0x11b"), Ok(G2Poly(0x11b)));
+        assert_eq!(parse_poly("283"), Ok(G2Poly(0x11b)));
+        assert_eq!(parse_poly("0b1_0001_1011"), Ok(G2Poly(0x11b)));
+        assert_eq!(parse_poly("x^8 + x^4 + x^3 + x + 1"), Ok(G2Poly(0x11b)));
+        assert_eq!(parse_poly("x+x"), Ok(G2Poly(0)));
+        assert!(parse_poly("x^64").is_err());
+        assert!(parse_poly("y^2").is_err());
+        assert_eq!(format_poly(G2Poly(0x11b)), "x^8 + x^4 + x^3 + x + 1");
+        assert_eq!(format_poly(G2Poly(0)), "0");
+        assert_eq!(format_poly(G2Poly(1)), "1");
+    }
+
+    fn multiply(factors: &[(G2Poly, u32)]) -> G2Poly {
+        factors.iter().fold(G2Poly::UNIT, |acc, &(q, k)| {
+            (0..k).fold(acc, |acc, _| (acc * q).to_poly())
+        })
+    }
+
+    #[test]
+    fn test_factor() {
+        // x^4 + 1 = (x + 1)^4
+        assert_eq!(factor(G2Poly(0b10001)), vec![(G2Poly(0b11), 4)]);
+        assert_eq!(factor(G2Poly(0x11b)), vec![(G2Poly(0x11b), 1)]);
+        assert_eq!(factor(G2Poly(1)), vec![]);
+        assert_eq!(
+            factor(G2Poly(0b110)),
+            vec![(G2Poly(0b10), 1), (G2Poly(0b11), 1)]
+        );
+
+        // Products of several factors of the same degree, with multiplicities
+        let deg4 = [G2Poly(0b10011), G2Poly(0b11001), G2Poly(0b11111)];
+        let mut expected = vec![(G2Poly(0b111), 2), (deg4[0], 1), (deg4[1], 3), (deg4[2], 1)];
+        let f = multiply(&expected);
+        expected.sort_by_key(|&(q, _)| (q.degree(), q));
+        assert_eq!(factor(f), expected);
+
+        for f in (2..2000).map(G2Poly) {
+            let factors = factor(f);
+            assert_eq!(multiply(&factors), f);
+            assert!(factors.iter().all(|&(q, _)| q.is_irreducible()));
+        }
+    }
+
+    #[test]
+    fn test_prime_factors() {
+        assert_eq!(prime_factors(255), vec![3, 5, 17]);
+        assert_eq!(
+            prime_factors(u64::MAX),
+            vec![3, 5, 17, 257, 641, 65537, 6700417]
+        );
+        // 2^61 - 1 is prime
+        assert_eq!(prime_factors((1 << 61) - 1), vec![(1 << 61) - 1]);
+        assert_eq!(prime_factors((1 << 59) - 1), vec![179951, 3203431780337]);
+    }
+
+    #[test]
+    fn test_primitive() {
+        assert!(is_primitive(G2Poly(0b10011)));
+        // Irreducible, but x has order 5
+        assert!(!is_primitive(G2Poly(0b11111)));
+        assert!(!is_primitive(G2Poly(0b10001)));
+        // The AES modulus is not primitive, x + 1 generates the field
+        assert!(!is_primitive(G2Poly(0x11b)));
+        assert_eq!(find_generator(G2Poly(0x11b)), Some(G2Poly(3)));
+        // x^63 + x + 1
+        assert!(is_primitive(G2Poly((1 << 63) | 0b11)));
+
+        for f in (4..256).map(G2Poly).filter(|f| f.is_irreducible()) {
+            assert_eq!(is_primitive(f), G2Poly::X.is_generator(f));
+        }
+    }
+}
@@ -0,0 +1,167 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Printing the arithmetic tables of a field
+
+use std::io::{self, Write};
+
+use g2build::ExportTable;
+use g2poly::G2Poly;
+
+use crate::expr::inverse;
+
+/// Largest degree for which the multiplication table is printed
+pub const MAX_MUL_DEGREE: u64 = 8;
+/// Largest degree for which the other tables are printed
+pub const MAX_DEGREE: u64 = 16;
+/// Largest degree of fields supported by g2build, exports are limited per table
+pub const MAX_EXPORT_DEGREE: u64 = 32;
+
+/// Find a table by its name
+pub fn table_from_name(name: &str) -> Option<ExportTable> {
+    ExportTable::ALL.iter().copied().find(|t| t.name() == name)
+}
+
+/// Powers g^0, ..., g^(2^p - 2) of a generator
+pub fn exp_table(modulus: G2Poly, generator: G2Poly) -> Vec<u64> {
+    let p = modulus.degree().expect("Modulus is not zero");
+    let mut res = Vec::with_capacity((1 << p) - 1);
+    let mut x = G2Poly::UNIT;
+    for _ in 0..(1_u64 << p) - 1 {
+        res.push(x.0);
+        x = x * generator % modulus;
+    }
+    res
+}
+
+/// Discrete logarithms of 0, ..., 2^p - 1, where the entry for 0 is 0
+pub fn log_table(modulus: G2Poly, generator: G2Poly) -> Vec<u64> {
+    let p = modulus.degree().expect("Modulus is not zero");
+    let mut res = vec![0; 1 << p];
+    for (i, &x) in exp_table(modulus, generator).iter().enumerate() {
+        res[x as usize] = i as u64;
+    }
+    res
+}
+
+/// Write a table with 16 entries per line, prefixed by the index of the first entry
+fn write_rows<W: Write + ?Sized>(out: &mut W, values: &[u64], digits: usize) -> io::Result<()> {
+    let index_digits = digits.max(format!("{:x}", values.len().saturating_sub(1)).len());
+    for (row, chunk) in values.chunks(16).enumerate() {
+        write!(out, "{:0w$x}:", row * 16, w = index_digits)?;
+        for v in chunk {
+            write!(out, " {:0w$x}", v, w = digits)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Print a table for the field GF(2)[x] / `modulus`
+///
+/// The modulus must be irreducible with degree at most [`MAX_DEGREE`], or [`MAX_MUL_DEGREE`] for
+/// the multiplication and nibble tables. Nibble tables are printed as one row per constant, with
+/// the products for the low nibble followed by the products for the high nibble.
+pub fn write_table<W: Write + ?Sized>(
+    out: &mut W,
+    table: ExportTable,
+    modulus: G2Poly,
+    generator: G2Poly,
+) -> io::Result<()> {
+    let p = modulus.degree().expect("Modulus is not zero");
+    let size = 1_u64 << p;
+    let digits = ((p + 3) / 4) as usize;
+    match table {
+        ExportTable::Mul => {
+            write!(out, "{:w$} ", "*", w = digits)?;
+            for b in 0..size {
+                write!(out, " {:0w$x}", b, w = digits)?;
+            }
+            writeln!(out)?;
+            for a in 0..size {
+                write!(out, "{:0w$x}:", a, w = digits)?;
+                for b in 0..size {
+                    let prod = G2Poly(a) * G2Poly(b) % modulus;
+                    write!(out, " {:0w$x}", prod.0, w = digits)?;
+                }
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        ExportTable::Inv => {
+            let inv: Vec<u64> = (0..size)
+                .map(|a| inverse(G2Poly(a), modulus).map_or(0, |x| x.0))
+                .collect();
+            write_rows(out, &inv, digits)
+        }
+        ExportTable::Log => write_rows(out, &log_table(modulus, generator), digits),
+        ExportTable::Exp => write_rows(out, &exp_table(modulus, generator), digits),
+        ExportTable::Nibble => {
+            for c in 0..size {
+                write!(out, "{:0w$x}:", c, w = digits)?;
+                for shift in [0, 4] {
+                    if shift > 0 {
+                        write!(out, " |")?;
+                    }
+                    for n in 0..16 {
+                        let prod = G2Poly(c) * G2Poly((n << shift) & (size - 1)) % modulus;
+                        write!(out, " {:0w$x}", prod.0, w = digits)?;
+                    }
+                }
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exp_log() {
+        let m = G2Poly(0b10011);
+        let exp = exp_table(m, G2Poly::X);
+        assert_eq!(exp[..5], [1, 2, 4, 8, 3]);
+        assert_eq!(exp.len(), 15);
+        let log = log_table(m, G2Poly::X);
+        for (i, &x) in exp.iter().enumerate() {
+            assert_eq!(log[x as usize], i as u64);
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let m = G2Poly(0b111);
+        let mut out = Vec::new();
+        write_table(&mut out, ExportTable::Mul, m, G2Poly::X).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "*  0 1 2 3\n0: 0 0 0 0\n1: 0 1 2 3\n2: 0 2 3 1\n3: 0 3 1 2\n"
+        );
+
+        let mut out = Vec::new();
+        write_table(&mut out, ExportTable::Inv, m, G2Poly::X).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "0: 0 1 3 2\n");
+
+        let mut out = Vec::new();
+        let aes = G2Poly(0x11b);
+        write_table(&mut out, ExportTable::Inv, aes, G2Poly(3)).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 16);
+        assert!(text.starts_with("00: 00 01 8d f6 cb 52 7b d1"));
+
+        let mut out = Vec::new();
+        write_table(&mut out, ExportTable::Nibble, m, G2Poly::X).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 4);
+        assert_eq!(
+            text.lines().nth(2),
+            Some("2: 0 2 3 1 0 2 3 1 0 2 3 1 0 2 3 1 | 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0")
+        );
+    }
+}
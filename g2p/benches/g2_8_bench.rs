@@ -1,11 +1,7 @@
 use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
-use galois_2p8;
+use g2p::GaloisField;
 use galois_2p8::Field;
-use rand;
 use rand::{Rng, RngCore};
-use reed_solomon_erasure;
-
-use g2p;
 
 g2p::g2p!(GF256, 8);
 
@@ -13,7 +9,7 @@ fn g2p_addition(a: &[u8], b: &[u8], dest: &mut [u8]) {
     assert_eq!(a.len(), b.len());
     assert_eq!(b.len(), dest.len());
 
-    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.into_iter(), b), dest) {
+    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.iter(), b), dest) {
         *d = (GF256::from(l) + GF256::from(r)).into()
     }
 }
@@ -35,7 +31,7 @@ fn reed_solomon_erasure_addition(a: &[u8], b: &[u8], dest: &mut [u8]) {
     assert_eq!(a.len(), b.len());
     assert_eq!(b.len(), dest.len());
 
-    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.into_iter(), b), dest) {
+    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.iter(), b), dest) {
         *d = reed_solomon_erasure::galois_8::add(l, r);
     }
 }
@@ -44,7 +40,7 @@ fn g2p_multiplication(a: &[u8], b: &[u8], dest: &mut [u8]) {
     assert_eq!(a.len(), b.len());
     assert_eq!(b.len(), dest.len());
 
-    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.into_iter(), b), dest) {
+    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.iter(), b), dest) {
         *d = (GF256::from(l) * GF256::from(r)).into()
     }
 }
@@ -58,7 +54,7 @@ fn galois_2p8_multiplication(
     assert_eq!(a.len(), b.len());
     assert_eq!(b.len(), dest.len());
 
-    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.into_iter(), b), dest) {
+    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.iter(), b), dest) {
         *d = field.mult(l, r)
     }
 }
@@ -67,7 +63,7 @@ fn reed_solomon_erasure_multiplication(a: &[u8], b: &[u8], dest: &mut [u8]) {
     assert_eq!(a.len(), b.len());
     assert_eq!(b.len(), dest.len());
 
-    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.into_iter(), b), dest) {
+    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.iter(), b), dest) {
         *d = reed_solomon_erasure::galois_8::mul(l, r);
     }
 }
@@ -75,11 +71,24 @@ fn reed_solomon_erasure_multiplication(a: &[u8], b: &[u8], dest: &mut [u8]) {
 fn g2p_multiplication_const(a: &[u8], b: u8, dest: &mut [u8]) {
     assert_eq!(a.len(), dest.len());
 
-    for (&l, d) in Iterator::zip(a.into_iter(), dest) {
+    for (&l, d) in Iterator::zip(a.iter(), dest) {
         *d = (GF256::from(l) * GF256::from(b)).into()
     }
 }
 
+fn g2p_multiplier_multiplication_const(a: &[u8], b: u8, dest: &mut [u8]) {
+    assert_eq!(a.len(), dest.len());
+
+    let m = GF256::multiplier(GF256::from(b));
+    for (&l, d) in Iterator::zip(a.iter(), dest) {
+        *d = m.apply(GF256::from(l)).into()
+    }
+}
+
+fn g2p_slice_multiplication_const(a: &[GF256], b: u8, dest: &mut [GF256]) {
+    GF256::mul_slice(dest, a, GF256::from(b));
+}
+
 fn galois_2p8_multiplication_const(
     field: &galois_2p8::PrimitivePolynomialField,
     a: &[u8],
@@ -102,7 +111,7 @@ fn g2p_division(a: &[u8], b: &[u8], dest: &mut [u8]) {
     assert_eq!(a.len(), b.len());
     assert_eq!(b.len(), dest.len());
 
-    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.into_iter(), b), dest) {
+    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.iter(), b), dest) {
         *d = (GF256::from(l) / GF256::from(r)).into()
     }
 }
@@ -116,7 +125,7 @@ fn galois_2p8_division(
     assert_eq!(a.len(), b.len());
     assert_eq!(b.len(), dest.len());
 
-    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.into_iter(), b), dest) {
+    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.iter(), b), dest) {
         *d = field.div(l, r)
     }
 }
@@ -125,7 +134,7 @@ fn reed_solomon_erasure_division(a: &[u8], b: &[u8], dest: &mut [u8]) {
     assert_eq!(a.len(), b.len());
     assert_eq!(b.len(), dest.len());
 
-    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.into_iter(), b), dest) {
+    for ((&l, &r), d) in Iterator::zip(Iterator::zip(a.iter(), b), dest) {
         *d = reed_solomon_erasure::galois_8::div(l, r);
     }
 }
@@ -133,11 +142,15 @@ fn reed_solomon_erasure_division(a: &[u8], b: &[u8], dest: &mut [u8]) {
 fn g2p_division_const(a: &[u8], b: u8, dest: &mut [u8]) {
     assert_eq!(a.len(), dest.len());
 
-    for (&l, d) in Iterator::zip(a.into_iter(), dest) {
+    for (&l, d) in Iterator::zip(a.iter(), dest) {
         *d = (GF256::from(l) / GF256::from(b)).into()
     }
 }
 
+fn g2p_slice_division_const(a: &[GF256], b: u8, dest: &mut [GF256]) {
+    GF256::div_slice(dest, a, GF256::from(b));
+}
+
 fn galois_2p8_division_const(
     field: &galois_2p8::PrimitivePolynomialField,
     a: &[u8],
@@ -153,7 +166,7 @@ fn galois_2p8_division_const(
 fn reed_solomon_erasure_division_const(a: &[u8], b: u8, dest: &mut [u8]) {
     assert_eq!(a.len(), dest.len());
 
-    for (&l, d) in Iterator::zip(a.into_iter(), dest) {
+    for (&l, d) in Iterator::zip(a.iter(), dest) {
         *d = reed_solomon_erasure::galois_8::div(l, b);
     }
 }
@@ -161,7 +174,7 @@ fn reed_solomon_erasure_division_const(a: &[u8], b: u8, dest: &mut [u8]) {
 fn g2p_inverse(a: &[u8], dest: &mut [u8]) {
     assert_eq!(a.len(), dest.len());
 
-    for (&inv, d) in Iterator::zip(a.into_iter(), dest) {
+    for (&inv, d) in Iterator::zip(a.iter(), dest) {
         *d = (GF256::from(1) / GF256::from(inv)).into()
     }
 }
@@ -169,7 +182,7 @@ fn g2p_inverse(a: &[u8], dest: &mut [u8]) {
 fn galois_2p8_inverse(field: &galois_2p8::PrimitivePolynomialField, a: &[u8], dest: &mut [u8]) {
     assert_eq!(a.len(), dest.len());
 
-    for (&inv, d) in Iterator::zip(a.into_iter(), dest) {
+    for (&inv, d) in Iterator::zip(a.iter(), dest) {
         *d = field.div(1, inv);
     }
 }
@@ -177,7 +190,7 @@ fn galois_2p8_inverse(field: &galois_2p8::PrimitivePolynomialField, a: &[u8], de
 fn reed_solomon_erasure_inverse(a: &[u8], dest: &mut [u8]) {
     assert_eq!(a.len(), dest.len());
 
-    for (&inv, d) in Iterator::zip(a.into_iter(), dest) {
+    for (&inv, d) in Iterator::zip(a.iter(), dest) {
         *d = reed_solomon_erasure::galois_8::div(1, inv);
     }
 }
@@ -289,6 +302,28 @@ fn all_benches(c: &mut Criterion) {
                 BatchSize::SmallInput,
             )
         });
+        group.bench_function(BenchmarkId::new("g2p_multiplier", i), |bencher| {
+            bencher.iter_batched(
+                || (a.clone(), dest.clone()),
+                |(a, mut dest)| {
+                    g2p_multiplier_multiplication_const(&a, b, &mut dest);
+                    dest
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_function(BenchmarkId::new("g2p_slice", i), |bencher| {
+            let a: Vec<GF256> = a.iter().map(|&v| GF256::from(v)).collect();
+            let dest = vec![GF256::ZERO; i];
+            bencher.iter_batched(
+                || (a.clone(), dest.clone()),
+                |(a, mut dest)| {
+                    g2p_slice_multiplication_const(&a, b, &mut dest);
+                    dest
+                },
+                BatchSize::SmallInput,
+            )
+        });
         group.bench_function(BenchmarkId::new("galois_2p8", i), |bencher| {
             bencher.iter_batched(
                 || (a.clone(), dest.clone()),
@@ -421,6 +456,18 @@ fn all_benches(c: &mut Criterion) {
                 BatchSize::SmallInput,
             )
         });
+        group.bench_function(BenchmarkId::new("g2p_slice", i), |bencher| {
+            let a: Vec<GF256> = a.iter().map(|&v| GF256::from(v)).collect();
+            let dest = vec![GF256::ZERO; i];
+            bencher.iter_batched(
+                || (a.clone(), dest.clone()),
+                |(a, mut dest)| {
+                    g2p_slice_division_const(&a, b, &mut dest);
+                    dest
+                },
+                BatchSize::SmallInput,
+            )
+        });
         group.bench_function(BenchmarkId::new("galois_2p8", i), |bencher| {
             bencher.iter_batched(
                 || (a.clone(), dest.clone()),
@@ -0,0 +1,262 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Binary BCH codes
+//!
+//! A narrow-sense binary BCH code of length n = 2^m - 1 and designed distance δ has the roots
+//! a, a^2, ..., a^(δ-1), where a is the generator of GF(2^m). Its generator polynomial is the
+//! product of the distinct minimal polynomials of these roots, so it has coefficients in GF(2).
+//! The code corrects t = (δ - 1) / 2 bit errors.
+//!
+//! Every BCH codeword is also a codeword of the Reed-Solomon code over GF(2^m) with the same
+//! roots, so decoding lifts the bits to the field and uses the
+//! [`ReedSolomonCode`](crate::reed_solomon::ReedSolomonCode) decoder: syndromes in GF(2^m),
+//! Berlekamp-Massey and a Chien search. Requires the `alloc` feature.
+//!
+//! ```rust
+//! use g2p::{g2p, bch::Bch, GaloisField, GF2};
+//!
+//! g2p!(GF16, 4, modulus: 0b10011);
+//! # fn main() {
+//! // The (15, 7) BCH code corrects two errors
+//! let code = Bch::<GF16>::new(5).unwrap();
+//! assert_eq!(code.parity_len(), 8);
+//! assert_eq!(code.generator_g2poly(), Some(g2p::G2Poly(0b1_1101_0001)));
+//!
+//! let message = [GF2::ONE, GF2::ZERO, GF2::ONE, GF2::ONE, GF2::ZERO, GF2::ZERO, GF2::ONE];
+//! let codeword = code.encode(&message);
+//! let mut received = codeword.clone();
+//! received[2] += GF2::ONE;
+//! received[12] += GF2::ONE;
+//! assert_eq!(code.decode(&mut received), Ok(2));
+//! assert_eq!(received, codeword);
+//! # }
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    extension::minimal_polynomial,
+    poly::Poly,
+    reed_solomon::{DecodeError, ReedSolomonCode},
+    G2Poly, GaloisField, GF2,
+};
+
+/// Narrow-sense binary BCH code over GF(2^m)
+///
+/// Codewords are stored message first, like for [`ReedSolomonCode`]: `codeword[i]` is the
+/// coefficient of x^(n-1-i). Shortened codes are supported by passing shorter messages.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Bch<F> {
+    designed_distance: usize,
+    minimal_polynomials: Vec<G2Poly>,
+    // Bits of the generator polynomial, lowest degree first
+    generator: Vec<u64>,
+    degree: usize,
+    rs: ReedSolomonCode<F>,
+}
+
+impl<F: GaloisField> Bch<F> {
+    /// Create a code with the given designed distance
+    ///
+    /// Returns `None` if the designed distance is smaller than 2 or larger than 2^m - 1.
+    pub fn new(designed_distance: usize) -> Option<Self> {
+        let n = F::SIZE - 1;
+        if designed_distance < 2 || designed_distance > n {
+            return None;
+        }
+
+        // Each cyclotomic coset {i, 2i, 4i, ...} shares one minimal polynomial
+        let mut covered = vec![false; designed_distance];
+        let mut minimal_polynomials = Vec::new();
+        let mut generator = vec![1_u64];
+        let mut degree = 0;
+        for i in 1..designed_distance {
+            if covered[i] {
+                continue;
+            }
+            let mut exponent = i;
+            loop {
+                if let Some(c) = covered.get_mut(exponent) {
+                    *c = true;
+                }
+                exponent = (exponent * 2) % n;
+                if exponent == i {
+                    break;
+                }
+            }
+
+            let m = minimal_polynomial(F::GENERATOR.pow(i));
+            let m_degree = m.degree().expect("Minimal polynomial is not zero") as usize;
+            generator = mul_words(&generator, m);
+            degree += m_degree;
+            minimal_polynomials.push(m);
+        }
+
+        Some(Bch {
+            designed_distance,
+            minimal_polynomials,
+            generator,
+            degree,
+            rs: ReedSolomonCode::with_params(designed_distance - 1, 1, F::GENERATOR),
+        })
+    }
+
+    /// The designed distance δ
+    pub fn designed_distance(&self) -> usize {
+        self.designed_distance
+    }
+
+    /// Number of bit errors the code corrects
+    pub fn correction_capability(&self) -> usize {
+        (self.designed_distance - 1) / 2
+    }
+
+    /// Number of parity bits, the degree of the generator polynomial
+    pub fn parity_len(&self) -> usize {
+        self.degree
+    }
+
+    /// Maximum length of a codeword, 2^m - 1
+    pub fn max_len(&self) -> usize {
+        F::SIZE - 1
+    }
+
+    /// Maximum length of a message, 2^m - 1 - [`parity_len`](Self::parity_len)
+    pub fn max_message_len(&self) -> usize {
+        self.max_len() - self.degree
+    }
+
+    /// The distinct minimal polynomials whose product is the generator polynomial
+    pub fn minimal_polynomials(&self) -> &[G2Poly] {
+        &self.minimal_polynomials
+    }
+
+    /// The generator polynomial
+    pub fn generator(&self) -> Poly<GF2> {
+        Poly::new((0..=self.degree).map(|i| bit(&self.generator, i)).collect())
+    }
+
+    /// The generator polynomial as [`G2Poly`], if its degree is less than 64
+    pub fn generator_g2poly(&self) -> Option<G2Poly> {
+        if self.degree < 64 {
+            Some(G2Poly(self.generator[0]))
+        } else {
+            None
+        }
+    }
+
+    /// Calculate the parity bits for a message
+    ///
+    /// # Panics
+    /// Panics if `message` is longer than [`max_message_len`](Self::max_message_len).
+    pub fn parity(&self, message: &[GF2]) -> Vec<GF2> {
+        assert!(
+            message.len() <= self.max_message_len(),
+            "Message too long for the code"
+        );
+        let words = (self.degree + 63) / 64;
+
+        // Remainder of message * x^r by the generator, using a shift register
+        let mut rem = vec![0_u64; words];
+        for &m in message {
+            let feedback = (m == GF2::ONE) ^ (bit(&rem, self.degree - 1) == GF2::ONE);
+            shift_left(&mut rem, self.degree);
+            if feedback {
+                for (r, &g) in rem.iter_mut().zip(self.generator.iter()) {
+                    *r ^= g;
+                }
+                // The x^r term of the generator was shifted out
+                if let Some(last) = rem.get_mut(self.degree / 64) {
+                    *last &= !(1 << (self.degree % 64));
+                }
+            }
+        }
+        (0..self.degree).rev().map(|i| bit(&rem, i)).collect()
+    }
+
+    /// Encode a message, returning the message followed by the parity bits
+    ///
+    /// # Panics
+    /// Panics if `message` is longer than [`max_message_len`](Self::max_message_len).
+    pub fn encode(&self, message: &[GF2]) -> Vec<GF2> {
+        let mut res = Vec::with_capacity(message.len() + self.degree);
+        res.extend_from_slice(message);
+        res.extend(self.parity(message));
+        res
+    }
+
+    /// Calculate the syndromes c(a^j) for j in 1..δ in the extension field
+    pub fn syndromes(&self, codeword: &[GF2]) -> Vec<F> {
+        self.rs.syndromes(&lift(codeword))
+    }
+
+    /// Check if `codeword` is a valid codeword
+    pub fn is_valid(&self, codeword: &[GF2]) -> bool {
+        self.syndromes(codeword).iter().all(|s| s.is_zero())
+    }
+
+    /// Correct bit errors in place
+    ///
+    /// Returns the number of corrected bits.
+    pub fn decode(&self, codeword: &mut [GF2]) -> Result<usize, DecodeError> {
+        let mut lifted = lift(codeword);
+        let corrected = self.rs.decode(&mut lifted, &[])?;
+        if lifted.iter().any(|&x| x != F::ZERO && x != F::ONE) {
+            // The Reed-Solomon decoder found a codeword which is not binary
+            return Err(DecodeError::Uncorrectable);
+        }
+        for (c, &x) in codeword.iter_mut().zip(lifted.iter()) {
+            *c = if x == F::ONE { GF2::ONE } else { GF2::ZERO };
+        }
+        Ok(corrected)
+    }
+}
+
+fn lift<F: GaloisField>(bits: &[GF2]) -> Vec<F> {
+    bits.iter()
+        .map(|&b| if b == GF2::ONE { F::ONE } else { F::ZERO })
+        .collect()
+}
+
+fn bit(words: &[u64], i: usize) -> GF2 {
+    match words.get(i / 64) {
+        Some(w) if (w >> (i % 64)) & 1 == 1 => GF2::ONE,
+        _ => GF2::ZERO,
+    }
+}
+
+/// Multiply a multi-word polynomial with a small polynomial
+fn mul_words(a: &[u64], b: G2Poly) -> Vec<u64> {
+    let b_degree = b.degree().unwrap_or(0) as usize;
+    let mut res = vec![0_u64; a.len() + (b_degree + 63) / 64 + 1];
+    for shift in (0..=b_degree).filter(|&s| (b.0 >> s) & 1 == 1) {
+        let (words, bits) = (shift / 64, shift % 64);
+        for (i, &w) in a.iter().enumerate() {
+            res[i + words] ^= w << bits;
+            if bits > 0 {
+                res[i + words + 1] ^= w >> (64 - bits);
+            }
+        }
+    }
+    while res.len() > 1 && res.last() == Some(&0) {
+        res.pop();
+    }
+    res
+}
+
+/// Shift a register of `len` bits left by one, dropping the top bit
+fn shift_left(words: &mut [u64], len: usize) {
+    for i in (0..words.len()).rev() {
+        let carry = if i > 0 { words[i - 1] >> 63 } else { 0 };
+        words[i] = (words[i] << 1) | carry;
+    }
+    if len % 64 != 0 {
+        if let Some(last) = words.last_mut() {
+            *last &= (1 << (len % 64)) - 1;
+        }
+    }
+}
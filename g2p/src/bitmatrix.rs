@@ -0,0 +1,438 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Bit-matrices and XOR schedules for Cauchy Reed-Solomon coding
+//!
+//! Multiplication with a constant of GF(2^p) is linear over GF(2), so it is described by a p x p
+//! [`BitMatrix`]. Expanding every entry of a [`Matrix`] the same way turns a code over GF(2^p)
+//! into a code using only XORs, as in Cauchy Reed-Solomon coding.
+//!
+//! The XORs are executed on packets instead of single bits. Each shard is split into p packets of
+//! equal length, where packet `a` holds the coefficients of x^a of the shard elements. Column
+//! `j * p + a` of the expanded matrix refers to packet `a` of input shard `j`, and row
+//! `i * p + b` to packet `b` of output shard `i`. An [`XorSchedule`] is the list of packet XORs
+//! computing the outputs. Common subexpression elimination finds pairs of packets that are XORed
+//! together for several outputs and computes them only once. Requires the `alloc` feature.
+//!
+//! ```rust
+//! use g2p::{g2p, bitmatrix::{BitMatrix, XorSchedule}, matrix::Matrix};
+//!
+//! g2p!(GF16, 4);
+//! # fn main() {
+//! // Parity matrix for 3 data and 2 parity shards
+//! let xs = [GF16::from(0), GF16::from(1)];
+//! let ys = [GF16::from(2), GF16::from(3), GF16::from(4)];
+//! let parity = BitMatrix::from_matrix(&Matrix::cauchy(&xs, &ys).unwrap());
+//! assert_eq!((parity.rows(), parity.cols()), (8, 12));
+//!
+//! let direct = XorSchedule::direct(&parity);
+//! let schedule = XorSchedule::with_cse(&parity);
+//! assert!(schedule.xor_count() < direct.xor_count());
+//!
+//! // 3 data shards with 4 packets of 2 bytes each
+//! let data: Vec<[u8; 2]> = (0..12).map(|i| [i, 3 * i + 1]).collect();
+//! let inputs: Vec<&[u8]> = data.iter().map(|p| &p[..]).collect();
+//! let mut parity_packets = vec![[0; 2]; 8];
+//! let mut outputs: Vec<&mut [u8]> = parity_packets.iter_mut().map(|p| &mut p[..]).collect();
+//! schedule.apply(&inputs, &mut outputs);
+//! # }
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::{convert::TryFrom, fmt, ops};
+
+use crate::{matrix::Matrix, GaloisField};
+
+/// Matrix with entries in GF(2), stored as one bit per entry
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+    /// Words per row
+    stride: usize,
+    data: Vec<u64>,
+}
+
+impl BitMatrix {
+    /// Create a matrix with all entries zero
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        let stride = (cols + 63) / 64;
+        BitMatrix {
+            rows,
+            cols,
+            stride,
+            data: vec![0; rows * stride],
+        }
+    }
+
+    /// Create the n x n identity matrix
+    pub fn identity(n: usize) -> Self {
+        let mut res = Self::zero(n, n);
+        for i in 0..n {
+            res.set(i, i, true);
+        }
+        res
+    }
+
+    /// Create the p x p matrix of the multiplication with `c`
+    ///
+    /// Column `j` is the representation of `c * x^j`, so multiplying the matrix with the bits of
+    /// `a` results in the bits of `c * a`.
+    pub fn from_element<F: GaloisField>(c: F) -> Self {
+        let p = F::DEGREE as usize;
+        let mut res = Self::zero(p, p);
+        res.set_block(0, 0, c);
+        res
+    }
+
+    /// Expand a matrix over GF(2^p) into a matrix over GF(2)
+    ///
+    /// Every entry is replaced by the p x p matrix from [`from_element`](BitMatrix::from_element),
+    /// so the result has p times as many rows and columns.
+    pub fn from_matrix<F: GaloisField>(m: &Matrix<F>) -> Self {
+        let p = F::DEGREE as usize;
+        let mut res = Self::zero(m.rows() * p, m.cols() * p);
+        for r in 0..m.rows() {
+            for (c, &v) in m.row(r).iter().enumerate() {
+                res.set_block(r * p, c * p, v);
+            }
+        }
+        res
+    }
+
+    fn set_block<F: GaloisField>(&mut self, row: usize, col: usize, c: F) {
+        for j in 0..F::DEGREE {
+            let power = F::Repr::try_from(1 << j)
+                .ok()
+                .and_then(F::from_repr)
+                .expect("x^j is an element of the field");
+            let column: u32 = (c * power).to_repr().into();
+            for i in 0..F::DEGREE {
+                self.set(row + i as usize, col + j as usize, (column >> i) & 1 == 1);
+            }
+        }
+    }
+
+    /// Number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Get an entry
+    ///
+    /// # Panics
+    /// Panics if the position is out of range.
+    pub fn get(&self, r: usize, c: usize) -> bool {
+        assert!(r < self.rows && c < self.cols, "Index out of range");
+        (self.data[r * self.stride + c / 64] >> (c % 64)) & 1 == 1
+    }
+
+    /// Set an entry
+    ///
+    /// # Panics
+    /// Panics if the position is out of range.
+    pub fn set(&mut self, r: usize, c: usize, value: bool) {
+        assert!(r < self.rows && c < self.cols, "Index out of range");
+        let word = &mut self.data[r * self.stride + c / 64];
+        let bit = 1 << (c % 64);
+        if value {
+            *word |= bit;
+        } else {
+            *word &= !bit;
+        }
+    }
+
+    /// Number of ones in a row
+    pub fn row_ones(&self, r: usize) -> usize {
+        self.row_words(r)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    /// Number of ones in the matrix
+    ///
+    /// Computing the outputs without common subexpressions takes `ones() - rows()` XORs for a
+    /// matrix without zero rows, so matrices with fewer ones lead to faster codes.
+    pub fn ones(&self) -> usize {
+        self.data.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Columns of the ones in a row, in increasing order
+    fn row_columns(&self, r: usize) -> Vec<usize> {
+        (0..self.cols).filter(|&c| self.get(r, c)).collect()
+    }
+
+    fn row_words(&self, r: usize) -> &[u64] {
+        &self.data[r * self.stride..(r + 1) * self.stride]
+    }
+}
+
+impl<'a> ops::Mul<&'a BitMatrix> for &'a BitMatrix {
+    type Output = BitMatrix;
+
+    fn mul(self, rhs: &'a BitMatrix) -> BitMatrix {
+        assert_eq!(self.cols, rhs.rows, "Matrix dimensions do not match");
+        let mut res = BitMatrix::zero(self.rows, rhs.cols);
+        for r in 0..self.rows {
+            for k in 0..self.cols {
+                if self.get(r, k) {
+                    let dst = &mut res.data[r * res.stride..(r + 1) * res.stride];
+                    for (d, s) in dst.iter_mut().zip(rhs.row_words(k)) {
+                        *d ^= s;
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+impl ops::Mul for BitMatrix {
+    type Output = BitMatrix;
+
+    fn mul(self, rhs: BitMatrix) -> BitMatrix {
+        &self * &rhs
+    }
+}
+
+impl fmt::Debug for BitMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Row<'a>(&'a BitMatrix, usize);
+
+        impl fmt::Debug for Row<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for c in 0..self.0.cols {
+                    f.write_str(if self.0.get(self.1, c) { "1" } else { "0" })?;
+                }
+                Ok(())
+            }
+        }
+
+        f.debug_list()
+            .entries((0..self.rows).map(|r| Row(self, r)))
+            .finish()
+    }
+}
+
+/// A packet used by an [`XorSchedule`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Slot {
+    /// Input packet, corresponding to a column of the bit-matrix
+    Input(usize),
+    /// Output packet, corresponding to a row of the bit-matrix
+    Output(usize),
+    /// Temporary packet holding a common subexpression
+    Temp(usize),
+}
+
+/// Operation of an [`XorSchedule`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum XorOp {
+    /// Copy `src` to `dst`
+    Copy {
+        /// Source packet
+        src: Slot,
+        /// Destination packet
+        dst: Slot,
+    },
+    /// XOR `src` into `dst`
+    Xor {
+        /// Source packet
+        src: Slot,
+        /// Destination packet
+        dst: Slot,
+    },
+    /// Set `dst` to zero, used for zero rows of the bit-matrix
+    Zero {
+        /// Destination packet
+        dst: Slot,
+    },
+}
+
+/// Sequence of packet operations computing the product of a [`BitMatrix`] with input packets
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct XorSchedule {
+    inputs: usize,
+    outputs: usize,
+    temps: usize,
+    ops: Vec<XorOp>,
+}
+
+impl XorSchedule {
+    /// Compute every output as the XOR of the inputs in its row
+    pub fn direct(m: &BitMatrix) -> Self {
+        let rows: Vec<Vec<usize>> = (0..m.rows).map(|r| m.row_columns(r)).collect();
+        Self::from_rows(m.cols, &rows, &[])
+    }
+
+    /// Compute the outputs using common subexpression elimination
+    ///
+    /// The pair of packets appearing together in the most rows is XORed into a temporary packet,
+    /// which replaces the pair in those rows. This is repeated while some pair appears in more
+    /// than one row. Each temporary packet takes one XOR and saves at least one, so the schedule
+    /// never needs more XORs than [`direct`](XorSchedule::direct).
+    pub fn with_cse(m: &BitMatrix) -> Self {
+        let mut rows: Vec<Vec<usize>> = (0..m.rows).map(|r| m.row_columns(r)).collect();
+        let mut temps = Vec::new();
+        loop {
+            let symbols = m.cols + temps.len();
+            let mut counts = vec![0_u32; symbols * symbols];
+            for row in &rows {
+                for (i, &a) in row.iter().enumerate() {
+                    for &b in &row[i + 1..] {
+                        counts[a * symbols + b] += 1;
+                    }
+                }
+            }
+
+            // Ties are broken by the smallest pair, so the schedule is deterministic
+            let best = counts
+                .iter()
+                .enumerate()
+                .rev()
+                .max_by_key(|&(_, count)| count);
+            let best = match best {
+                Some((best, &count)) if count >= 2 => best,
+                _ => break,
+            };
+
+            let (a, b) = (best / symbols, best % symbols);
+            for row in &mut rows {
+                if row.binary_search(&a).is_ok() && row.binary_search(&b).is_ok() {
+                    row.retain(|&s| s != a && s != b);
+                    // The new symbol is the largest, so the row stays sorted
+                    row.push(symbols);
+                }
+            }
+            temps.push((a, b));
+        }
+        Self::from_rows(m.cols, &rows, &temps)
+    }
+
+    /// Build the schedule from the terms of each output and the pairs summed into temporaries
+    ///
+    /// Symbols below `inputs` are input packets, symbol `inputs + t` is temporary `t`.
+    fn from_rows(inputs: usize, rows: &[Vec<usize>], temps: &[(usize, usize)]) -> Self {
+        let slot = |s: usize| {
+            if s < inputs {
+                Slot::Input(s)
+            } else {
+                Slot::Temp(s - inputs)
+            }
+        };
+
+        let mut ops = Vec::new();
+        for (t, &(a, b)) in temps.iter().enumerate() {
+            let dst = Slot::Temp(t);
+            ops.push(XorOp::Copy { src: slot(a), dst });
+            ops.push(XorOp::Xor { src: slot(b), dst });
+        }
+        for (r, row) in rows.iter().enumerate() {
+            let dst = Slot::Output(r);
+            match row.split_first() {
+                None => ops.push(XorOp::Zero { dst }),
+                Some((&first, rest)) => {
+                    ops.push(XorOp::Copy {
+                        src: slot(first),
+                        dst,
+                    });
+                    ops.extend(rest.iter().map(|&s| XorOp::Xor { src: slot(s), dst }));
+                }
+            }
+        }
+
+        XorSchedule {
+            inputs,
+            outputs: rows.len(),
+            temps: temps.len(),
+            ops,
+        }
+    }
+
+    /// Number of input packets
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    /// Number of output packets
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    /// Number of temporary packets needed while applying the schedule
+    pub fn temporaries(&self) -> usize {
+        self.temps
+    }
+
+    /// The operations, in the order they are executed
+    pub fn ops(&self) -> &[XorOp] {
+        &self.ops
+    }
+
+    /// Number of packet XORs, the cost of the schedule
+    pub fn xor_count(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|op| matches!(op, XorOp::Xor { .. }))
+            .count()
+    }
+
+    /// Apply the schedule to packets
+    ///
+    /// Temporary packets are allocated as needed.
+    ///
+    /// # Panics
+    /// Panics if the number of inputs or outputs does not match the schedule, or if the packets
+    /// have different lengths.
+    pub fn apply(&self, inputs: &[&[u8]], outputs: &mut [&mut [u8]]) {
+        assert_eq!(inputs.len(), self.inputs, "Wrong number of inputs");
+        assert_eq!(outputs.len(), self.outputs, "Wrong number of outputs");
+        let len = inputs
+            .first()
+            .map(|p| p.len())
+            .or_else(|| outputs.first().map(|p| p.len()))
+            .unwrap_or(0);
+        assert!(
+            inputs.iter().all(|p| p.len() == len) && outputs.iter().all(|p| p.len() == len),
+            "Packets have different lengths"
+        );
+
+        let mut temps = vec![vec![0; len]; self.temps];
+        for op in &self.ops {
+            let (dst, src) = match *op {
+                XorOp::Zero { dst } => (dst, None),
+                XorOp::Copy { src, dst } | XorOp::Xor { src, dst } => (dst, Some(src)),
+            };
+            // Temporaries only depend on inputs and earlier temporaries
+            let (dst, src): (&mut [u8], Option<&[u8]>) = match (dst, src) {
+                (Slot::Temp(d), Some(Slot::Temp(s))) if s < d => {
+                    let (done, rest) = temps.split_at_mut(d);
+                    (&mut rest[0], Some(&done[s]))
+                }
+                (Slot::Temp(d), Some(Slot::Input(s))) => (&mut temps[d], Some(inputs[s])),
+                (Slot::Output(d), Some(Slot::Input(s))) => (&mut *outputs[d], Some(inputs[s])),
+                (Slot::Output(d), Some(Slot::Temp(s))) => (&mut *outputs[d], Some(&temps[s])),
+                (Slot::Output(d), None) => (&mut *outputs[d], None),
+                _ => unreachable!("Schedules write outputs from inputs and temporaries"),
+            };
+            match (op, src) {
+                (XorOp::Copy { .. }, Some(src)) => dst.copy_from_slice(src),
+                (XorOp::Xor { .. }, Some(src)) => {
+                    for (d, s) in dst.iter_mut().zip(src.iter()) {
+                        *d ^= s;
+                    }
+                }
+                _ => dst.iter_mut().for_each(|d| *d = 0),
+            }
+        }
+    }
+}
@@ -0,0 +1,329 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reed-Solomon erasure coding
+//!
+//! A [`ReedSolomon`] code splits data into k data shards and computes m parity shards. The
+//! original data can be recovered from any k of the k + m shards. The code is systematic: the
+//! data shards are stored unchanged, and each parity shard is a linear combination of the data
+//! shards.
+//!
+//! The code works with any [`GaloisField`]. The number of shards is limited by the field size, so
+//! GF(2^8) supports up to 256 shards and GF(2^16) up to 65536. Requires the `alloc` feature.
+//!
+//! ```rust
+//! use g2p::{g2p, erasure::ReedSolomon};
+//!
+//! g2p!(GF256, 8);
+//! # fn main() {
+//! let rs = ReedSolomon::<GF256>::new(3, 2).unwrap();
+//! let mut shards: Vec<Vec<GF256>> = vec![
+//!     vec![0.into(), 1.into(), 2.into()],
+//!     vec![3.into(), 4.into(), 5.into()],
+//!     vec![6.into(), 7.into(), 8.into()],
+//!     vec![0.into(); 3],
+//!     vec![0.into(); 3],
+//! ];
+//! rs.encode(&mut shards).unwrap();
+//! assert!(rs.verify(&shards).unwrap());
+//!
+//! // Lose any two shards
+//! let mut received: Vec<Option<Vec<GF256>>> = shards.iter().cloned().map(Some).collect();
+//! received[0] = None;
+//! received[4] = None;
+//! rs.reconstruct(&mut received).unwrap();
+//! assert_eq!(received[0].as_ref(), Some(&shards[0]));
+//! assert_eq!(received[4].as_ref(), Some(&shards[4]));
+//! # }
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::{convert::TryFrom, fmt};
+
+use crate::{matrix::Matrix, GaloisField};
+
+/// Errors returned by [`ReedSolomon`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ErasureError {
+    /// The code needs at least one data and one parity shard
+    ZeroShards,
+    /// The field has fewer elements than the total number of shards
+    TooManyShards,
+    /// The number of shards passed does not match the code
+    WrongShardCount,
+    /// The shards have no elements
+    EmptyShard,
+    /// The shards have different lengths
+    ShardSizeMismatch,
+    /// Fewer than k shards are present, so the data can not be recovered
+    TooFewShards,
+}
+
+impl fmt::Display for ErasureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErasureError::ZeroShards => write!(f, "Need at least one data and parity shard"),
+            ErasureError::TooManyShards => write!(f, "Too many shards for the field size"),
+            ErasureError::WrongShardCount => write!(f, "Wrong number of shards"),
+            ErasureError::EmptyShard => write!(f, "Shards must not be empty"),
+            ErasureError::ShardSizeMismatch => write!(f, "Shards have different lengths"),
+            ErasureError::TooFewShards => write!(f, "Too few shards present to reconstruct"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ErasureError {}
+
+/// Systematic Reed-Solomon code with k data and m parity shards
+///
+/// The parity shards are calculated by multiplying the data with an m x k matrix. The full
+/// (k + m) x k encoding matrix consists of the identity and the parity rows. Any k of its rows
+/// form an invertible k x k matrix, which makes the code maximum distance separable.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReedSolomon<F> {
+    data_shards: usize,
+    parity_shards: usize,
+    parity: Matrix<F>,
+}
+
+impl<F: GaloisField> ReedSolomon<F> {
+    /// Create a code based on a Vandermonde matrix
+    ///
+    /// The encoding matrix is derived from the (k + m) x k Vandermonde matrix over the points
+    /// 0, 1, 2, ... by multiplying it with the inverse of its top k rows. This is the construction
+    /// used by most Reed-Solomon erasure libraries.
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self, ErasureError> {
+        check_shards::<F>(data_shards, parity_shards)?;
+        let (k, n) = (data_shards, data_shards + parity_shards);
+
+        let points: Vec<F> = (0..n).map(element).collect();
+        let vandermonde = Matrix::vandermonde(&points, k);
+        let top_rows: Vec<usize> = (0..k).collect();
+        let parity_rows: Vec<usize> = (k..n).collect();
+        let top_inv = vandermonde
+            .select_rows(&top_rows)
+            .inverse()
+            .expect("Vandermonde matrix is invertible");
+        let parity = &vandermonde.select_rows(&parity_rows) * &top_inv;
+        Ok(ReedSolomon {
+            data_shards,
+            parity_shards,
+            parity,
+        })
+    }
+
+    /// Create a code based on a Cauchy matrix
+    ///
+    /// The entry in row i and column j of the parity matrix is 1 / (x_i + y_j), with
+    /// x_i = i and y_j = m + j. It is cheaper to construct than [`new`](Self::new), but produces
+    /// different parity shards.
+    pub fn cauchy(data_shards: usize, parity_shards: usize) -> Result<Self, ErasureError> {
+        check_shards::<F>(data_shards, parity_shards)?;
+
+        let xs: Vec<F> = (0..parity_shards).map(element).collect();
+        let ys: Vec<F> = (parity_shards..parity_shards + data_shards)
+            .map(element)
+            .collect();
+        let parity = Matrix::cauchy(&xs, &ys).expect("Points are distinct");
+        Ok(ReedSolomon {
+            data_shards,
+            parity_shards,
+            parity,
+        })
+    }
+
+    /// Number of data shards k
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    /// Number of parity shards m
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    /// Total number of shards k + m
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    /// The coefficient of data shard `data` in parity shard `parity`
+    ///
+    /// # Panics
+    /// Panics if one of the indices is out of range.
+    pub fn coefficient(&self, parity: usize, data: usize) -> F {
+        assert!(parity < self.parity_shards && data < self.data_shards);
+        self.parity[(parity, data)]
+    }
+
+    /// The m x k matrix mapping the data shards to the parity shards
+    pub fn parity_matrix(&self) -> &Matrix<F> {
+        &self.parity
+    }
+
+    /// Calculate the parity shards
+    ///
+    /// `shards` contains the k data shards followed by the m parity shards, which are
+    /// overwritten.
+    pub fn encode<S>(&self, shards: &mut [S]) -> Result<(), ErasureError>
+    where
+        S: AsRef<[F]> + AsMut<[F]>,
+    {
+        if shards.len() != self.total_shards() {
+            return Err(ErasureError::WrongShardCount);
+        }
+        check_sizes(shards.iter().map(|s| s.as_ref().len()))?;
+
+        let (data, parity) = shards.split_at_mut(self.data_shards);
+        for (coeffs, out) in self
+            .parity
+            .as_slice()
+            .chunks(self.data_shards)
+            .zip(parity.iter_mut())
+        {
+            let out = out.as_mut();
+            out.iter_mut().for_each(|x| *x = F::ZERO);
+            for (&c, d) in coeffs.iter().zip(data.iter()) {
+                F::mul_add_slice(out, d.as_ref(), c);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the parity shards match the data shards
+    pub fn verify<S: AsRef<[F]>>(&self, shards: &[S]) -> Result<bool, ErasureError> {
+        if shards.len() != self.total_shards() {
+            return Err(ErasureError::WrongShardCount);
+        }
+        let len = check_sizes(shards.iter().map(|s| s.as_ref().len()))?;
+
+        let (data, parity) = shards.split_at(self.data_shards);
+        let mut buf = vec![F::ZERO; len];
+        for (coeffs, expected) in self
+            .parity
+            .as_slice()
+            .chunks(self.data_shards)
+            .zip(parity.iter())
+        {
+            buf.iter_mut().for_each(|x| *x = F::ZERO);
+            for (&c, d) in coeffs.iter().zip(data.iter()) {
+                F::mul_add_slice(&mut buf, d.as_ref(), c);
+            }
+            if buf[..] != *expected.as_ref() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Recover all missing shards
+    ///
+    /// Missing shards are `None`. At least k shards must be present.
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<F>>]) -> Result<(), ErasureError> {
+        self.reconstruct_impl(shards, true)
+    }
+
+    /// Recover the missing data shards only
+    ///
+    /// Missing parity shards stay `None`. At least k shards must be present.
+    pub fn reconstruct_data(&self, shards: &mut [Option<Vec<F>>]) -> Result<(), ErasureError> {
+        self.reconstruct_impl(shards, false)
+    }
+
+    fn reconstruct_impl(
+        &self,
+        shards: &mut [Option<Vec<F>>],
+        parity: bool,
+    ) -> Result<(), ErasureError> {
+        let k = self.data_shards;
+        if shards.len() != self.total_shards() {
+            return Err(ErasureError::WrongShardCount);
+        }
+        let len = check_sizes(shards.iter().flatten().map(|s| s.len()))?;
+
+        let present: Vec<usize> = (0..shards.len())
+            .filter(|&i| shards[i].is_some())
+            .take(k)
+            .collect();
+        if present.len() < k {
+            return Err(ErasureError::TooFewShards);
+        }
+
+        if shards[..k].iter().any(Option::is_none) {
+            // The rows of the encoding matrix for the present shards map the data to them, so
+            // the inverse maps them back to the data.
+            let decode = Matrix::identity(k)
+                .stack(&self.parity)
+                .select_rows(&present)
+                .inverse()
+                .expect("Submatrices of the encoding matrix are invertible");
+
+            for d in 0..k {
+                if shards[d].is_some() {
+                    continue;
+                }
+                let mut out = vec![F::ZERO; len];
+                for (&c, &i) in decode.row(d).iter().zip(present.iter()) {
+                    let src = shards[i].as_ref().expect("Shard is present");
+                    F::mul_add_slice(&mut out, src, c);
+                }
+                shards[d] = Some(out);
+            }
+        }
+
+        if parity {
+            let (data, parity_shards) = shards.split_at_mut(k);
+            for (coeffs, out) in self
+                .parity
+                .as_slice()
+                .chunks(k)
+                .zip(parity_shards.iter_mut())
+            {
+                if out.is_some() {
+                    continue;
+                }
+                let mut buf = vec![F::ZERO; len];
+                for (&c, d) in coeffs.iter().zip(data.iter()) {
+                    F::mul_add_slice(&mut buf, d.as_ref().expect("Data is recovered"), c);
+                }
+                *out = Some(buf);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_shards<F: GaloisField>(data: usize, parity: usize) -> Result<(), ErasureError> {
+    if data == 0 || parity == 0 {
+        return Err(ErasureError::ZeroShards);
+    }
+    match data.checked_add(parity) {
+        Some(n) if n <= F::SIZE => Ok(()),
+        _ => Err(ErasureError::TooManyShards),
+    }
+}
+
+/// Check that all shards have the same, non-zero length
+pub(crate) fn check_sizes(mut lengths: impl Iterator<Item = usize>) -> Result<usize, ErasureError> {
+    let len = lengths.next().unwrap_or(0);
+    if len == 0 {
+        return Err(ErasureError::EmptyShard);
+    }
+    if lengths.any(|l| l != len) {
+        return Err(ErasureError::ShardSizeMismatch);
+    }
+    Ok(len)
+}
+
+/// The element represented by `i`
+pub(crate) fn element<F: GaloisField>(i: usize) -> F {
+    u32::try_from(i)
+        .ok()
+        .and_then(|v| F::Repr::try_from(v).ok())
+        .and_then(F::from_repr)
+        .expect("Index is smaller than the field size")
+}
@@ -0,0 +1,473 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Extensions of finite fields
+//!
+//! [`Extension<F, N, M>`](Extension) is the field GF((2^m)^N): polynomials of degree < N with
+//! coefficients in a field F with 2^m elements, modulo an irreducible polynomial of degree N over
+//! F. Such composite fields are used for example for compact AES S-box implementations, which
+//! compute in GF((2^4)^2) instead of GF(2^8).
+//!
+//! The modulus is given by a type implementing [`ExtensionModulus`]. It also has to provide a
+//! generator and the minimal polynomial of the generator over GF(2), which are required by
+//! [`GaloisField`]. These can be computed once with [`Extension::find_parameters`].
+//!
+//! ```rust
+//! use g2p::{g2p, GaloisField, G2Poly, extension::{Extension, ExtensionModulus}};
+//!
+//! g2p!(GF16, 4, modulus: 0b1_0011);
+//!
+//! // y^2 + y + 8 is irreducible over GF16
+//! struct Modulus;
+//! impl ExtensionModulus<GF16, 2> for Modulus {
+//!     const COEFFS: [GF16; 2] = [GF16(8), GF16(1)];
+//!     const GENERATOR: [GF16; 2] = [GF16(2), GF16(1)];
+//!     const MODULUS: G2Poly = G2Poly(0b1_0110_1001);
+//! }
+//!
+//! type GF16x2 = Extension<GF16, 2, Modulus>;
+//! # fn main() {
+//! assert_eq!(GF16x2::check(), Ok(()));
+//!
+//! let a = GF16x2::new([GF16(3), GF16(7)]);
+//! assert_eq!(a * a.inverse(), GF16x2::ONE);
+//! assert_eq!(GF16x2::SIZE, 256);
+//! # }
+//! ```
+
+use core::{
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::{Product, Sum},
+    marker::PhantomData,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use crate::{G2Poly, GaloisField};
+
+/// The irreducible polynomial defining an [`Extension`]
+pub trait ExtensionModulus<F: GaloisField, const N: usize>: 'static {
+    /// Lower coefficients of the monic modulus
+    ///
+    /// The modulus is y^N + `COEFFS[N - 1]`·y^(N - 1) + ... + `COEFFS[0]`.
+    const COEFFS: [F; N];
+
+    /// Coefficients of a generator of the multiplicative group
+    const GENERATOR: [F; N];
+
+    /// Minimal polynomial of the generator over GF(2)
+    ///
+    /// This is the modulus of an isomorphic field using the polynomial representation over GF(2).
+    const MODULUS: G2Poly;
+}
+
+/// Errors found by [`Extension::check`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ExtensionError {
+    /// The field has more than 2^32 elements, so it can not be represented by `u32`
+    TooLarge,
+    /// The generator does not generate the multiplicative group, or the modulus is reducible
+    NotPrimitive,
+    /// `MODULUS` is not the minimal polynomial of the generator
+    WrongMinimalPolynomial,
+}
+
+impl fmt::Display for ExtensionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtensionError::TooLarge => write!(f, "Extension has more than 32 bits"),
+            ExtensionError::NotPrimitive => {
+                write!(f, "Generator is not primitive or modulus is reducible")
+            }
+            ExtensionError::WrongMinimalPolynomial => {
+                write!(f, "MODULUS is not the minimal polynomial of the generator")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ExtensionError {}
+
+/// The field GF((2^m)^N) over a field F with 2^m elements
+///
+/// Elements are polynomials of degree < N over F, stored as coefficients, lowest degree first.
+/// The field has at most 2^32 elements, larger extensions fail to compile when they are used:
+///
+/// ```compile_fail
+/// use g2p::{g2p, GaloisField, G2Poly, extension::{Extension, ExtensionModulus}};
+///
+/// g2p!(GF65536, 16);
+///
+/// struct Modulus;
+/// impl ExtensionModulus<GF65536, 3> for Modulus {
+///     const COEFFS: [GF65536; 3] = [GF65536(1), GF65536(0), GF65536(1)];
+///     const GENERATOR: [GF65536; 3] = [GF65536(0), GF65536(1), GF65536(0)];
+///     const MODULUS: G2Poly = G2Poly(0);
+/// }
+///
+/// # fn main() {
+/// let _ = Extension::<GF65536, 3, Modulus>::ZERO;
+/// # }
+/// ```
+pub struct Extension<F, const N: usize, M> {
+    coeffs: [F; N],
+    modulus: PhantomData<fn() -> M>,
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> Extension<F, N, M> {
+    // Elements are represented by u32, this is evaluated by the constants and the representation
+    // conversions to reject larger extensions at compile time.
+    const FITS_REPR: () = assert!(
+        F::DEGREE as usize * N <= 32,
+        "Extension has more than 32 bits"
+    );
+
+    /// Create an element from its coefficients, lowest degree first
+    pub const fn new(coeffs: [F; N]) -> Self {
+        Extension {
+            coeffs,
+            modulus: PhantomData,
+        }
+    }
+
+    /// Embed an element of the base field
+    pub fn from_base(c: F) -> Self {
+        let mut coeffs = [F::ZERO; N];
+        coeffs[0] = c;
+        Self::new(coeffs)
+    }
+
+    /// The coefficients of the element, lowest degree first
+    pub fn coeffs(&self) -> [F; N] {
+        self.coeffs
+    }
+
+    /// Multiply with an element of the base field
+    pub fn scale(self, c: F) -> Self {
+        let mut coeffs = self.coeffs;
+        for x in coeffs.iter_mut() {
+            *x *= c;
+        }
+        Self::new(coeffs)
+    }
+
+    /// Calculate the norm relative to the base field
+    ///
+    /// This is the product of all conjugates x·x^q·x^(q^2)·...·x^(q^(N - 1)), with q = 2^m.
+    /// The result is always an element of the base field.
+    pub fn relative_norm(self) -> F {
+        (self * self.conjugate_product()).coeffs[0]
+    }
+
+    /// Product of the conjugates x^q·x^(q^2)·...·x^(q^(N - 1))
+    fn conjugate_product(self) -> Self {
+        let mut res = Self::ONE;
+        let mut conjugate = self;
+        for _ in 1..N {
+            conjugate = conjugate.frobenius(F::DEGREE);
+            res *= conjugate;
+        }
+        res
+    }
+
+    /// Verify the parameters given by the modulus `M`
+    ///
+    /// Checks that the field fits into 32 bits, that `GENERATOR` generates the multiplicative
+    /// group and that `MODULUS` is its minimal polynomial. A generator can only exist if the
+    /// modulus is irreducible, so this also checks `COEFFS`.
+    pub fn check() -> Result<(), ExtensionError> {
+        if Self::DEGREE > 32 {
+            return Err(ExtensionError::TooLarge);
+        }
+        let g = Self::GENERATOR;
+        if !is_primitive(g) {
+            return Err(ExtensionError::NotPrimitive);
+        }
+        let modulus = M::MODULUS;
+        if modulus.degree() != Some(u64::from(Self::DEGREE))
+            || !modulus.is_irreducible()
+            || !eval_g2poly(modulus, g).is_zero()
+        {
+            return Err(ExtensionError::WrongMinimalPolynomial);
+        }
+        Ok(())
+    }
+
+    /// Find a generator and its minimal polynomial for the modulus `M`
+    ///
+    /// Only `M::COEFFS` is used, so this can be called with placeholder values for the other
+    /// constants. Returns `None` if the modulus is reducible or the field is larger than 32 bits.
+    pub fn find_parameters() -> Option<([F; N], G2Poly)> {
+        if Self::DEGREE > 32 {
+            return None;
+        }
+        let size = 1_u64 << Self::DEGREE;
+        let g = (2..size)
+            .filter_map(|repr| Self::from_repr(repr as u32))
+            .find(|&g| is_primitive(g))?;
+        Some((g.coeffs, minimal_polynomial(g)))
+    }
+}
+
+/// Check if g has order 2^p - 1
+fn is_primitive<E: GaloisField>(g: E) -> bool {
+    let order = (1_u64 << E::DEGREE) - 1;
+    if g.pow(order as usize) != E::ONE {
+        return false;
+    }
+
+    // Trial division is fast enough for 2^32 - 1
+    let mut rest = order;
+    let mut factor = 2;
+    while factor * factor <= rest {
+        if rest % factor == 0 {
+            if g.pow((order / factor) as usize) == E::ONE {
+                return false;
+            }
+            while rest % factor == 0 {
+                rest /= factor;
+            }
+        }
+        factor += 1;
+    }
+    rest <= 1 || g.pow((order / rest) as usize) != E::ONE
+}
+
+/// Evaluate a polynomial over GF(2) at x
+fn eval_g2poly<E: GaloisField>(poly: G2Poly, x: E) -> E {
+    let mut res = E::ZERO;
+    for i in (0..64).rev() {
+        res *= x;
+        if (poly.0 >> i) & 1 == 1 {
+            res += E::ONE;
+        }
+    }
+    res
+}
+
+/// Find the minimal polynomial of x over GF(2)
+///
+/// The powers 1, x, x^2, ... are reduced against the previous ones until one of them is linearly
+/// dependent. The powers combined to reach zero give the minimal polynomial. For a generator,
+/// this happens at x^p.
+pub(crate) fn minimal_polynomial<E: GaloisField>(x: E) -> G2Poly {
+    // Reduced powers indexed by their lowest set bit, with the powers of x combined to get them
+    let mut basis = [(0_u32, 0_u64); 32];
+    let mut power = E::ONE;
+    for i in 0..=E::DEGREE {
+        let mut bits: u32 = power.to_repr().into();
+        let mut combination = 1_u64 << i;
+        while bits != 0 {
+            let (row, row_combination) = basis[bits.trailing_zeros() as usize];
+            if row == 0 {
+                basis[bits.trailing_zeros() as usize] = (bits, combination);
+                break;
+            }
+            bits ^= row;
+            combination ^= row_combination;
+        }
+        if bits == 0 {
+            return G2Poly(combination);
+        }
+        power *= x;
+    }
+    unreachable!("The powers of x span a space of dimension at most p")
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> GaloisField for Extension<F, N, M> {
+    const SIZE: usize = F::SIZE.pow(N as u32);
+    const ZERO: Self = {
+        let () = Self::FITS_REPR;
+        Self::new([F::ZERO; N])
+    };
+    const ONE: Self = {
+        let () = Self::FITS_REPR;
+        let mut coeffs = [F::ZERO; N];
+        coeffs[0] = F::ONE;
+        Self::new(coeffs)
+    };
+    const GENERATOR: Self = Self::new(M::GENERATOR);
+    const MODULUS: G2Poly = M::MODULUS;
+
+    type Repr = u32;
+    const DEGREE: u32 = F::DEGREE * N as u32;
+
+    fn to_repr(self) -> u32 {
+        let () = Self::FITS_REPR;
+        let mut res = 0;
+        for (i, c) in self.coeffs.iter().enumerate() {
+            let c: u32 = c.to_repr().into();
+            res |= c << (i as u32 * F::DEGREE);
+        }
+        res
+    }
+
+    fn from_repr(repr: u32) -> Option<Self> {
+        let () = Self::FITS_REPR;
+        if Self::DEGREE < 32 && repr >> Self::DEGREE != 0 {
+            return None;
+        }
+        let mask = (1_u64 << F::DEGREE) - 1;
+        let mut coeffs = [F::ZERO; N];
+        for (i, c) in coeffs.iter_mut().enumerate() {
+            let bits = (u64::from(repr) >> (i as u32 * F::DEGREE)) & mask;
+            *c = F::from_repr(F::Repr::try_from(bits as u32).ok()?)?;
+        }
+        Some(Self::new(coeffs))
+    }
+
+    fn is_zero(self) -> bool {
+        self.coeffs.iter().all(|c| c.is_zero())
+    }
+
+    fn inverse(self) -> Self {
+        // x^-1 = (x^q·...·x^(q^(N - 1))) / N(x), where the norm N(x) is in the base field
+        let conjugates = self.conjugate_product();
+        let norm = (self * conjugates).coeffs[0];
+        conjugates.scale(norm.inverse())
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> Mul for Extension<F, N, M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let (a, b) = (&self.coeffs, &rhs.coeffs);
+        // Product of degree 2N - 2, coefficient k is stored in prod[k / N][k % N]
+        let mut prod = [[F::ZERO; N]; 2];
+        let mut add_to = |k: usize, v: F| prod[k / N][k % N] += v;
+
+        // Karatsuba-like multiplication with N(N + 1)/2 base field multiplications:
+        // a_i·b_j + a_j·b_i = (a_i + a_j)(b_i + b_j) - a_i·b_i - a_j·b_j
+        let mut diag = [F::ZERO; N];
+        for i in 0..N {
+            diag[i] = a[i] * b[i];
+            add_to(2 * i, diag[i]);
+        }
+        for i in 0..N {
+            for j in i + 1..N {
+                add_to(i + j, (a[i] + a[j]) * (b[i] + b[j]) - diag[i] - diag[j]);
+            }
+        }
+
+        // Reduce using y^N = COEFFS[N - 1]·y^(N - 1) + ... + COEFFS[0]
+        for k in (N..2 * N - 1).rev() {
+            let top = prod[1][k - N];
+            for (i, &c) in M::COEFFS.iter().enumerate() {
+                let idx = k - N + i;
+                prod[idx / N][idx % N] += top * c;
+            }
+        }
+        Self::new(prod[0])
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> MulAssign for Extension<F, N, M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> Product for Extension<F, N, M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, Mul::mul)
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> Div for Extension<F, N, M> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        if rhs.is_zero() {
+            panic!("Division by 0 in Extension");
+        }
+        self * rhs.inverse()
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> DivAssign for Extension<F, N, M> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> Add for Extension<F, N, M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let mut coeffs = self.coeffs;
+        for (c, &r) in coeffs.iter_mut().zip(rhs.coeffs.iter()) {
+            *c += r;
+        }
+        Self::new(coeffs)
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> AddAssign for Extension<F, N, M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> Sum for Extension<F, N, M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> Sub for Extension<F, N, M> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        self + rhs
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> SubAssign for Extension<F, N, M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<F: GaloisField, const N: usize, M: ExtensionModulus<F, N>> Neg for Extension<F, N, M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+impl<F: Copy, const N: usize, M> Clone for Extension<F, N, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: Copy, const N: usize, M> Copy for Extension<F, N, M> {}
+
+impl<F: PartialEq, const N: usize, M> PartialEq for Extension<F, N, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.coeffs == other.coeffs
+    }
+}
+
+impl<F: Eq, const N: usize, M> Eq for Extension<F, N, M> {}
+
+impl<F: Hash, const N: usize, M> Hash for Extension<F, N, M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.coeffs.hash(state)
+    }
+}
+
+impl<F: fmt::Debug, const N: usize, M> fmt::Debug for Extension<F, N, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Extension").field(&self.coeffs).finish()
+    }
+}
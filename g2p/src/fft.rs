@@ -0,0 +1,509 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Additive FFT over binary fields
+//!
+//! The multiplicative group of GF(2^p) has odd order, so the classic FFT does not apply. The
+//! additive FFT by Lin, Chung and Han evaluates a polynomial on the points of an affine subspace
+//! instead. Let ω_i be the element represented by `i`, so ω_i + ω_j = ω_(i ^ j), and let
+//! W_j(x) = ∏(x - ω_a) for a < 2^j be the subspace vanishing polynomials, normalized to
+//! Ŵ_j(x) = W_j(x) / W_j(ω_(2^j)). Polynomials are written in the novel basis
+//! X_i(x) = ∏ Ŵ_j(x) over the set bits j of i. In this basis, a polynomial of degree < 2^r is
+//! evaluated on the 2^r points ω_(s + u) for u < 2^r with O(r 2^r) operations.
+//!
+//! [`FftReedSolomon`] builds an erasure code on top of the transform, similar to Leopard-RS. It
+//! encodes and decodes in O(n log n) per symbol, which makes codes with thousands of shards over
+//! GF(2^16) practical. Requires the `alloc` feature.
+//!
+//! ```rust
+//! use g2p::{g2p, fft::AdditiveFft, GaloisField};
+//!
+//! g2p!(GF256, 8);
+//! # fn main() {
+//! let fft = AdditiveFft::<GF256>::new(3).unwrap();
+//! // 5 + 7·X_1(x) = 5 + 7x
+//! let mut data = [5, 7, 0, 0, 0, 0, 0, 0].map(GF256::from);
+//! fft.fft(&mut data, 8);
+//! for (u, &y) in data.iter().enumerate() {
+//!     assert_eq!(y, GF256::from(5) + GF256::from(7) * GF256::from(8 + u as u8));
+//! }
+//! fft.ifft(&mut data, 8);
+//! assert_eq!(data, [5, 7, 0, 0, 0, 0, 0, 0].map(GF256::from));
+//! # }
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    erasure::{check_sizes, element, ErasureError},
+    GaloisField,
+};
+
+/// Tables for the additive FFT of size up to 2^r
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AdditiveFft<F> {
+    log_size: u32,
+    // basis[j][i] = Ŵ_j(ω_(2^i)), zero for i < j
+    basis: Vec<Vec<F>>,
+    // Ŵ_j'(x), which is constant
+    derivative: Vec<F>,
+}
+
+impl<F: GaloisField> AdditiveFft<F> {
+    /// Create the tables for transforms of up to 2^`log_size` points
+    ///
+    /// Returns `None` if the field has fewer than 2^`log_size` elements.
+    pub fn new(log_size: u32) -> Option<Self> {
+        if log_size > F::DEGREE {
+            return None;
+        }
+        let p = F::DEGREE as usize;
+        let r = log_size as usize;
+
+        // values[i] = W_j(ω_(2^i)), using W_(j+1)(x) = W_j(x) (W_j(x) + W_j(ω_(2^j)))
+        let mut values: Vec<F> = (0..p).map(|i| element(1 << i)).collect();
+        // Coefficient of x in W_j(x), which is its derivative
+        let mut linear = F::ONE;
+        let mut basis = Vec::with_capacity(r);
+        let mut derivative = Vec::with_capacity(r);
+        for j in 0..r {
+            let norm = values[j];
+            let inv = norm.inverse();
+            basis.push(values.iter().map(|&v| v * inv).collect());
+            derivative.push(linear * inv);
+
+            for v in values.iter_mut() {
+                *v *= *v + norm;
+            }
+            linear *= norm;
+        }
+
+        Some(AdditiveFft {
+            log_size,
+            basis,
+            derivative,
+        })
+    }
+
+    /// The maximum number of points
+    pub fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    /// Evaluate Ŵ_j at ω_t
+    fn w_hat(&self, j: usize, t: usize) -> F {
+        self.basis[j]
+            .iter()
+            .enumerate()
+            .skip(j)
+            .filter(|&(i, _)| (t >> i) & 1 == 1)
+            .fold(F::ZERO, |acc, (_, &v)| acc + v)
+    }
+
+    /// Evaluate a polynomial given in the novel basis at the points ω_(shift + u)
+    ///
+    /// `data` holds the coefficients and is replaced with the values. Its length must be a power
+    /// of two not larger than [`size`](Self::size), and `shift` a multiple of it.
+    ///
+    /// # Panics
+    /// Panics if the length or shift is invalid.
+    pub fn fft(&self, data: &mut [F], shift: usize) {
+        self.fft_impl(data, shift);
+    }
+
+    /// Interpolate the values at the points ω_(shift + u), inverting [`fft`](Self::fft)
+    ///
+    /// # Panics
+    /// Panics if the length or shift is invalid.
+    pub fn ifft(&self, data: &mut [F], shift: usize) {
+        self.ifft_impl(data, shift);
+    }
+
+    /// Calculate the formal derivative of a polynomial given in the novel basis
+    ///
+    /// # Panics
+    /// Panics if the length of `data` is not a power of two or larger than
+    /// [`size`](Self::size).
+    pub fn formal_derivative(&self, data: &mut [F]) {
+        self.derivative_impl(data);
+    }
+
+    /// Apply [`fft`](Self::fft) to shards, treating each shard position independently
+    ///
+    /// # Panics
+    /// Panics if the length or shift is invalid, or the shards have different lengths.
+    pub fn fft_shards(&self, data: &mut [Vec<F>], shift: usize) {
+        self.fft_impl(data, shift);
+    }
+
+    /// Apply [`ifft`](Self::ifft) to shards, treating each shard position independently
+    ///
+    /// # Panics
+    /// Panics if the length or shift is invalid, or the shards have different lengths.
+    pub fn ifft_shards(&self, data: &mut [Vec<F>], shift: usize) {
+        self.ifft_impl(data, shift);
+    }
+
+    /// Apply [`formal_derivative`](Self::formal_derivative) to shards
+    ///
+    /// # Panics
+    /// Panics if the length is invalid, or the shards have different lengths.
+    pub fn formal_derivative_shards(&self, data: &mut [Vec<F>]) {
+        self.derivative_impl(data);
+    }
+
+    fn check_len(&self, len: usize, shift: usize) {
+        assert!(
+            len.is_power_of_two() && len <= self.size(),
+            "Length must be a power of two up to the transform size"
+        );
+        assert_eq!(shift % len, 0, "Shift must be a multiple of the length");
+    }
+
+    fn fft_impl<L: Lane<F>>(&self, data: &mut [L], shift: usize) {
+        self.check_len(data.len(), shift);
+        let n = data.len();
+        let mut half = n / 2;
+        while half > 0 {
+            let j = half.trailing_zeros() as usize;
+            for start in (0..n).step_by(2 * half) {
+                let lambda = self.w_hat(j, shift ^ start);
+                let (lo, hi) = data[start..start + 2 * half].split_at_mut(half);
+                for (a, b) in lo.iter_mut().zip(hi.iter_mut()) {
+                    a.mul_add(b, lambda);
+                    b.add(a);
+                }
+            }
+            half /= 2;
+        }
+    }
+
+    fn ifft_impl<L: Lane<F>>(&self, data: &mut [L], shift: usize) {
+        self.check_len(data.len(), shift);
+        let n = data.len();
+        let mut half = 1;
+        while half < n {
+            let j = half.trailing_zeros() as usize;
+            for start in (0..n).step_by(2 * half) {
+                let lambda = self.w_hat(j, shift ^ start);
+                let (lo, hi) = data[start..start + 2 * half].split_at_mut(half);
+                for (a, b) in lo.iter_mut().zip(hi.iter_mut()) {
+                    b.add(a);
+                    a.mul_add(b, lambda);
+                }
+            }
+            half *= 2;
+        }
+    }
+
+    fn derivative_impl<L: Lane<F>>(&self, data: &mut [L]) {
+        self.check_len(data.len(), 0);
+        // X_i' is the sum of Ŵ_j' X_(i - 2^j) over the set bits j of i, so the new coefficient k
+        // depends on the old coefficients above k only.
+        let n = data.len();
+        for k in 0..n {
+            let (lo, hi) = data.split_at_mut(k + 1);
+            let dst = &mut lo[k];
+            dst.set_zero();
+            for (j, &c) in self.derivative.iter().enumerate() {
+                if (k >> j) & 1 == 0 && k + (1 << j) < n {
+                    dst.mul_add(&hi[(1 << j) - 1], c);
+                }
+            }
+        }
+    }
+}
+
+/// Element of a transform: a single value or a shard of values
+trait Lane<F> {
+    /// self += src * c
+    fn mul_add(&mut self, src: &Self, c: F);
+    /// self += src
+    fn add(&mut self, src: &Self);
+    /// self = 0
+    fn set_zero(&mut self);
+}
+
+impl<F: GaloisField> Lane<F> for F {
+    fn mul_add(&mut self, src: &Self, c: F) {
+        *self += *src * c;
+    }
+
+    fn add(&mut self, src: &Self) {
+        *self += *src;
+    }
+
+    fn set_zero(&mut self) {
+        *self = F::ZERO;
+    }
+}
+
+impl<F: GaloisField> Lane<F> for Vec<F> {
+    fn mul_add(&mut self, src: &Self, c: F) {
+        if !c.is_zero() {
+            F::mul_add_slice(self, src, c);
+        }
+    }
+
+    fn add(&mut self, src: &Self) {
+        F::add_slice(self, src);
+    }
+
+    fn set_zero(&mut self) {
+        self.iter_mut().for_each(|x| *x = F::ZERO);
+    }
+}
+
+/// Systematic Reed-Solomon erasure code using the additive FFT
+///
+/// Data shard i is the value of a polynomial of degree < K at ω_i, where K is k rounded up to a
+/// power of two and the points ω_k .. ω_K are fixed to zero. Parity shard i is the value at
+/// ω_(K + i). The data is recovered from any k shards, like for
+/// [`ReedSolomon`](crate::erasure::ReedSolomon), but the parity shards differ.
+///
+/// Encoding is an inverse and a forward FFT. Decoding multiplies with the erasure locator
+/// polynomial, whose values are found with a Walsh-Hadamard transform of discrete logarithms,
+/// and divides its formal derivative by the derivative of the locator. Setting up the logarithms
+/// takes time linear in the field size, so this is meant for fields up to about GF(2^20).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FftReedSolomon<F> {
+    data_shards: usize,
+    parity_shards: usize,
+    // Data shards rounded up to a power of two
+    data_size: usize,
+    // Total points of the decoding transform
+    size: usize,
+    fft: AdditiveFft<F>,
+    // Walsh-Hadamard transform of log(ω_i), modulo 2^p - 1
+    log_walsh: Vec<u64>,
+}
+
+impl<F: GaloisField> FftReedSolomon<F> {
+    /// Create a code with `data_shards` data and `parity_shards` parity shards
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self, ErasureError> {
+        if data_shards == 0 || parity_shards == 0 {
+            return Err(ErasureError::ZeroShards);
+        }
+        let data_size = data_shards.next_power_of_two();
+        let size = data_size
+            .checked_add(parity_shards)
+            .map(usize::next_power_of_two)
+            .filter(|&n| n <= F::SIZE)
+            .ok_or(ErasureError::TooManyShards)?;
+        let fft = AdditiveFft::new(size.trailing_zeros()).ok_or(ErasureError::TooManyShards)?;
+
+        // Discrete logarithms of ω_1 .. ω_(size - 1)
+        let mut log_walsh = vec![0; size];
+        let mut x = F::ONE;
+        for e in 0..(F::SIZE - 1) as u64 {
+            let repr: u32 = x.to_repr().into();
+            if let Some(l) = log_walsh.get_mut(repr as usize) {
+                *l = e;
+            }
+            x *= F::GENERATOR;
+        }
+        walsh_hadamard(&mut log_walsh, modulus::<F>());
+
+        Ok(FftReedSolomon {
+            data_shards,
+            parity_shards,
+            data_size,
+            size,
+            fft,
+            log_walsh,
+        })
+    }
+
+    /// Number of data shards k
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    /// Number of parity shards m
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    /// Total number of shards k + m
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    /// Calculate the parity shards
+    ///
+    /// `shards` contains the k data shards followed by the m parity shards, which are
+    /// overwritten.
+    pub fn encode<S>(&self, shards: &mut [S]) -> Result<(), ErasureError>
+    where
+        S: AsRef<[F]> + AsMut<[F]>,
+    {
+        if shards.len() != self.total_shards() {
+            return Err(ErasureError::WrongShardCount);
+        }
+        let len = check_sizes(shards.iter().map(|s| s.as_ref().len()))?;
+
+        let (data, parity) = shards.split_at_mut(self.data_shards);
+        let parity_values = self.parity_values(data.iter().map(|s| s.as_ref()), len);
+        for (out, values) in parity.iter_mut().zip(parity_values) {
+            out.as_mut().copy_from_slice(&values);
+        }
+        Ok(())
+    }
+
+    /// Check that the parity shards match the data shards
+    pub fn verify<S: AsRef<[F]>>(&self, shards: &[S]) -> Result<bool, ErasureError> {
+        if shards.len() != self.total_shards() {
+            return Err(ErasureError::WrongShardCount);
+        }
+        let len = check_sizes(shards.iter().map(|s| s.as_ref().len()))?;
+
+        let (data, parity) = shards.split_at(self.data_shards);
+        let parity_values = self.parity_values(data.iter().map(|s| s.as_ref()), len);
+        Ok(parity
+            .iter()
+            .zip(parity_values)
+            .all(|(p, values)| p.as_ref() == &values[..]))
+    }
+
+    /// Recover all missing shards
+    ///
+    /// Missing shards are `None`. At least k shards must be present.
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<F>>]) -> Result<(), ErasureError> {
+        self.reconstruct_impl(shards, true)
+    }
+
+    /// Recover the missing data shards only
+    ///
+    /// Missing parity shards stay `None`. At least k shards must be present.
+    pub fn reconstruct_data(&self, shards: &mut [Option<Vec<F>>]) -> Result<(), ErasureError> {
+        self.reconstruct_impl(shards, false)
+    }
+
+    /// Evaluate the polynomial through the data at the parity points
+    fn parity_values<'a, I>(&self, data: I, len: usize) -> Vec<Vec<F>>
+    where
+        I: Iterator<Item = &'a [F]>,
+        F: 'a,
+    {
+        let k = self.data_size;
+        let mut coeffs: Vec<Vec<F>> = data.map(|d| d.to_vec()).collect();
+        coeffs.resize(k, vec![F::ZERO; len]);
+        self.fft.ifft_shards(&mut coeffs, 0);
+
+        // Evaluate on the cosets following the data points until all parity is calculated
+        let mut res = Vec::with_capacity(self.parity_shards);
+        let mut shift = k;
+        while res.len() < self.parity_shards {
+            let mut values = coeffs.clone();
+            self.fft.fft_shards(&mut values, shift);
+            let needed = self.parity_shards - res.len();
+            res.extend(values.into_iter().take(needed));
+            shift += k;
+        }
+        res
+    }
+
+    /// Index of shard i in the transform
+    fn position(&self, i: usize) -> usize {
+        if i < self.data_shards {
+            i
+        } else {
+            self.data_size + i - self.data_shards
+        }
+    }
+
+    fn reconstruct_impl(
+        &self,
+        shards: &mut [Option<Vec<F>>],
+        parity: bool,
+    ) -> Result<(), ErasureError> {
+        if shards.len() != self.total_shards() {
+            return Err(ErasureError::WrongShardCount);
+        }
+        let len = check_sizes(shards.iter().flatten().map(|s| s.len()))?;
+        let present = shards.iter().filter(|s| s.is_some()).count();
+        if present < self.data_shards {
+            return Err(ErasureError::TooFewShards);
+        }
+        let wanted: Vec<usize> = (0..shards.len())
+            .filter(|&i| shards[i].is_none() && (parity || i < self.data_shards))
+            .collect();
+        if wanted.is_empty() {
+            return Ok(());
+        }
+
+        // Erased points: missing shards and the unused points after the parity
+        let n = self.size;
+        let mut erased = vec![0_u64; n];
+        for i in (0..shards.len()).filter(|&i| shards[i].is_none()) {
+            erased[self.position(i)] = 1;
+        }
+        for e in erased[self.data_size + self.parity_shards..].iter_mut() {
+            *e = 1;
+        }
+
+        // log(Π(ω_i)) for known points and log(Π'(ω_i)) for erased points, where Π is the
+        // erasure locator. Both are the XOR convolution of the erasures with the logarithms.
+        let q = modulus::<F>();
+        walsh_hadamard(&mut erased, q);
+        for (e, &l) in erased.iter_mut().zip(self.log_walsh.iter()) {
+            *e = mul_mod(*e, l, q);
+        }
+        walsh_hadamard(&mut erased, q);
+        // Undo the scaling by n = 2^r with 2^-r = 2^(p - r) mod 2^p - 1
+        let scale = F::DEGREE - n.trailing_zeros();
+        let locator_logs: Vec<u64> = erased.iter().map(|&e| mul_mod(e, 1 << scale, q)).collect();
+
+        // Values of Π(x) D(x) on all points
+        let mut work = vec![vec![F::ZERO; len]; n];
+        for (i, shard) in shards.iter().enumerate() {
+            if let Some(s) = shard {
+                let pos = self.position(i);
+                let factor = F::GENERATOR.pow(locator_logs[pos] as usize);
+                F::mul_slice(&mut work[pos], s, factor);
+            }
+        }
+
+        self.fft.ifft_shards(&mut work, 0);
+        self.fft.formal_derivative_shards(&mut work);
+        self.fft.fft_shards(&mut work, 0);
+
+        // D(ω_e) = (Π D)'(ω_e) / Π'(ω_e) at the erased points
+        for i in wanted {
+            let pos = self.position(i);
+            let factor = F::GENERATOR.pow(((q - locator_logs[pos]) % q) as usize);
+            let mut out = core::mem::take(&mut work[pos]);
+            out.iter_mut().for_each(|x| *x *= factor);
+            shards[i] = Some(out);
+        }
+        Ok(())
+    }
+}
+
+/// Order of the multiplicative group
+fn modulus<F: GaloisField>() -> u64 {
+    F::SIZE as u64 - 1
+}
+
+fn mul_mod(a: u64, b: u64, q: u64) -> u64 {
+    ((u128::from(a) * u128::from(b)) % u128::from(q.max(1))) as u64
+}
+
+/// Walsh-Hadamard transform modulo q, without normalization
+fn walsh_hadamard(data: &mut [u64], q: u64) {
+    let q = q.max(1);
+    let mut half = 1;
+    while half < data.len() {
+        for start in (0..data.len()).step_by(2 * half) {
+            for i in start..start + half {
+                let (a, b) = (data[i], data[i + half]);
+                data[i] = (a + b) % q;
+                data[i + half] = (a + q - b) % q;
+            }
+        }
+        half *= 2;
+    }
+}
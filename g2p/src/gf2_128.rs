@@ -0,0 +1,490 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The field GF(2^128) used by GHASH and POLYVAL
+//!
+//! [`GF2_128`] is the field with modulus x^128 + x^7 + x^2 + x + 1, far too large for the tables
+//! of `g2p!`. Bit i of the `u128` representation is the coefficient of x^i. Multiplication uses
+//! the carry-less multiplication instruction (`pclmulqdq`) on x86 when available, and a portable
+//! constant time fallback otherwise.
+//!
+//! The type can not implement [`GaloisField`](crate::GaloisField): its size, representation and
+//! modulus do not fit into `usize`, `u32` and [`G2Poly`](crate::G2Poly). It provides the same
+//! operations as inherent methods instead.
+//!
+//! AES-GCM and AES-GCM-SIV serialize elements differently:
+//! * GHASH (NIST SP 800-38D) reflects the bits: the most significant bit of the first byte is the
+//!   coefficient of x^0. See [`GF2_128::from_ghash_bytes`].
+//! * POLYVAL (RFC 8452) uses little endian bytes in the field with the reciprocal modulus
+//!   x^128 + x^127 + x^126 + x^121 + 1. Reversing the bit order maps this field onto
+//!   [`GF2_128`], see [`GF2_128::from_polyval_bytes`]. The POLYVAL product a·b·x^-128
+//!   becomes a·b·x under this mapping.
+//!
+//! [`Ghash`] and [`Polyval`] implement the universal hash functions on top of these conversions.
+//!
+//! ```rust
+//! use g2p::gf2_128::{Ghash, GF2_128};
+//!
+//! # fn main() {
+//! let a = GF2_128::from(0x1234_5678);
+//! assert_eq!(a * a.inverse(), GF2_128::ONE);
+//! assert_eq!(GF2_128::from_ghash_bytes(&a.to_ghash_bytes()), a);
+//!
+//! let h = [0x42; 16];
+//! let mut ghash = Ghash::new(&h);
+//! ghash.update_padded(b"additional data");
+//! let tag = ghash.finalize();
+//! assert_ne!(tag, [0; 16]);
+//! # }
+//! ```
+
+use core::{
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use crate::GF2;
+
+/// An element of GF(2^128) with modulus x^128 + x^7 + x^2 + x + 1
+#[derive(Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct GF2_128(pub u128);
+
+impl GF2_128 {
+    /// The value 0
+    pub const ZERO: Self = GF2_128(0);
+    /// The value 1
+    pub const ONE: Self = GF2_128(1);
+    /// The element x, which generates the multiplicative group
+    pub const GENERATOR: Self = GF2_128(2);
+    /// Degree of the field, which has 2^128 elements
+    pub const DEGREE: u32 = 128;
+    /// The modulus without the x^128 term
+    pub const MODULUS_LOW: u128 = 0x87;
+
+    /// Get the integer representing the value
+    ///
+    /// Bit i of the result is the coefficient of x^i.
+    pub const fn to_repr(self) -> u128 {
+        self.0
+    }
+
+    /// Create a value from its integer representation
+    pub const fn from_repr(repr: u128) -> Self {
+        GF2_128(repr)
+    }
+
+    /// Read a value from a block in the bit reflected GHASH convention
+    pub fn from_ghash_bytes(bytes: &[u8; 16]) -> Self {
+        GF2_128(u128::from_be_bytes(*bytes).reverse_bits())
+    }
+
+    /// Write the value as a block in the bit reflected GHASH convention
+    pub fn to_ghash_bytes(self) -> [u8; 16] {
+        self.0.reverse_bits().to_be_bytes()
+    }
+
+    /// Read a block in the POLYVAL convention
+    ///
+    /// The POLYVAL element with coefficients a_i maps to the sum of a_i·x^(127 - i).
+    pub fn from_polyval_bytes(bytes: &[u8; 16]) -> Self {
+        GF2_128(u128::from_le_bytes(*bytes).reverse_bits())
+    }
+
+    /// Write the value as a block in the POLYVAL convention
+    pub fn to_polyval_bytes(self) -> [u8; 16] {
+        self.0.reverse_bits().to_le_bytes()
+    }
+
+    /// Check if the value is zero
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Multiply with x
+    pub fn mul_x(self) -> Self {
+        let carry = (self.0 >> 127).wrapping_neg() & Self::MODULUS_LOW;
+        GF2_128((self.0 << 1) ^ carry)
+    }
+
+    /// Calculate the square of the value
+    pub fn square(self) -> Self {
+        self * self
+    }
+
+    /// Calculate the value to the power `exp`
+    pub fn pow(self, exp: u128) -> Self {
+        let mut val = Self::ONE;
+        for i in (0..128).rev() {
+            val = val.square();
+            if (exp >> i) & 1 == 1 {
+                val *= self;
+            }
+        }
+        val
+    }
+
+    /// Apply the Frobenius automorphism k times, i.e. calculate x^(2^k)
+    pub fn frobenius(self, k: u32) -> Self {
+        (0..k % 128).fold(self, |acc, _| acc.square())
+    }
+
+    /// Calculate the multiplicative inverse
+    ///
+    /// Zero is mapped to zero, like [`GaloisField::inverse`](crate::GaloisField::inverse).
+    pub fn inverse(self) -> Self {
+        // x^(2^128 - 2), using a fixed sequence of operations
+        let mut res = self;
+        for _ in 1..127 {
+            res = res.square() * self;
+        }
+        res.square()
+    }
+
+    /// Calculate the multiplicative inverse, or `None` if the value is zero
+    pub fn checked_inv(self) -> Option<Self> {
+        if self.is_zero() {
+            None
+        } else {
+            Some(self.inverse())
+        }
+    }
+
+    /// Divide by `rhs`, or return `None` if `rhs` is zero
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        rhs.checked_inv().map(|inv| self * inv)
+    }
+
+    /// Calculate the unique square root
+    pub fn sqrt(self) -> Self {
+        self.frobenius(127)
+    }
+
+    /// Calculate the absolute trace, the sum of all conjugates
+    pub fn trace(self) -> GF2 {
+        let mut acc = self;
+        let mut sum = self;
+        for _ in 1..128 {
+            acc = acc.square();
+            sum += acc;
+        }
+        GF2((sum.0 & 1) as u8)
+    }
+}
+
+/// Carry-less multiplication of two 64 bit polynomials
+fn clmul(a: u64, b: u64) -> u128 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if x86::has_pclmulqdq() {
+            // SAFETY: PCLMULQDQ support was checked above
+            return unsafe { x86::clmul_pclmul(a, b) };
+        }
+    }
+
+    #[allow(unreachable_code)]
+    clmul_portable(a, b)
+}
+
+/// Carry-less multiplication without data dependent branches or lookups
+#[inline(always)]
+fn clmul_portable(a: u64, b: u64) -> u128 {
+    let a = u128::from(a);
+    let mut res = 0;
+    for i in 0..64 {
+        let mask = u128::from((b >> i) & 1).wrapping_neg();
+        res ^= (a << i) & mask;
+    }
+    res
+}
+
+/// Reduce the 256 bit product hi·x^128 + lo
+#[inline(always)]
+fn reduce(hi: u128, lo: u128) -> u128 {
+    // x^128 = x^7 + x^2 + x + 1, the bits shifted out of hi are folded back once more
+    let overflow = (hi >> 127) ^ (hi >> 126) ^ (hi >> 121);
+    let folded = hi ^ overflow;
+    lo ^ folded ^ (folded << 1) ^ (folded << 2) ^ (folded << 7)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use core::arch::x86_64::*;
+
+    #[inline]
+    pub(super) fn has_pclmulqdq() -> bool {
+        #[cfg(feature = "std")]
+        {
+            std::is_x86_feature_detected!("pclmulqdq")
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            cfg!(target_feature = "pclmulqdq")
+        }
+    }
+
+    #[target_feature(enable = "pclmulqdq")]
+    pub(super) unsafe fn clmul_pclmul(a: u64, b: u64) -> u128 {
+        let a = _mm_set_epi64x(0, a as i64);
+        let b = _mm_set_epi64x(0, b as i64);
+        let mut out = [0_u8; 16];
+        _mm_storeu_si128(
+            out.as_mut_ptr() as *mut __m128i,
+            _mm_clmulepi64_si128(a, b, 0),
+        );
+        u128::from_le_bytes(out)
+    }
+}
+
+/// The GHASH universal hash function of AES-GCM
+///
+/// The hash of blocks X_1, ..., X_n is the sum of X_i·H^(n - i + 1).
+#[derive(Clone)]
+pub struct Ghash {
+    key: GF2_128,
+    acc: GF2_128,
+}
+
+impl Ghash {
+    /// Create a hasher with the key H, which is the encryption of the zero block in AES-GCM
+    pub fn new(key: &[u8; 16]) -> Self {
+        Ghash {
+            key: GF2_128::from_ghash_bytes(key),
+            acc: GF2_128::ZERO,
+        }
+    }
+
+    /// Process a single block
+    pub fn update_block(&mut self, block: &[u8; 16]) {
+        self.acc = (self.acc + GF2_128::from_ghash_bytes(block)) * self.key;
+    }
+
+    /// Process data, padding the last block with zeros
+    pub fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.update_block(&block);
+        }
+    }
+
+    /// Get the hash of the processed blocks
+    pub fn finalize(self) -> [u8; 16] {
+        self.acc.to_ghash_bytes()
+    }
+}
+
+impl fmt::Debug for Ghash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Do not print the key
+        f.debug_struct("Ghash").finish_non_exhaustive()
+    }
+}
+
+/// The POLYVAL universal hash function of AES-GCM-SIV (RFC 8452)
+#[derive(Clone)]
+pub struct Polyval {
+    key: GF2_128,
+    acc: GF2_128,
+}
+
+impl Polyval {
+    /// Create a hasher with the key H
+    pub fn new(key: &[u8; 16]) -> Self {
+        // dot(a, H) = a·H·x^-128 becomes a·H·x in the bit reversed field
+        Polyval {
+            key: GF2_128::from_polyval_bytes(key).mul_x(),
+            acc: GF2_128::ZERO,
+        }
+    }
+
+    /// Process a single block
+    pub fn update_block(&mut self, block: &[u8; 16]) {
+        self.acc = (self.acc + GF2_128::from_polyval_bytes(block)) * self.key;
+    }
+
+    /// Process data, padding the last block with zeros
+    pub fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.update_block(&block);
+        }
+    }
+
+    /// Get the hash of the processed blocks
+    pub fn finalize(self) -> [u8; 16] {
+        self.acc.to_polyval_bytes()
+    }
+}
+
+impl fmt::Debug for Polyval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Do not print the key
+        f.debug_struct("Polyval").finish_non_exhaustive()
+    }
+}
+
+impl From<u128> for GF2_128 {
+    fn from(v: u128) -> Self {
+        GF2_128(v)
+    }
+}
+
+impl From<GF2_128> for u128 {
+    fn from(v: GF2_128) -> Self {
+        v.0
+    }
+}
+
+impl fmt::Debug for GF2_128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}_GF2_128", self.0)
+    }
+}
+
+impl fmt::Display for GF2_128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}_GF2_128", self.0)
+    }
+}
+
+impl Mul for GF2_128 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let (a0, a1) = (self.0 as u64, (self.0 >> 64) as u64);
+        let (b0, b1) = (rhs.0 as u64, (rhs.0 >> 64) as u64);
+        let lo = clmul(a0, b0);
+        let hi = clmul(a1, b1);
+        let mid = clmul(a0, b1) ^ clmul(a1, b0);
+        GF2_128(reduce(hi ^ (mid >> 64), lo ^ (mid << 64)))
+    }
+}
+
+impl MulAssign for GF2_128 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Product for GF2_128 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Div for GF2_128 {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        if rhs.is_zero() {
+            panic!("Division by 0");
+        }
+        self * rhs.inverse()
+    }
+}
+
+impl DivAssign for GF2_128 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Add for GF2_128 {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        GF2_128(self.0 ^ rhs.0)
+    }
+}
+
+impl AddAssign for GF2_128 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for GF2_128 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Sub for GF2_128 {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        GF2_128(self.0 ^ rhs.0)
+    }
+}
+
+impl SubAssign for GF2_128 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for GF2_128 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for GF2_128 {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        let (a, b) = (self.0, other.0);
+        (a as u64).ct_eq(&(b as u64)) & ((a >> 64) as u64).ct_eq(&((b >> 64) as u64))
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConditionallySelectable for GF2_128 {
+    fn conditional_select(a: &Self, b: &Self, choice: subtle::Choice) -> Self {
+        let lo = u64::conditional_select(&(a.0 as u64), &(b.0 as u64), choice);
+        let hi = u64::conditional_select(&((a.0 >> 64) as u64), &((b.0 >> 64) as u64), choice);
+        GF2_128(u128::from(lo) | (u128::from(hi) << 64))
+    }
+}
+
+#[cfg(feature = "rand")]
+impl rand::distributions::Distribution<GF2_128> for rand::distributions::Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> GF2_128 {
+        GF2_128(rng.gen())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_clmul() {
+        let cases = [
+            (0, 0),
+            (1, u64::MAX),
+            (u64::MAX, u64::MAX),
+            (0x8000_0000_0000_0001, 0x8000_0000_0000_0001),
+            (0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210),
+        ];
+        for &(a, b) in cases.iter() {
+            let mut expected = 0_u128;
+            for i in 0..64 {
+                if (b >> i) & 1 == 1 {
+                    expected ^= u128::from(a) << i;
+                }
+            }
+            assert_eq!(clmul_portable(a, b), expected);
+            assert_eq!(clmul(a, b), expected);
+        }
+    }
+}
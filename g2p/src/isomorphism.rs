@@ -0,0 +1,329 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Isomorphisms between fields of the same size
+//!
+//! All fields with 2^p elements are isomorphic, but fields generated with different moduli use
+//! different representations. An [`Isomorphism`] maps the elements of one field onto the other,
+//! preserving addition and multiplication.
+//!
+//! It is found by taking the minimal polynomial of the generator of the source field and finding
+//! one of its roots in the target field. Mapping the powers of the generator to the powers of the
+//! root is a field isomorphism, which is linear over GF(2). It is stored as a matrix in both
+//! directions, so applying it takes one XOR per bit.
+//!
+//! ```rust
+//! use g2p::{g2p, isomorphism::{convert, Isomorphism}};
+//!
+//! g2p!(Ours, 8, modulus: 0x11b);
+//! g2p!(Theirs, 8, modulus: 0x11d);
+//! # fn main() {
+//! let iso = Isomorphism::<Ours, Theirs>::new().unwrap();
+//! let a = Ours::from(0x53);
+//! let b = Ours::from(0xca);
+//! assert_eq!(iso.map(a * b), iso.map(a) * iso.map(b));
+//! assert_eq!(iso.map_back(iso.map(a)), a);
+//!
+//! let c: Theirs = convert(a);
+//! assert_eq!(c, iso.map(a));
+//! # }
+//! ```
+
+use core::{convert::TryFrom, fmt, marker::PhantomData};
+
+use crate::{extension::minimal_polynomial, GaloisField};
+
+/// Field isomorphism from A to B
+///
+/// The isomorphism is not unique: composing it with the Frobenius automorphism gives another one.
+/// [`new`](Self::new) always returns the same one.
+pub struct Isomorphism<A, B> {
+    forward: [u32; 32],
+    backward: [u32; 32],
+    degree: usize,
+    fields: PhantomData<fn(A) -> B>,
+}
+
+impl<A: GaloisField, B: GaloisField> Isomorphism<A, B> {
+    /// Find an isomorphism from A to B
+    ///
+    /// Returns `None` if the fields have a different number of elements.
+    pub fn new() -> Option<Self> {
+        if A::DEGREE != B::DEGREE || A::DEGREE > 32 {
+            return None;
+        }
+        let p = A::DEGREE as usize;
+
+        let generator = A::GENERATOR;
+        let root = find_root::<B>(minimal_polynomial(generator).0);
+
+        // Columns are the representations of the powers of the generator (root) in A (B)
+        let mut powers_a = [0; 32];
+        let mut powers_b = [0; 32];
+        let (mut x, mut y) = (A::ONE, B::ONE);
+        for i in 0..p {
+            powers_a[i] = repr(x);
+            powers_b[i] = repr(y);
+            x *= generator;
+            y *= root;
+        }
+
+        // Change of basis: A -> coordinates in powers of the generator -> B
+        let forward = compose(&powers_b, &invert(&powers_a, p), p);
+        let backward = compose(&powers_a, &invert(&powers_b, p), p);
+        Some(Isomorphism {
+            forward,
+            backward,
+            degree: p,
+            fields: PhantomData,
+        })
+    }
+
+    /// Map an element of A to B
+    pub fn map(&self, x: A) -> B {
+        let v = apply(&self.forward[..self.degree], repr(x));
+        from_repr(v)
+    }
+
+    /// Map an element of B back to A
+    pub fn map_back(&self, y: B) -> A {
+        let v = apply(&self.backward[..self.degree], repr(y));
+        from_repr(v)
+    }
+
+    /// The isomorphism in the opposite direction
+    pub fn inverse(&self) -> Isomorphism<B, A> {
+        Isomorphism {
+            forward: self.backward,
+            backward: self.forward,
+            degree: self.degree,
+            fields: PhantomData,
+        }
+    }
+
+    /// The basis-change matrix from A to B
+    ///
+    /// Entry j is the representation in B of the element of A represented by `1 << j`.
+    pub fn forward_matrix(&self) -> &[u32] {
+        &self.forward[..self.degree]
+    }
+
+    /// The basis-change matrix from B to A
+    ///
+    /// Entry j is the representation in A of the element of B represented by `1 << j`.
+    pub fn backward_matrix(&self) -> &[u32] {
+        &self.backward[..self.degree]
+    }
+}
+
+impl<A, B> Clone for Isomorphism<A, B> {
+    fn clone(&self) -> Self {
+        Isomorphism {
+            forward: self.forward,
+            backward: self.backward,
+            degree: self.degree,
+            fields: PhantomData,
+        }
+    }
+}
+
+impl<A, B> fmt::Debug for Isomorphism<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Isomorphism")
+            .field("forward", &&self.forward[..self.degree])
+            .field("backward", &&self.backward[..self.degree])
+            .finish()
+    }
+}
+
+/// Convert an element of A to the isomorphic field B
+///
+/// This computes the isomorphism on every call. To convert many elements, create an
+/// [`Isomorphism`] once instead.
+///
+/// # Panics
+/// Panics if the fields have a different number of elements.
+pub fn convert<A: GaloisField, B: GaloisField>(x: A) -> B {
+    Isomorphism::<A, B>::new()
+        .expect("Fields must have the same size")
+        .map(x)
+}
+
+fn repr<F: GaloisField>(x: F) -> u32 {
+    x.to_repr().into()
+}
+
+fn from_repr<F: GaloisField>(v: u32) -> F {
+    F::Repr::try_from(v)
+        .ok()
+        .and_then(F::from_repr)
+        .expect("Isomorphism maps to valid elements")
+}
+
+/// Multiply a GF(2) matrix, given as columns, with a vector
+fn apply(columns: &[u32], v: u32) -> u32 {
+    columns
+        .iter()
+        .enumerate()
+        .filter(|(j, _)| (v >> j) & 1 == 1)
+        .fold(0, |acc, (_, c)| acc ^ c)
+}
+
+/// Multiply two p x p GF(2) matrices, given as columns
+fn compose(a: &[u32; 32], b: &[u32; 32], p: usize) -> [u32; 32] {
+    let mut res = [0; 32];
+    for (r, &col) in res.iter_mut().zip(b.iter()).take(p) {
+        *r = apply(&a[..p], col);
+    }
+    res
+}
+
+/// Invert a p x p GF(2) matrix, given as columns
+fn invert(columns: &[u32; 32], p: usize) -> [u32; 32] {
+    // Solve for every unit vector: track the combination of columns in the upper bits
+    let mut rows = [0_u64; 32];
+    for (j, row) in rows.iter_mut().enumerate().take(p) {
+        *row = u64::from(columns[j]) | (1 << (32 + j));
+    }
+    for bit in 0..p {
+        let pivot = (bit..p)
+            .find(|&r| (rows[r] >> bit) & 1 == 1)
+            .expect("Matrix is invertible");
+        rows.swap(bit, pivot);
+        for r in 0..p {
+            if r != bit && (rows[r] >> bit) & 1 == 1 {
+                rows[r] ^= rows[bit];
+            }
+        }
+    }
+    // Now rows[bit] has only `bit` set in the lower part, and the columns combining to it above
+    let mut res = [0; 32];
+    for (r, row) in res.iter_mut().zip(rows.iter()).take(p) {
+        *r = (row >> 32) as u32;
+    }
+    res
+}
+
+/// Polynomial over F of degree < 64, used for root finding
+#[derive(Clone, Copy)]
+struct SmallPoly<F> {
+    coeffs: [F; 64],
+    len: usize,
+}
+
+impl<F: GaloisField> SmallPoly<F> {
+    fn from_g2poly(poly: u64) -> Self {
+        let mut res = SmallPoly {
+            coeffs: [F::ZERO; 64],
+            len: 64,
+        };
+        for (i, c) in res.coeffs.iter_mut().enumerate() {
+            if (poly >> i) & 1 == 1 {
+                *c = F::ONE;
+            }
+        }
+        res.normalize();
+        res
+    }
+
+    fn normalize(&mut self) {
+        while self.len > 0 && self.coeffs[self.len - 1].is_zero() {
+            self.len -= 1;
+        }
+    }
+
+    fn degree(&self) -> usize {
+        self.len - 1
+    }
+
+    fn monic(mut self) -> Self {
+        let lead = self.coeffs[self.len - 1].inverse();
+        for c in self.coeffs[..self.len].iter_mut() {
+            *c *= lead;
+        }
+        self
+    }
+
+    /// Remainder of division by a monic polynomial
+    fn rem(mut self, m: &Self) -> Self {
+        while self.len >= m.len {
+            let lead = self.coeffs[self.len - 1];
+            let shift = self.len - m.len;
+            for (i, &c) in m.coeffs[..m.len].iter().enumerate() {
+                self.coeffs[shift + i] -= lead * c;
+            }
+            self.normalize();
+        }
+        self
+    }
+
+    /// Square, using (a + b)^2 = a^2 + b^2 in characteristic 2
+    fn square(&self) -> Self {
+        let mut res = SmallPoly {
+            coeffs: [F::ZERO; 64],
+            len: 2 * self.len,
+        };
+        for (i, &c) in self.coeffs[..self.len].iter().enumerate() {
+            res.coeffs[2 * i] = c.square();
+        }
+        res.normalize();
+        res
+    }
+
+    fn add(mut self, rhs: &Self) -> Self {
+        for (c, &r) in self.coeffs.iter_mut().zip(rhs.coeffs.iter()) {
+            *c += r;
+        }
+        self.len = self.len.max(rhs.len);
+        self.normalize();
+        self
+    }
+}
+
+fn gcd<F: GaloisField>(mut a: SmallPoly<F>, mut b: SmallPoly<F>) -> SmallPoly<F> {
+    while b.len > 0 {
+        let r = a.rem(&b.monic());
+        a = b;
+        b = r;
+    }
+    a.monic()
+}
+
+/// Find a root of an irreducible polynomial over GF(2) of degree p in a field with 2^p elements
+///
+/// Uses the trace algorithm by Berlekamp: for a root-splitting polynomial f and any δ, the trace
+/// Tr(δ·y) mod f vanishes on part of the roots, so gcd(f, Tr(δ·y)) is a factor of f. Trying
+/// the elements of a basis as δ always finds a proper factor.
+fn find_root<F: GaloisField>(poly: u64) -> F {
+    let mut f = SmallPoly::<F>::from_g2poly(poly);
+    while f.degree() > 1 {
+        let mut found = false;
+        for j in 0..F::DEGREE {
+            let delta = from_repr::<F>(1 << j);
+            // t = δ·y, reduced as deg f > 1
+            let mut t = SmallPoly {
+                coeffs: [F::ZERO; 64],
+                len: 2,
+            };
+            t.coeffs[1] = delta;
+            let mut trace = t;
+            for _ in 1..F::DEGREE {
+                t = t.square().rem(&f);
+                trace = trace.add(&t);
+            }
+
+            let g = gcd(f, trace);
+            if g.degree() > 0 && g.degree() < f.degree() {
+                f = g;
+                found = true;
+                break;
+            }
+        }
+        assert!(found, "Polynomial splits into linear factors");
+    }
+    // f = y + c, so the root is c
+    f.coeffs[0]
+}
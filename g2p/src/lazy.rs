@@ -0,0 +1,285 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Tables computed at runtime
+//!
+//! For large fields, the static tables generated by `g2p!` take up multiple megabytes in the
+//! binary and slow down compilation. Using `tables: lazy`, the generated type instead computes
+//! the same tables on first use.
+//!
+//! ```rust
+//! g2p::g2p!(GF1M, 20, tables: lazy);
+//! # fn main() {
+//! let a = GF1M::from(123_456);
+//! let b = GF1M::from(654_321);
+//! assert_eq!(a * b / b, a);
+//! # }
+//! ```
+//!
+//! By default the tables are allocated on the heap. They can also be placed in a buffer provided
+//! by the user, which has to happen before the first arithmetic operation. Without the `alloc`
+//! feature, this is the only option and using the field before initializing it panics.
+//!
+//! ```rust
+//! g2p::g2p!(GF4096, 12, tables: lazy);
+//! # fn main() {
+//! let buffer = vec![0; GF4096::TABLE_LEN].leak();
+//! GF4096::init_tables(buffer).unwrap();
+//!
+//! let a = GF4096::from(1234);
+//! assert_eq!(a / a, GF4096::from(1));
+//! # }
+//! ```
+
+use core::{
+    fmt, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::G2Poly;
+
+/// Integer types that can be used as table entries
+///
+/// Implemented for `u8`, `u16` and `u32`, the representations used by `g2p!`.
+pub trait TableEntry: Copy + Send + Sync + 'static {
+    /// Zero value used to fill fresh tables
+    const ZERO: Self;
+
+    /// Convert the coefficients of a polynomial of degree < 32 to an entry
+    fn from_poly(p: G2Poly) -> Self;
+
+    /// Convert the entry to an index into the tables
+    fn to_index(self) -> usize;
+}
+
+macro_rules! impl_table_entry {
+    ($($ty:ty),*) => {
+        $(
+            impl TableEntry for $ty {
+                const ZERO: Self = 0;
+
+                fn from_poly(p: G2Poly) -> Self {
+                    p.0 as $ty
+                }
+
+                fn to_index(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_table_entry!(u8, u16, u32);
+
+/// Error returned when a user provided buffer can not be used for the tables
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InitError {
+    /// The tables were already initialized, either by a previous call or by an arithmetic
+    /// operation
+    AlreadyInitialized,
+    /// The buffer does not have the required length
+    BufferSize {
+        /// The required length
+        expected: usize,
+        /// The length of the provided buffer
+        actual: usize,
+    },
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitError::AlreadyInitialized => write!(f, "Tables are already initialized"),
+            InitError::BufferSize { expected, actual } => write!(
+                f,
+                "Buffer has length {}, but tables require {}",
+                actual, expected
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InitError {}
+
+/// Multiplication and inversion tables which are computed on first use
+///
+/// The tables use the same layout as the static tables generated by `g2p!`, stored in a single
+/// slice: first the multiplication tables `[left][right][i][j]`, followed by the inversion table.
+pub struct LazyTables<T: 'static> {
+    modulus: G2Poly,
+    generator: G2Poly,
+    tables: AtomicPtr<T>,
+}
+
+impl<T> LazyTables<T> {
+    /// Create uninitialized tables for the field generated by `modulus`
+    ///
+    /// `generator` has to generate the multiplicative group of the field, it is used to compute
+    /// the inverses.
+    pub const fn new(modulus: G2Poly, generator: G2Poly) -> Self {
+        LazyTables {
+            modulus,
+            generator,
+            tables: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Number of entries in the tables
+    pub fn table_len(&self) -> usize {
+        inv_offset(self.degree()) + (1 << self.degree())
+    }
+
+    fn degree(&self) -> usize {
+        63 - self.modulus.0.leading_zeros() as usize
+    }
+}
+
+impl<T: TableEntry> LazyTables<T> {
+    /// Get the tables, computing them if necessary
+    #[inline]
+    pub fn get(&self) -> &'static [T] {
+        let tables = self.tables.load(Ordering::Acquire);
+        if tables.is_null() {
+            return self.init_heap();
+        }
+        // SAFETY: A non-null pointer is only ever stored after the tables of `table_len()` entries were
+        // completely written. The storage is leaked or borrowed for 'static and never mutated
+        // again.
+        unsafe { core::slice::from_raw_parts(tables, self.table_len()) }
+    }
+
+    /// Compute the tables into the given buffer and use them from now on
+    ///
+    /// The buffer must have exactly [`table_len`](Self::table_len) entries. Fails if the tables
+    /// were already initialized.
+    pub fn init_with(&self, buffer: &'static mut [T]) -> Result<(), InitError> {
+        if buffer.len() != self.table_len() {
+            return Err(InitError::BufferSize {
+                expected: self.table_len(),
+                actual: buffer.len(),
+            });
+        }
+        if !self.tables.load(Ordering::Acquire).is_null() {
+            return Err(InitError::AlreadyInitialized);
+        }
+
+        self.fill(buffer);
+        self.tables
+            .compare_exchange(
+                ptr::null_mut(),
+                buffer.as_mut_ptr(),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .map(|_| ())
+            .map_err(|_| InitError::AlreadyInitialized)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[cold]
+    fn init_heap(&self) -> &'static [T] {
+        let mut buffer = alloc::vec![T::ZERO; self.table_len()].into_boxed_slice();
+        self.fill(&mut buffer);
+        let new = alloc::boxed::Box::into_raw(buffer) as *mut T;
+
+        match self.tables.compare_exchange(
+            ptr::null_mut(),
+            new,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => (),
+            Err(_) => {
+                // Someone else was faster, use their tables instead.
+                // SAFETY: `new` was created by `Box::into_raw` above and never shared.
+                drop(unsafe {
+                    alloc::boxed::Box::from_raw(ptr::slice_from_raw_parts_mut(
+                        new,
+                        self.table_len(),
+                    ))
+                });
+            }
+        }
+        self.get()
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[cold]
+    fn init_heap(&self) -> &'static [T] {
+        panic!("Tables are not initialized and the alloc feature is disabled, use init_with")
+    }
+
+    fn fill(&self, buffer: &mut [T]) {
+        let p = self.degree();
+        let field_size = 1_u64 << p;
+        let nparts = (p + 7) / 8;
+        let (mul_table, inv_table) = buffer.split_at_mut(inv_offset(p));
+
+        let mut entries = mul_table.iter_mut();
+        for left in 0..nparts {
+            for right in 0..nparts {
+                for i in 0..256_u64 {
+                    let i = i << (8 * left);
+                    for j in 0..256_u64 {
+                        let j = j << (8 * right);
+                        let v = if i < field_size && j < field_size {
+                            G2Poly(i) * G2Poly(j) % self.modulus
+                        } else {
+                            G2Poly(0)
+                        };
+                        *entries.next().expect("Table has the right size") = T::from_poly(v);
+                    }
+                }
+            }
+        }
+
+        let mul = |a: usize, b: usize| {
+            let mut res = 0;
+            for left in 0..nparts {
+                for right in 0..nparts {
+                    let i = (a >> (8 * left)) & 255;
+                    let j = (b >> (8 * right)) & 255;
+                    res ^= mul_table[((left * nparts + right) * 256 + i) * 256 + j].to_index();
+                }
+            }
+            res
+        };
+
+        // Walk through all powers g^k of the generator and their inverses g^-k at the same time.
+        // Zero is not a power of the generator, it is mapped to itself explicitly since user
+        // provided buffers are not cleared.
+        inv_table[0] = T::ZERO;
+        let g = self.generator.0 as usize;
+        let g_inv = G2Poly(g as u64).pow_mod(field_size - 2, self.modulus).0 as usize;
+        let mut x = 1;
+        let mut x_inv = 1;
+        for _ in 1..field_size {
+            inv_table[x] = T::from_poly(G2Poly(x_inv as u64));
+            x = mul(x, g);
+            x_inv = mul(x_inv, g_inv);
+        }
+    }
+}
+
+/// Offset of the inversion table for a field with 2^p elements
+fn inv_offset(p: usize) -> usize {
+    let nparts = (p + 7) / 8;
+    nparts * nparts * 256 * 256
+}
+
+impl<T> fmt::Debug for LazyTables<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyTables")
+            .field("modulus", &self.modulus)
+            .field(
+                "initialized",
+                &!self.tables.load(Ordering::Acquire).is_null(),
+            )
+            .finish()
+    }
+}
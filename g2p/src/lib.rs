@@ -21,12 +21,76 @@
 //! The types generated by this library are probably not suitable for cryptographic purposes, as
 //! multiplication is not guaranteed to be constant time.
 //!
+//! For use with secret data, enable the `subtle` feature and generate the type with
+//! `constant_time: true`. Multiplication then uses bitwise operations instead of table lookups,
+//! and inversion is calculated as x^(2^p - 2) using a fixed sequence of multiplications. The type
+//! also implements [`ConstantTimeEq`](subtle::ConstantTimeEq) and
+//! [`ConditionallySelectable`](subtle::ConditionallySelectable). Square roots and the trace are
+//! calculated without branches as well. Note that dividing by zero still panics, and that
+//! [`pow`](GaloisField::pow) branches on the exponent and [`norm`](GaloisField::norm) and
+//! [`checked_inv`](GaloisField::checked_inv) on whether the value is zero.
+//!
+//! ```rust
+//! g2p::g2p!(GF256, 8, modulus: 0b1_0001_1011, constant_time: true);
+//! # fn main() {
+//! use g2p::subtle::ConstantTimeEq;
+//!
+//! let a = GF256::from(0x53);
+//! let b = GF256::from(0xca);
+//! assert!(bool::from((a * b).ct_eq(&GF256::from(1))));
+//! # }
+//! ```
+//!
 //! # Note
 //! The implementation was tested for finite fields up to 2^17 in size, which compiles reasonably
 //! fast. The space requirements are linear to the field size for the inversion table and log^2(N)
 //! for the multiplication table. This means it is not feasible to use this to generate fields of
 //! size 2^32, which would 4*4GB memory.
 //!
+//! For large fields, consider generating the type from a build script using
+//! [g2build](https://docs.rs/g2build). This avoids regenerating the tables every time the crate is
+//! compiled.
+//! Alternatively, `tables: lazy` computes the tables at runtime on first use, which keeps them out
+//! of the binary. See the [`lazy`] module.
+//!
+//! # `no_std`
+//! This crate and the code generated by `g2p!` only depend on `core`. The default `std` feature
+//! can be disabled for use on targets without the standard library. Without the `alloc` feature,
+//! fields using `tables: lazy` need a user provided buffer, see the [`lazy`] module.
+//!
+//! # Polynomials
+//! With the `alloc` feature, the `poly` module provides polynomials with coefficients in any
+//! generated field, including division, gcd, evaluation and interpolation.
+//!
+//! # Erasure coding
+//! The `erasure` module implements a systematic Reed-Solomon erasure code over any generated
+//! field. It also requires the `alloc` feature. For errors at unknown positions, the
+//! `reed_solomon` module provides a decoder correcting both errors and erasures, and the `bch`
+//! module binary BCH codes. The `fft` module provides the additive FFT over binary fields and an
+//! erasure code on top of it, which scales to thousands of shards.
+//!
+//! # Linear algebra
+//! The `matrix` module provides dense matrices over generated fields with Gaussian elimination,
+//! inversion and solving of linear systems. The `bitmatrix` module expands elements and matrices
+//! into matrices over GF(2), and derives XOR schedules with common subexpression elimination for
+//! XOR-only Cauchy Reed-Solomon coding.
+//!
+//! # Network coding
+//! The `rlnc` module implements random linear network coding with an encoder, a recoder and a
+//! progressive Gauss-Jordan decoder.
+//!
+//! # Secret sharing
+//! The `shamir` module splits secrets into shares with Shamir's scheme over any generated field.
+//!
+//! # GF(2^128)
+//! The `gf2_128` module provides the field used by AES-GCM and AES-GCM-SIV, with conversions for
+//! the GHASH and POLYVAL block formats. It is too large for `g2p!` and uses carry-less
+//! multiplication instead of tables.
+//!
+//! # Random elements
+//! With the `rand` feature, generated types can be sampled uniformly with `rng.gen()`. The
+//! `random` module provides sampling of non-zero elements.
+//!
 //! # Examples
 //!
 //! ```rust
@@ -68,17 +132,81 @@
 //! have to be added together using the normal finite field addition. For our GF65536 example this
 //! means the multiplication tables use 4 * 256 * 256 entries á 2 byte which is ~0.5MB
 
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+// Allows using `g2p!` in this crate
+extern crate self as g2p;
+
 use core::{
+    convert::TryFrom,
+    fmt::Debug,
+    hash::Hash,
     iter::{Product, Sum},
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 
+#[cfg(feature = "alloc")]
+pub mod bch;
+#[cfg(feature = "alloc")]
+pub mod bitmatrix;
+#[cfg(feature = "alloc")]
+pub mod erasure;
+pub mod extension;
+#[cfg(feature = "alloc")]
+pub mod fft;
+pub mod gf2_128;
+pub mod isomorphism;
+pub mod lazy;
+#[cfg(feature = "alloc")]
+pub mod matrix;
+pub mod multiplier;
+#[cfg(feature = "alloc")]
+pub mod poly;
+pub mod quadratic;
+#[cfg(feature = "rand")]
+pub mod random;
+#[cfg(feature = "alloc")]
+pub mod reed_solomon;
+#[cfg(feature = "alloc")]
+pub mod rlnc;
+#[cfg(feature = "alloc")]
+pub mod shamir;
+pub mod simd;
+
 /// Procedural macro to generate binary galois fields
 pub use g2gen::g2p;
 
 /// Polynomial representation of values
 pub use g2poly::G2Poly;
 
+mod gf2 {
+    crate::g2p!(GF2, 1);
+}
+
+/// The field with two elements, returned by [`GaloisField::trace`] and [`GaloisField::norm`]
+pub use gf2::GF2;
+
+/// Constant time primitives used by fields with `constant_time: true`
+#[cfg(feature = "subtle")]
+pub use subtle;
+
+/// Random number generation used to sample field elements
+#[cfg(feature = "rand")]
+pub use rand;
+
+/// Without the `rand` feature, generated types do not implement `Distribution<Standard>`
+#[cfg(not(feature = "rand"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_rand {
+    ($ident:ident, $ty:ty) => {};
+}
+
 /// Common trait for finite fields
 ///
 /// All types generated by `g2p!` implement this trait.
@@ -129,6 +257,101 @@ pub trait GaloisField:
     /// Polynomial representation of the modulus used to generate the field
     const MODULUS: G2Poly;
 
+    /// Integer type used to represent the elements: `u8`, `u16` or `u32`
+    type Repr: Copy + Eq + Ord + Hash + Debug + Into<u32> + TryFrom<u32>;
+
+    /// Degree p of the field, which has 2^p elements
+    const DEGREE: u32;
+
+    /// Number of bits in the representation type
+    const BITS: u32 = (core::mem::size_of::<Self::Repr>() * 8) as u32;
+
+    /// Number of bytes used by the byte encoding of an element
+    const BYTES: usize = (Self::DEGREE as usize + 7) / 8;
+
+    /// Get the integer representing the value
+    ///
+    /// Bit i of the result is the coefficient of x^i in the polynomial representation.
+    fn to_repr(self) -> Self::Repr;
+
+    /// Create a value from its integer representation
+    ///
+    /// Returns `None` if the integer has bits set at position `DEGREE` or higher.
+    ///
+    /// # Example
+    /// ```rust
+    /// use g2p::{GaloisField, g2p};
+    ///
+    /// g2p!(GF16, 4);
+    /// # fn main() {
+    /// assert_eq!(GF16::from_repr(5), Some(GF16::from(5)));
+    /// assert_eq!(GF16::from_repr(16), None);
+    /// assert_eq!(GF16::from(5).to_repr(), 5);
+    /// # }
+    /// ```
+    fn from_repr(repr: Self::Repr) -> Option<Self>;
+
+    /// Write the value as little endian bytes
+    ///
+    /// # Panics
+    /// Panics if `out` is not exactly [`BYTES`](Self::BYTES) long.
+    ///
+    /// # Example
+    /// ```rust
+    /// use g2p::{GaloisField, g2p};
+    ///
+    /// g2p!(GF1024, 10);
+    /// # fn main() {
+    /// let mut buf = [0; 2];
+    /// GF1024::from(0x123).write_le_bytes(&mut buf);
+    /// assert_eq!(buf, [0x23, 0x01]);
+    /// assert_eq!(GF1024::from_le_bytes(&buf), Some(GF1024::from(0x123)));
+    /// # }
+    /// ```
+    fn write_le_bytes(self, out: &mut [u8]) {
+        assert_eq!(out.len(), Self::BYTES, "Output must have BYTES length");
+        let repr: u32 = self.to_repr().into();
+        out.copy_from_slice(&repr.to_le_bytes()[..Self::BYTES]);
+    }
+
+    /// Write the value as big endian bytes
+    ///
+    /// # Panics
+    /// Panics if `out` is not exactly [`BYTES`](Self::BYTES) long.
+    fn write_be_bytes(self, out: &mut [u8]) {
+        assert_eq!(out.len(), Self::BYTES, "Output must have BYTES length");
+        let repr: u32 = self.to_repr().into();
+        out.copy_from_slice(&repr.to_be_bytes()[4 - Self::BYTES..]);
+    }
+
+    /// Read a value from little endian bytes
+    ///
+    /// Returns `None` if `bytes` is not exactly [`BYTES`](Self::BYTES) long or does not encode
+    /// an element of the field.
+    fn from_le_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTES {
+            return None;
+        }
+        let mut buf = [0; 4];
+        buf[..Self::BYTES].copy_from_slice(bytes);
+        let repr = Self::Repr::try_from(u32::from_le_bytes(buf)).ok()?;
+        Self::from_repr(repr)
+    }
+
+    /// Read a value from big endian bytes
+    ///
+    /// Returns `None` if `bytes` is not exactly [`BYTES`](Self::BYTES) long or does not encode
+    /// an element of the field.
+    fn from_be_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTES {
+            return None;
+        }
+        let mut buf = [0; 4];
+        buf[4 - Self::BYTES..].copy_from_slice(bytes);
+        let repr = Self::Repr::try_from(u32::from_be_bytes(buf)).ok()?;
+        Self::from_repr(repr)
+    }
+
     /// Calculate the p-th power of a value
     ///
     /// Calculate the value of x to the power p in finite field arithmethic
@@ -149,7 +372,7 @@ pub trait GaloisField:
     /// ```
     fn pow(self, p: usize) -> Self {
         let mut val = Self::ONE;
-        let mut pow_pos = 1 << (::std::mem::size_of::<usize>() * 8 - 1);
+        let mut pow_pos = 1 << (::core::mem::size_of::<usize>() * 8 - 1);
         assert_eq!(pow_pos << 1, 0);
         while pow_pos > 0 {
             val *= val;
@@ -160,4 +383,215 @@ pub trait GaloisField:
         }
         val
     }
+
+    /// Check if the value is zero
+    fn is_zero(self) -> bool {
+        self == Self::ZERO
+    }
+
+    /// Calculate the multiplicative inverse
+    ///
+    /// Zero has no inverse, it is mapped to zero instead of panicking. Use
+    /// [`checked_inv`](Self::checked_inv) to detect this case.
+    ///
+    /// # Example
+    /// ```rust
+    /// use g2p::{GaloisField, g2p};
+    ///
+    /// g2p!(GF256, 8);
+    /// # fn main() {
+    /// let a = GF256::from(0x53);
+    /// assert_eq!(a * a.inverse(), GF256::ONE);
+    /// assert_eq!(GF256::ZERO.inverse(), GF256::ZERO);
+    /// # }
+    /// ```
+    fn inverse(self) -> Self {
+        if self.is_zero() {
+            return Self::ZERO;
+        }
+        // x^(2^p - 1) = 1, so x^(2^p - 2) is the inverse of x
+        self.pow(Self::SIZE - 2)
+    }
+
+    /// Calculate the multiplicative inverse, or `None` if the value is zero
+    fn checked_inv(self) -> Option<Self> {
+        if self.is_zero() {
+            None
+        } else {
+            Some(self.inverse())
+        }
+    }
+
+    /// Divide by `rhs`, or return `None` if `rhs` is zero
+    ///
+    /// # Example
+    /// ```rust
+    /// use g2p::{GaloisField, g2p};
+    ///
+    /// g2p!(GF16, 4);
+    /// # fn main() {
+    /// let a = GF16::from(5);
+    /// assert_eq!(a.checked_div(a), Some(GF16::ONE));
+    /// assert_eq!(a.checked_div(GF16::ZERO), None);
+    /// # }
+    /// ```
+    fn checked_div(self, rhs: Self) -> Option<Self> {
+        rhs.checked_inv().map(|inv| self * inv)
+    }
+
+    /// Calculate the square of a value
+    fn square(self) -> Self {
+        self * self
+    }
+
+    /// Apply the Frobenius automorphism k times
+    ///
+    /// Calculates x^(2^k). Since x^(2^p) = x, k is taken modulo p. Generated fields take p - k
+    /// square roots instead of k squarings if that is shorter.
+    ///
+    /// # Example
+    /// ```rust
+    /// use g2p::{GaloisField, g2p};
+    ///
+    /// g2p!(GF256, 8);
+    /// # fn main() {
+    /// let a = GF256::from(0x53);
+    /// assert_eq!(a.frobenius(1), a * a);
+    /// assert_eq!(a.frobenius(3), a.pow(8));
+    /// assert_eq!(a.frobenius(8), a);
+    /// # }
+    /// ```
+    fn frobenius(self, k: u32) -> Self {
+        let mut res = self;
+        for _ in 0..k % Self::DEGREE {
+            res = res.square();
+        }
+        res
+    }
+
+    /// Calculate the absolute trace x + x^2 + x^4 + ... + x^(2^(p - 1))
+    ///
+    /// The trace is always an element of GF(2).
+    ///
+    /// # Example
+    /// ```rust
+    /// use g2p::{GaloisField, g2p, GF2};
+    ///
+    /// g2p!(GF16, 4);
+    /// # fn main() {
+    /// assert_eq!(GF16::ONE.trace(), GF2::ZERO);
+    /// let a = GF16::from(8);
+    /// assert_eq!(a.trace(), GF2::ONE);
+    /// # }
+    /// ```
+    fn trace(self) -> GF2 {
+        let mut res = Self::ZERO;
+        let mut y = self;
+        for _ in 0..Self::DEGREE {
+            res += y;
+            y = y.square();
+        }
+        if res == Self::ZERO {
+            GF2::ZERO
+        } else {
+            GF2::ONE
+        }
+    }
+
+    /// Calculate the absolute norm x * x^2 * x^4 * ... * x^(2^(p - 1))
+    ///
+    /// The norm is x^(2^p - 1), which is 1 for all values except 0.
+    fn norm(self) -> GF2 {
+        if self.is_zero() {
+            GF2::ZERO
+        } else {
+            GF2::ONE
+        }
+    }
+
+    /// Calculate the square root
+    ///
+    /// Every element has exactly one square root, x^(2^(p - 1)).
+    ///
+    /// # Example
+    /// ```rust
+    /// use g2p::{GaloisField, g2p};
+    ///
+    /// g2p!(GF256, 8);
+    /// # fn main() {
+    /// let a = GF256::from(0x53);
+    /// assert_eq!(a.sqrt().square(), a);
+    /// # }
+    /// ```
+    fn sqrt(self) -> Self {
+        let mut res = self;
+        for _ in 1..Self::DEGREE {
+            res = res.square();
+        }
+        res
+    }
+
+    /// Multiply all elements of a slice with a constant
+    ///
+    /// Calculates `dst[i] = src[i] * c`. For fields with p <= 8, this uses SIMD instructions if
+    /// the CPU supports them, see [`simd`].
+    ///
+    /// # Panics
+    /// Panics if `dst` and `src` have different lengths.
+    ///
+    /// # Example
+    /// ```rust
+    /// use g2p::{GaloisField, g2p};
+    ///
+    /// g2p!(GF256, 8);
+    /// # fn main() {
+    /// let src = [GF256::from(1), GF256::from(2), GF256::from(3)];
+    /// let mut dst = [GF256::ZERO; 3];
+    /// GF256::mul_slice(&mut dst, &src, 7.into());
+    /// assert_eq!(dst, [src[0] * 7.into(), src[1] * 7.into(), src[2] * 7.into()]);
+    /// # }
+    /// ```
+    fn mul_slice(dst: &mut [Self], src: &[Self], c: Self) {
+        assert_eq!(dst.len(), src.len(), "Slices must have the same length");
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d = s * c;
+        }
+    }
+
+    /// Multiply all elements of a slice with a constant and add them to another slice
+    ///
+    /// Calculates `dst[i] = dst[i] + src[i] * c`. This is the main operation when encoding with a
+    /// linear code like Reed-Solomon.
+    ///
+    /// # Panics
+    /// Panics if `dst` and `src` have different lengths.
+    fn mul_add_slice(dst: &mut [Self], src: &[Self], c: Self) {
+        assert_eq!(dst.len(), src.len(), "Slices must have the same length");
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d += s * c;
+        }
+    }
+
+    /// Add all elements of a slice to another slice
+    ///
+    /// Calculates `dst[i] = dst[i] + src[i]`.
+    ///
+    /// # Panics
+    /// Panics if `dst` and `src` have different lengths.
+    fn add_slice(dst: &mut [Self], src: &[Self]) {
+        assert_eq!(dst.len(), src.len(), "Slices must have the same length");
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d += s;
+        }
+    }
+
+    /// Divide all elements of a slice by a constant
+    ///
+    /// Calculates `dst[i] = src[i] / c`.
+    ///
+    /// # Panics
+    /// Panics if `dst` and `src` have different lengths or `c` is zero.
+    fn div_slice(dst: &mut [Self], src: &[Self], c: Self) {
+        Self::mul_slice(dst, src, Self::ONE / c)
+    }
 }
@@ -0,0 +1,422 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Dense matrices over finite fields
+//!
+//! [`Matrix<F>`](Matrix) stores its entries row major. It provides the linear algebra used by
+//! erasure and network codes: products, Gaussian elimination, rank, determinant, inversion,
+//! solving linear systems and null spaces. Requires the `alloc` feature.
+//!
+//! ```rust
+//! use g2p::{g2p, matrix::Matrix, GaloisField};
+//!
+//! g2p!(GF256, 8);
+//! # fn main() {
+//! let points: Vec<GF256> = (1..5).map(GF256::from).collect();
+//! let v = Matrix::vandermonde(&points, 4);
+//! let inv = v.inverse().unwrap();
+//! assert_eq!(&v * &inv, Matrix::identity(4));
+//! assert_eq!(v.rank(), 4);
+//!
+//! let b = [GF256::from(1), GF256::from(2), GF256::from(3), GF256::from(4)];
+//! let x = v.solve(&b).unwrap();
+//! assert_eq!(v.mul_vec(&x), b);
+//! # }
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::{fmt, ops};
+
+use crate::GaloisField;
+
+/// Number of columns processed at once by [`Matrix::mul_vec`]
+const BLOCK: usize = 256;
+
+/// Dense matrix with entries in a finite field
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Matrix<F> {
+    rows: usize,
+    cols: usize,
+    data: Vec<F>,
+}
+
+impl<F: GaloisField> Matrix<F> {
+    /// Create a matrix with all entries zero
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: vec![F::ZERO; rows * cols],
+        }
+    }
+
+    /// Create the n x n identity matrix
+    pub fn identity(n: usize) -> Self {
+        let mut res = Self::zero(n, n);
+        for i in 0..n {
+            res[(i, i)] = F::ONE;
+        }
+        res
+    }
+
+    /// Create a matrix from its entries, row major
+    ///
+    /// # Panics
+    /// Panics if `data` does not have `rows * cols` entries.
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<F>) -> Self {
+        assert_eq!(data.len(), rows * cols, "Wrong number of entries");
+        Matrix { rows, cols, data }
+    }
+
+    /// Create a matrix from rows
+    ///
+    /// # Panics
+    /// Panics if the rows have different lengths.
+    pub fn from_rows<R: AsRef<[F]>>(rows: &[R]) -> Self {
+        let cols = rows.first().map_or(0, |r| r.as_ref().len());
+        let mut data = Vec::with_capacity(rows.len() * cols);
+        for r in rows {
+            assert_eq!(r.as_ref().len(), cols, "Rows have different lengths");
+            data.extend_from_slice(r.as_ref());
+        }
+        Matrix {
+            rows: rows.len(),
+            cols,
+            data,
+        }
+    }
+
+    /// Create the Vandermonde matrix with entries `points[i]^j`
+    ///
+    /// Any `cols` rows for distinct points are linearly independent.
+    pub fn vandermonde(points: &[F], cols: usize) -> Self {
+        let mut res = Self::zero(points.len(), cols);
+        for (row, &x) in res.data.chunks_mut(cols.max(1)).zip(points.iter()) {
+            let mut power = F::ONE;
+            for v in row.iter_mut() {
+                *v = power;
+                power *= x;
+            }
+        }
+        res
+    }
+
+    /// Create the Cauchy matrix with entries `1 / (xs[i] + ys[j])`
+    ///
+    /// Every square submatrix of a Cauchy matrix is invertible. Returns `None` if `xs` and `ys`
+    /// share an element.
+    pub fn cauchy(xs: &[F], ys: &[F]) -> Option<Self> {
+        let mut data = Vec::with_capacity(xs.len() * ys.len());
+        for &x in xs {
+            for &y in ys {
+                data.push((x + y).checked_inv()?);
+            }
+        }
+        Some(Self::from_vec(xs.len(), ys.len(), data))
+    }
+
+    /// Number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Check if the matrix has as many rows as columns
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    /// All entries, row major
+    pub fn as_slice(&self) -> &[F] {
+        &self.data
+    }
+
+    /// The entries of row `r`
+    pub fn row(&self, r: usize) -> &[F] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    /// The entries of row `r`, mutable
+    pub fn row_mut(&mut self, r: usize) -> &mut [F] {
+        &mut self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    /// Create a matrix from the given rows of this one
+    pub fn select_rows(&self, rows: &[usize]) -> Self {
+        let mut data = Vec::with_capacity(rows.len() * self.cols);
+        for &r in rows {
+            data.extend_from_slice(self.row(r));
+        }
+        Self::from_vec(rows.len(), self.cols, data)
+    }
+
+    /// Stack `other` below this matrix
+    ///
+    /// # Panics
+    /// Panics if the matrices have a different number of columns.
+    pub fn stack(&self, other: &Self) -> Self {
+        assert_eq!(self.cols, other.cols, "Matrices have different widths");
+        let mut data = self.data.clone();
+        data.extend_from_slice(&other.data);
+        Self::from_vec(self.rows + other.rows, self.cols, data)
+    }
+
+    /// Calculate the transposed matrix
+    pub fn transpose(&self) -> Self {
+        let mut res = Self::zero(self.cols, self.rows);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                res[(c, r)] = self[(r, c)];
+            }
+        }
+        res
+    }
+
+    /// Multiply with a column vector
+    ///
+    /// The columns are processed in blocks, so the used part of `x` stays in cache for large
+    /// matrices.
+    ///
+    /// # Panics
+    /// Panics if `x` does not have one entry per column.
+    pub fn mul_vec(&self, x: &[F]) -> Vec<F> {
+        assert_eq!(x.len(), self.cols, "Vector has the wrong length");
+        let mut res = vec![F::ZERO; self.rows];
+        for start in (0..self.cols).step_by(BLOCK) {
+            let end = (start + BLOCK).min(self.cols);
+            let block = &x[start..end];
+            for (r, y) in res.iter_mut().enumerate() {
+                let row = &self.data[r * self.cols + start..r * self.cols + end];
+                *y += row.iter().zip(block.iter()).map(|(&a, &b)| a * b).sum();
+            }
+        }
+        res
+    }
+
+    /// Bring the matrix into reduced row echelon form
+    ///
+    /// Returns the pivot column of each non-zero row. The number of pivots is the rank.
+    pub fn row_reduce(&mut self) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        for col in 0..self.cols {
+            let row = pivots.len();
+            if row == self.rows {
+                break;
+            }
+            let pivot = match (row..self.rows).find(|&r| !self[(r, col)].is_zero()) {
+                Some(p) => p,
+                None => continue,
+            };
+            self.swap_rows(row, pivot);
+
+            let scale = self[(row, col)].inverse();
+            self.row_mut(row).iter_mut().for_each(|v| *v *= scale);
+            for r in 0..self.rows {
+                let factor = self[(r, col)];
+                if r != row && !factor.is_zero() {
+                    self.add_row_multiple(r, row, factor);
+                }
+            }
+            pivots.push(col);
+        }
+        pivots
+    }
+
+    /// The rank of the matrix
+    pub fn rank(&self) -> usize {
+        self.clone().row_reduce().len()
+    }
+
+    /// The determinant, `None` if the matrix is not square
+    pub fn determinant(&self) -> Option<F> {
+        if !self.is_square() {
+            return None;
+        }
+        let n = self.rows;
+        let mut m = self.clone();
+        let mut det = F::ONE;
+        for col in 0..n {
+            let pivot = match (col..n).find(|&r| !m[(r, col)].is_zero()) {
+                Some(p) => p,
+                None => return Some(F::ZERO),
+            };
+            // Swapping rows negates the determinant, which does nothing in characteristic 2
+            m.swap_rows(col, pivot);
+            let lead = m[(col, col)];
+            det *= lead;
+            let inv = lead.inverse();
+            for r in col + 1..n {
+                let factor = m[(r, col)] * inv;
+                if !factor.is_zero() {
+                    m.add_row_multiple(r, col, factor);
+                }
+            }
+        }
+        Some(det)
+    }
+
+    /// Calculate the inverse, `None` if the matrix is not square or singular
+    pub fn inverse(&self) -> Option<Self> {
+        if !self.is_square() {
+            return None;
+        }
+        let n = self.rows;
+        let mut augmented = Self::zero(n, 2 * n);
+        for r in 0..n {
+            augmented.row_mut(r)[..n].copy_from_slice(self.row(r));
+            augmented[(r, n + r)] = F::ONE;
+        }
+        let pivots = augmented.row_reduce();
+        if !pivots.iter().copied().take(n).eq(0..n) {
+            return None;
+        }
+        let mut data = Vec::with_capacity(n * n);
+        for r in 0..n {
+            data.extend_from_slice(&augmented.row(r)[n..]);
+        }
+        Some(Self::from_vec(n, n, data))
+    }
+
+    /// Find x with `self * x = b`
+    ///
+    /// If there are multiple solutions, the free variables are set to zero. Returns `None` if
+    /// there is no solution.
+    ///
+    /// # Panics
+    /// Panics if `b` does not have one entry per row.
+    pub fn solve(&self, b: &[F]) -> Option<Vec<F>> {
+        assert_eq!(b.len(), self.rows, "Vector has the wrong length");
+        let cols = self.cols;
+        let mut augmented = Self::zero(self.rows, cols + 1);
+        for r in 0..self.rows {
+            augmented.row_mut(r)[..cols].copy_from_slice(self.row(r));
+            augmented[(r, cols)] = b[r];
+        }
+        let pivots = augmented.row_reduce();
+        if pivots.last() == Some(&cols) {
+            return None;
+        }
+        let mut x = vec![F::ZERO; cols];
+        for (r, &p) in pivots.iter().enumerate() {
+            x[p] = augmented[(r, cols)];
+        }
+        Some(x)
+    }
+
+    /// Calculate a basis of the null space, the vectors x with `self * x = 0`
+    pub fn null_space(&self) -> Vec<Vec<F>> {
+        let mut m = self.clone();
+        let pivots = m.row_reduce();
+        let mut basis = Vec::new();
+        for free in (0..self.cols).filter(|c| !pivots.contains(c)) {
+            let mut v = vec![F::ZERO; self.cols];
+            v[free] = F::ONE;
+            for (r, &p) in pivots.iter().enumerate() {
+                v[p] = -m[(r, free)];
+            }
+            basis.push(v);
+        }
+        basis
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for c in 0..self.cols {
+                self.data.swap(a * self.cols + c, b * self.cols + c);
+            }
+        }
+    }
+
+    /// row[dst] -= factor * row[src]
+    fn add_row_multiple(&mut self, dst: usize, src: usize, factor: F) {
+        let cols = self.cols;
+        let (dst_row, src_row) = if dst < src {
+            let (lo, hi) = self.data.split_at_mut(src * cols);
+            (&mut lo[dst * cols..(dst + 1) * cols], &hi[..cols])
+        } else {
+            let (lo, hi) = self.data.split_at_mut(dst * cols);
+            (&mut hi[..cols], &lo[src * cols..(src + 1) * cols])
+        };
+        // Subtraction is addition in characteristic 2
+        F::mul_add_slice(dst_row, src_row, factor);
+    }
+}
+
+impl<F> ops::Index<(usize, usize)> for Matrix<F> {
+    type Output = F;
+
+    fn index(&self, (r, c): (usize, usize)) -> &F {
+        assert!(r < self.rows && c < self.cols, "Index out of range");
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl<F> ops::IndexMut<(usize, usize)> for Matrix<F> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut F {
+        assert!(r < self.rows && c < self.cols, "Index out of range");
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+impl<'a, F: GaloisField> ops::Mul<&'a Matrix<F>> for &'a Matrix<F> {
+    type Output = Matrix<F>;
+
+    fn mul(self, rhs: &'a Matrix<F>) -> Matrix<F> {
+        assert_eq!(self.cols, rhs.rows, "Matrix dimensions do not match");
+        let mut res = Matrix::zero(self.rows, rhs.cols);
+        for r in 0..self.rows {
+            for (k, &a) in self.row(r).iter().enumerate() {
+                if !a.is_zero() {
+                    F::mul_add_slice(res.row_mut(r), rhs.row(k), a);
+                }
+            }
+        }
+        res
+    }
+}
+
+impl<F: GaloisField> ops::Mul for Matrix<F> {
+    type Output = Matrix<F>;
+
+    fn mul(self, rhs: Matrix<F>) -> Matrix<F> {
+        &self * &rhs
+    }
+}
+
+impl<'a, F: GaloisField> ops::Add<&'a Matrix<F>> for &'a Matrix<F> {
+    type Output = Matrix<F>;
+
+    fn add(self, rhs: &'a Matrix<F>) -> Matrix<F> {
+        assert!(
+            self.rows == rhs.rows && self.cols == rhs.cols,
+            "Matrix dimensions do not match"
+        );
+        let mut res = self.clone();
+        F::add_slice(&mut res.data, &rhs.data);
+        res
+    }
+}
+
+impl<F: GaloisField> ops::Add for Matrix<F> {
+    type Output = Matrix<F>;
+
+    fn add(self, rhs: Matrix<F>) -> Matrix<F> {
+        &self + &rhs
+    }
+}
+
+impl<F: fmt::Debug> fmt::Debug for Matrix<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cols = self.cols.max(1);
+        f.debug_list()
+            .entries(self.data.chunks(cols).take(self.rows))
+            .finish()
+    }
+}
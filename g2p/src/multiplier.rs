@@ -0,0 +1,179 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Multiplication with a fixed operand
+//!
+//! A [`Multiplier`] precomputes the products of a constant with every possible byte of the other
+//! operand. Applying it takes one table lookup per byte of the element, compared to one lookup
+//! per pair of bytes for regular multiplication. For fields with p <= 8, slices are multiplied
+//! using the vectorized kernels of the [`simd`](crate::simd) module.
+//!
+//! ```rust
+//! use g2p::{g2p, GaloisField};
+//!
+//! g2p!(GF256, 8);
+//! # fn main() {
+//! let c = GF256::from(0x53);
+//! let m = GF256::multiplier(c);
+//! assert_eq!(m.apply(0xca.into()), c * 0xca.into());
+//!
+//! let src = [GF256::from(1), GF256::from(2), GF256::from(3)];
+//! let mut dst = [GF256::ZERO; 3];
+//! m.mul_slice(&mut dst, &src);
+//! assert_eq!(dst, [c, c * 2.into(), c * 3.into()]);
+//! # }
+//! ```
+
+use core::fmt;
+
+use crate::{lazy::TableEntry, simd::NibbleTables, GaloisField};
+
+/// Fields that support precomputed multiplication with a constant
+///
+/// Implemented by all types generated by `g2p!`.
+pub trait MultiplierTables: GaloisField {
+    /// Tables storing the products with a constant
+    type Tables: Clone;
+
+    /// Compute the tables for multiplication with `c`
+    fn mul_tables(c: Self) -> Self::Tables;
+
+    /// Multiply `x` with the constant of `tables`
+    fn mul_with(tables: &Self::Tables, x: Self) -> Self;
+
+    /// Calculate `dst[i] = c * src[i]` with the constant `c` of `tables`
+    ///
+    /// # Panics
+    /// Panics if `dst` and `src` have different lengths.
+    fn mul_slice_with(tables: &Self::Tables, dst: &mut [Self], src: &[Self]) {
+        assert_eq!(dst.len(), src.len(), "Slices must have the same length");
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d = Self::mul_with(tables, s);
+        }
+    }
+
+    /// Calculate `dst[i] = dst[i] + c * src[i]` with the constant `c` of `tables`
+    ///
+    /// # Panics
+    /// Panics if `dst` and `src` have different lengths.
+    fn mul_add_slice_with(tables: &Self::Tables, dst: &mut [Self], src: &[Self]) {
+        assert_eq!(dst.len(), src.len(), "Slices must have the same length");
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d += Self::mul_with(tables, s);
+        }
+    }
+}
+
+/// Precomputed multiplication with a constant
+///
+/// Created by `multiplier(c)` on the types generated by `g2p!` or [`Multiplier::new`].
+#[derive(Clone)]
+pub struct Multiplier<F: MultiplierTables> {
+    constant: F,
+    tables: F::Tables,
+}
+
+impl<F: MultiplierTables> Multiplier<F> {
+    /// Precompute the multiplication with `c`
+    pub fn new(c: F) -> Self {
+        Multiplier {
+            constant: c,
+            tables: F::mul_tables(c),
+        }
+    }
+
+    /// The constant this multiplier was created for
+    pub fn constant(&self) -> F {
+        self.constant
+    }
+
+    /// Calculate `c * x`
+    #[inline]
+    pub fn apply(&self, x: F) -> F {
+        F::mul_with(&self.tables, x)
+    }
+
+    /// Calculate `dst[i] = c * src[i]`
+    ///
+    /// # Panics
+    /// Panics if `dst` and `src` have different lengths.
+    pub fn mul_slice(&self, dst: &mut [F], src: &[F]) {
+        F::mul_slice_with(&self.tables, dst, src)
+    }
+
+    /// Calculate `dst[i] = dst[i] + c * src[i]`
+    ///
+    /// # Panics
+    /// Panics if `dst` and `src` have different lengths.
+    pub fn mul_add_slice(&self, dst: &mut [F], src: &[F]) {
+        F::mul_add_slice_with(&self.tables, dst, src)
+    }
+}
+
+impl<F: MultiplierTables + fmt::Debug> fmt::Debug for Multiplier<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Multiplier")
+            .field("constant", &self.constant)
+            .finish()
+    }
+}
+
+/// Tables for fields with p <= 8
+///
+/// Contains the product with every byte as well as the [`NibbleTables`] used for slices.
+#[derive(Clone)]
+pub struct ByteTables {
+    /// `row[x]` is the product of the constant and `x`
+    pub row: [u8; 256],
+    /// Tables for the vectorized slice operations
+    pub nibbles: NibbleTables,
+}
+
+impl ByteTables {
+    /// Build the tables from a function calculating the product with the constant
+    pub fn from_fn<M: Fn(u8) -> u8>(mul: M) -> Self {
+        let mut row = [0; 256];
+        for (x, r) in row.iter_mut().enumerate() {
+            *r = mul(x as u8);
+        }
+        ByteTables {
+            row,
+            nibbles: NibbleTables::from_fn(|x| row[x as usize]),
+        }
+    }
+}
+
+/// Tables for fields with elements of `N` bytes
+///
+/// `rows[k][b]` is the product of the constant and `b << (8 * k)`.
+#[derive(Clone)]
+pub struct WideTables<T, const N: usize> {
+    /// The products with each byte of the element
+    pub rows: [[T; 256]; N],
+}
+
+impl<T: TableEntry, const N: usize> WideTables<T, N> {
+    /// Build the tables from a function calculating the product with the constant
+    pub fn from_fn<M: Fn(usize) -> T>(mul: M) -> Self {
+        let mut rows = [[T::ZERO; 256]; N];
+        for (k, row) in rows.iter_mut().enumerate() {
+            for (b, r) in row.iter_mut().enumerate() {
+                *r = mul(b << (8 * k));
+            }
+        }
+        WideTables { rows }
+    }
+
+    /// Multiply the constant with the element represented by `x`
+    #[inline]
+    pub fn mul(&self, x: usize) -> usize {
+        let mut res = 0;
+        for (k, row) in self.rows.iter().enumerate() {
+            res ^= row[(x >> (8 * k)) & 255].to_index();
+        }
+        res
+    }
+}
@@ -0,0 +1,401 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Polynomials over finite fields
+//!
+//! [`Poly<F>`](Poly) is the equivalent of [`G2Poly`](crate::G2Poly) for coefficients from any
+//! [`GaloisField`]. It is the basis of the coding and secret sharing schemes in this crate.
+//! Requires the `alloc` feature.
+//!
+//! ```rust
+//! use g2p::{g2p, GaloisField, poly::{Poly, gcd}};
+//!
+//! g2p!(GF256, 8);
+//! # fn main() {
+//! // (x + 1)(x + 2)
+//! let a = Poly::from_roots(&[GF256::from(1), GF256::from(2)]);
+//! assert_eq!(a.eval(GF256::from(2)), GF256::ZERO);
+//! assert_eq!(a.coeffs(), &[GF256::from(2), GF256::from(3), GF256::ONE]);
+//!
+//! // (x + 2)(x + 3)
+//! let b = Poly::from_roots(&[GF256::from(2), GF256::from(3)]);
+//! assert_eq!(gcd(&a, &b), Poly::from_roots(&[GF256::from(2)]));
+//! # }
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::{fmt, ops};
+
+use crate::GaloisField;
+
+/// Polynomial with coefficients in a finite field
+///
+/// The coefficients are stored lowest degree first, without trailing zeros. The zero polynomial
+/// has no coefficients.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Poly<F> {
+    coeffs: Vec<F>,
+}
+
+impl<F: GaloisField> Poly<F> {
+    /// Create a polynomial from its coefficients, lowest degree first
+    pub fn new(coeffs: Vec<F>) -> Self {
+        let mut res = Poly { coeffs };
+        res.normalize();
+        res
+    }
+
+    /// The zero polynomial
+    pub fn zero() -> Self {
+        Poly { coeffs: Vec::new() }
+    }
+
+    /// The constant `1` polynomial
+    pub fn one() -> Self {
+        Self::constant(F::ONE)
+    }
+
+    /// The `x` polynomial
+    pub fn x() -> Self {
+        Self::monomial(F::ONE, 1)
+    }
+
+    /// The constant polynomial `c`
+    pub fn constant(c: F) -> Self {
+        Self::new(vec![c])
+    }
+
+    /// The polynomial `c·x^n`
+    pub fn monomial(c: F, n: usize) -> Self {
+        let mut coeffs = vec![F::ZERO; n + 1];
+        coeffs[n] = c;
+        Self::new(coeffs)
+    }
+
+    /// The monic polynomial with the given roots: (x - r_0)(x - r_1)...
+    pub fn from_roots(roots: &[F]) -> Self {
+        let mut coeffs = Vec::with_capacity(roots.len() + 1);
+        coeffs.push(F::ONE);
+        for &r in roots {
+            // Multiply by (x - r)
+            coeffs.push(F::ZERO);
+            for i in (0..coeffs.len()).rev() {
+                let lower = if i > 0 { coeffs[i - 1] } else { F::ZERO };
+                coeffs[i] = lower - r * coeffs[i];
+            }
+        }
+        Self::new(coeffs)
+    }
+
+    /// Find the polynomial of lowest degree through the given points
+    ///
+    /// Uses Lagrange interpolation. Returns `None` if two points have the same x coordinate.
+    ///
+    /// # Example
+    /// ```rust
+    /// use g2p::{g2p, GaloisField, poly::Poly};
+    ///
+    /// g2p!(GF16, 4);
+    /// # fn main() {
+    /// let p = Poly::new(vec![GF16::from(3), GF16::from(7), GF16::from(1)]);
+    /// let points: Vec<_> = (1..4).map(|x| (GF16::from(x), p.eval(GF16::from(x)))).collect();
+    /// assert_eq!(Poly::interpolate(&points), Some(p));
+    /// # }
+    /// ```
+    pub fn interpolate(points: &[(F, F)]) -> Option<Self> {
+        for (i, &(x, _)) in points.iter().enumerate() {
+            if points[..i].iter().any(|&(other, _)| other == x) {
+                return None;
+            }
+        }
+
+        let xs: Vec<F> = points.iter().map(|&(x, _)| x).collect();
+        let master = Self::from_roots(&xs);
+        let mut res = Self::zero();
+        for &(x, y) in points {
+            // L(t) = master(t) / (t - x) is zero on all other points
+            let (basis, _) = master.divrem(&Self::from_roots(&[x]));
+            let scale = y / basis.eval(x);
+            res = res + basis.scale(scale);
+        }
+        Some(res)
+    }
+
+    fn normalize(&mut self) {
+        while self.coeffs.last().map_or(false, |c| c.is_zero()) {
+            self.coeffs.pop();
+        }
+    }
+
+    /// The coefficients, lowest degree first
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    /// The coefficient of `x^i`
+    pub fn coeff(&self, i: usize) -> F {
+        self.coeffs.get(i).copied().unwrap_or(F::ZERO)
+    }
+
+    /// The degree of the polynomial, `None` for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    /// Check if this is the zero polynomial
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// The coefficient of the highest power, `None` for the zero polynomial
+    pub fn leading_coeff(&self) -> Option<F> {
+        self.coeffs.last().copied()
+    }
+
+    /// Evaluate the polynomial at `x` using Horner's method
+    pub fn eval(&self, x: F) -> F {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(F::ZERO, |acc, &c| acc * x + c)
+    }
+
+    /// Multiply all coefficients with `c`
+    pub fn scale(&self, c: F) -> Self {
+        Self::new(self.coeffs.iter().map(|&x| x * c).collect())
+    }
+
+    /// Divide by the leading coefficient, so it becomes one
+    ///
+    /// The zero polynomial stays zero.
+    pub fn monic(&self) -> Self {
+        match self.leading_coeff() {
+            Some(lead) => self.scale(lead.inverse()),
+            None => Self::zero(),
+        }
+    }
+
+    /// Calculate the formal derivative
+    ///
+    /// In characteristic 2, the derivative of `x^i` is `x^(i-1)` for odd i and zero otherwise.
+    pub fn derivative(&self) -> Self {
+        let coeffs = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, &c)| if i % 2 == 1 { c } else { F::ZERO })
+            .collect();
+        Self::new(coeffs)
+    }
+
+    /// Calculate the composition `self(other(x))`
+    pub fn compose(&self, other: &Self) -> Self {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(Self::zero(), |acc, &c| &(&acc * other) + &Self::constant(c))
+    }
+
+    /// Calculate quotient and remainder of the division by `divisor`
+    ///
+    /// Returns `(q, r)` with `self = q * divisor + r` and deg r < deg divisor.
+    ///
+    /// # Panics
+    /// Panics if `divisor` is zero.
+    pub fn divrem(&self, divisor: &Self) -> (Self, Self) {
+        let lead_inv = divisor
+            .leading_coeff()
+            .expect("Division by zero polynomial")
+            .inverse();
+        let d = divisor.coeffs.len();
+        if self.coeffs.len() < d {
+            return (Self::zero(), self.clone());
+        }
+
+        let mut rem = self.coeffs.clone();
+        let mut quot = vec![F::ZERO; rem.len() - d + 1];
+        for shift in (0..quot.len()).rev() {
+            let factor = rem[shift + d - 1] * lead_inv;
+            quot[shift] = factor;
+            for (r, &c) in rem[shift..].iter_mut().zip(divisor.coeffs.iter()) {
+                *r -= factor * c;
+            }
+        }
+        rem.truncate(d - 1);
+        (Self::new(quot), Self::new(rem))
+    }
+}
+
+/// Calculate the monic greatest common divisor of `a` and `b`
+///
+/// Returns the zero polynomial if both inputs are zero.
+pub fn gcd<F: GaloisField>(a: &Poly<F>, b: &Poly<F>) -> Poly<F> {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while !b.is_zero() {
+        let (_, r) = a.divrem(&b);
+        a = b;
+        b = r;
+    }
+    a.monic()
+}
+
+/// Calculate the monic greatest common divisor with Bézout coefficients
+///
+/// Returns `(g, s, t)` such that `g == a * s + b * t`.
+///
+/// # Example
+/// ```rust
+/// use g2p::{g2p, poly::{Poly, extended_gcd}};
+///
+/// g2p!(GF16, 4);
+/// # fn main() {
+/// let a = Poly::from_roots(&[GF16::from(1), GF16::from(5)]);
+/// let b = Poly::from_roots(&[GF16::from(5), GF16::from(9)]);
+/// let (g, s, t) = extended_gcd(&a, &b);
+/// assert_eq!(g, Poly::from_roots(&[GF16::from(5)]));
+/// assert_eq!(&(&a * &s) + &(&b * &t), g);
+/// # }
+/// ```
+pub fn extended_gcd<F: GaloisField>(a: &Poly<F>, b: &Poly<F>) -> (Poly<F>, Poly<F>, Poly<F>) {
+    let (mut old_r, mut r) = (a.clone(), b.clone());
+    let (mut old_s, mut s) = (Poly::one(), Poly::zero());
+    let (mut old_t, mut t) = (Poly::zero(), Poly::one());
+
+    while !r.is_zero() {
+        let (quotient, rem) = old_r.divrem(&r);
+        old_r = core::mem::replace(&mut r, rem);
+
+        let new_s = &old_s - &(&quotient * &s);
+        old_s = core::mem::replace(&mut s, new_s);
+
+        let new_t = &old_t - &(&quotient * &t);
+        old_t = core::mem::replace(&mut t, new_t);
+    }
+
+    match old_r.leading_coeff() {
+        Some(lead) => {
+            let inv = lead.inverse();
+            (old_r.scale(inv), old_s.scale(inv), old_t.scale(inv))
+        }
+        None => (old_r, old_s, old_t),
+    }
+}
+
+impl<'a, F: GaloisField> ops::Add<&'a Poly<F>> for &'a Poly<F> {
+    type Output = Poly<F>;
+
+    fn add(self, rhs: &'a Poly<F>) -> Poly<F> {
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        Poly::new((0..len).map(|i| self.coeff(i) + rhs.coeff(i)).collect())
+    }
+}
+
+impl<'a, F: GaloisField> ops::Sub<&'a Poly<F>> for &'a Poly<F> {
+    type Output = Poly<F>;
+
+    fn sub(self, rhs: &'a Poly<F>) -> Poly<F> {
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        Poly::new((0..len).map(|i| self.coeff(i) - rhs.coeff(i)).collect())
+    }
+}
+
+impl<'a, F: GaloisField> ops::Mul<&'a Poly<F>> for &'a Poly<F> {
+    type Output = Poly<F>;
+
+    fn mul(self, rhs: &'a Poly<F>) -> Poly<F> {
+        if self.is_zero() || rhs.is_zero() {
+            return Poly::zero();
+        }
+        let mut coeffs = vec![F::ZERO; self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] += a * b;
+            }
+        }
+        Poly::new(coeffs)
+    }
+}
+
+impl<'a, F: GaloisField> ops::Div<&'a Poly<F>> for &'a Poly<F> {
+    type Output = Poly<F>;
+
+    fn div(self, rhs: &'a Poly<F>) -> Poly<F> {
+        self.divrem(rhs).0
+    }
+}
+
+impl<'a, F: GaloisField> ops::Rem<&'a Poly<F>> for &'a Poly<F> {
+    type Output = Poly<F>;
+
+    fn rem(self, rhs: &'a Poly<F>) -> Poly<F> {
+        self.divrem(rhs).1
+    }
+}
+
+macro_rules! impl_owned_op {
+    ($($trait:ident, $fn:ident;)*) => {
+        $(
+            impl<F: GaloisField> ops::$trait for Poly<F> {
+                type Output = Poly<F>;
+
+                fn $fn(self, rhs: Poly<F>) -> Poly<F> {
+                    ops::$trait::$fn(&self, &rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_owned_op! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+    Rem, rem;
+}
+
+impl<F: GaloisField> ops::Neg for Poly<F> {
+    type Output = Poly<F>;
+
+    fn neg(self) -> Poly<F> {
+        Poly::new(self.coeffs.into_iter().map(|c| -c).collect())
+    }
+}
+
+impl<F: fmt::Debug> fmt::Debug for Poly<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Poly").field(&self.coeffs).finish()
+    }
+}
+
+impl<F: GaloisField + fmt::Display> fmt::Display for Poly<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "Poly {{ 0 }}");
+        }
+
+        write!(f, "Poly {{ ")?;
+        let mut append = false;
+        for (p, &c) in self.coeffs.iter().enumerate().rev() {
+            if c.is_zero() {
+                continue;
+            }
+            if append {
+                write!(f, " + ")?;
+            }
+            match (p, c == F::ONE) {
+                (0, _) => write!(f, "{}", c)?,
+                (1, true) => write!(f, "x")?,
+                (1, false) => write!(f, "{}*x", c)?,
+                (_, true) => write!(f, "x^{}", p)?,
+                (_, false) => write!(f, "{}*x^{}", c, p)?,
+            }
+            append = true;
+        }
+        write!(f, " }}")
+    }
+}
@@ -0,0 +1,120 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Solving quadratic equations
+//!
+//! In characteristic 2 the usual formula does not work, as it divides by 2. Instead, the equation
+//! x^2 + a·x + b = 0 is transformed to y^2 + y = c with x = a·y and c = b / a^2. This has a
+//! solution exactly if the trace of c is zero, which is found with the half-trace for odd p and
+//! a trace based method for even p.
+//!
+//! ```rust
+//! use g2p::{g2p, quadratic::{solve_quadratic, QuadraticRoots}};
+//!
+//! g2p!(GF256, 8);
+//! # fn main() {
+//! let r1 = GF256::from(3);
+//! let r2 = GF256::from(10);
+//! // (x + r1)(x + r2) = x^2 + (r1 + r2)x + r1·r2
+//! match solve_quadratic(r1 + r2, r1 * r2) {
+//!     QuadraticRoots::Two(x, y) => assert!((x, y) == (r1, r2) || (x, y) == (r2, r1)),
+//!     _ => panic!("Expected two roots"),
+//! }
+//! # }
+//! ```
+
+use crate::{GaloisField, GF2};
+
+/// Solutions of a quadratic equation
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum QuadraticRoots<F> {
+    /// The equation has no solution in the field
+    Zero,
+    /// The equation has a single (double) root
+    One(F),
+    /// The equation has two distinct roots
+    Two(F, F),
+}
+
+impl<F: Copy> QuadraticRoots<F> {
+    /// Number of distinct roots
+    pub fn count(&self) -> usize {
+        match self {
+            QuadraticRoots::Zero => 0,
+            QuadraticRoots::One(_) => 1,
+            QuadraticRoots::Two(_, _) => 2,
+        }
+    }
+
+    /// Get the i-th root
+    pub fn get(&self, i: usize) -> Option<F> {
+        match (self, i) {
+            (QuadraticRoots::One(x), 0) | (QuadraticRoots::Two(x, _), 0) => Some(*x),
+            (QuadraticRoots::Two(_, y), 1) => Some(*y),
+            _ => None,
+        }
+    }
+}
+
+/// Find all x with x^2 + a·x + b = 0
+pub fn solve_quadratic<F: GaloisField>(a: F, b: F) -> QuadraticRoots<F> {
+    if a.is_zero() {
+        // x^2 = b has exactly one solution
+        return QuadraticRoots::One(b.sqrt());
+    }
+
+    let c = b / a.square();
+    match solve_artin_schreier(c) {
+        Some(y) => QuadraticRoots::Two(a * y, a * (y + F::ONE)),
+        None => QuadraticRoots::Zero,
+    }
+}
+
+/// Find y with y^2 + y = c
+///
+/// If y is a solution, y + 1 is the other one. Returns `None` if there is no solution, which is
+/// the case exactly if the trace of c is one.
+pub fn solve_artin_schreier<F: GaloisField>(c: F) -> Option<F> {
+    if c.trace() != GF2::ZERO {
+        return None;
+    }
+
+    let y = if F::DEGREE % 2 == 1 {
+        half_trace(c)
+    } else {
+        // IEEE 1363, A.4.7: with t of trace one, the following loop results in a solution.
+        let t = trace_one_element::<F>();
+        let mut z = F::ZERO;
+        let mut w = c;
+        for _ in 1..F::DEGREE {
+            z = z.square() + w.square() * t;
+            w = w.square() + c;
+        }
+        z
+    };
+    debug_assert!(y.square() + y == c);
+    Some(y)
+}
+
+/// Calculate the half-trace c + c^4 + c^16 + ... + c^(4^((p - 1) / 2)) for odd p
+fn half_trace<F: GaloisField>(c: F) -> F {
+    let mut res = c;
+    let mut y = c;
+    for _ in 0..(F::DEGREE - 1) / 2 {
+        y = y.square().square();
+        res += y;
+    }
+    res
+}
+
+/// Find an element with trace one, trying the powers of the generator
+fn trace_one_element<F: GaloisField>() -> F {
+    let mut t = F::GENERATOR;
+    while t.trace() == GF2::ZERO {
+        t *= F::GENERATOR;
+    }
+    t
+}
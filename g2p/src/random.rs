@@ -0,0 +1,80 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sampling random field elements
+//!
+//! With the `rand` feature, all types generated by `g2p!` implement
+//! `Distribution<Standard>`, so `rng.gen()` returns uniformly distributed elements. Uniform
+//! invertible, i.e. non-zero, elements are sampled with [`random_nonzero`] or the [`NonZero`]
+//! distribution.
+//!
+//! ```rust
+//! use g2p::{g2p, random::{random_nonzero, NonZero}, GaloisField};
+//! use rand::Rng;
+//!
+//! g2p!(GF1024, 10);
+//! # fn main() {
+//! let mut rng = rand::thread_rng();
+//! let a: GF1024 = rng.gen();
+//! let b: GF1024 = random_nonzero(&mut rng);
+//! let c: GF1024 = rng.sample(NonZero);
+//! assert_eq!(a * b / b, a);
+//! assert!(!c.is_zero());
+//! # }
+//! ```
+
+use rand::{
+    distributions::{Distribution, Standard},
+    Rng,
+};
+
+use crate::GaloisField;
+
+/// Uniform distribution over all non-zero elements
+///
+/// These are exactly the invertible elements of the field.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NonZero;
+
+impl<F: GaloisField> Distribution<F> for NonZero
+where
+    Standard: Distribution<F>,
+{
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> F {
+        // At least half of all elements are non-zero, so this takes 2 tries in the worst case on
+        // average.
+        loop {
+            let x: F = rng.gen();
+            if !x.is_zero() {
+                return x;
+            }
+        }
+    }
+}
+
+/// Sample a uniformly distributed non-zero element
+pub fn random_nonzero<F: GaloisField, R: Rng + ?Sized>(rng: &mut R) -> F
+where
+    Standard: Distribution<F>,
+{
+    NonZero.sample(rng)
+}
+
+/// Implement `Distribution<Standard>` for a generated type
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_rand {
+    ($ident:ident, $ty:ty) => {
+        impl ::g2p::rand::distributions::Distribution<$ident>
+            for ::g2p::rand::distributions::Standard
+        {
+            fn sample<R: ::g2p::rand::Rng + ?Sized>(&self, rng: &mut R) -> $ident {
+                // The lower bits of a uniform integer are uniform as well
+                $ident(rng.gen::<$ty>() & $ident::MASK)
+            }
+        }
+    };
+}
@@ -0,0 +1,328 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reed-Solomon error correction
+//!
+//! Unlike the [`erasure`](crate::erasure) code, which only recovers shards known to be missing,
+//! a [`ReedSolomonCode`] corrects errors at unknown positions. A code with `r` parity symbols
+//! corrects any combination of `t` errors and `e` erasures with `2t + e <= r`.
+//!
+//! Codewords are multiples of the generator polynomial g(x) = (x - a^c)(x - a^(c+1))...
+//! (x - a^(c+r-1)), where a is the generator and c the first consecutive root. Both are
+//! configurable to match existing standards, e.g. QR codes use c = 0 and CCSDS uses c = 112 with
+//! a = x^11 over the modulus 0x187.
+//!
+//! Decoding computes the syndromes, finds the error locator with the Berlekamp-Massey algorithm,
+//! its roots with a Chien search and the error values with Forney's algorithm. Requires the
+//! `alloc` feature.
+//!
+//! ```rust
+//! use g2p::{g2p, reed_solomon::ReedSolomonCode};
+//!
+//! g2p!(GF256, 8, modulus: 0b1_0001_1101);
+//! # fn main() {
+//! let code = ReedSolomonCode::<GF256>::new(4);
+//! let message: Vec<GF256> = b"Hello".iter().map(|&b| b.into()).collect();
+//! let codeword = code.encode(&message);
+//! assert_eq!(&codeword[..5], &message[..]);
+//!
+//! // Two errors at unknown positions
+//! let mut received = codeword.clone();
+//! received[1] = 0.into();
+//! received[6] += 1.into();
+//! assert_eq!(code.decode(&mut received, &[]), Ok(2));
+//! assert_eq!(received, codeword);
+//! # }
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{poly::Poly, GaloisField};
+
+/// Errors returned by [`ReedSolomonCode::decode`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DecodeError {
+    /// The codeword is longer than the order of the generator, or shorter than the parity
+    InvalidLength,
+    /// An erasure position is out of range or given twice
+    InvalidErasure,
+    /// There are more erasures than parity symbols
+    TooManyErasures,
+    /// The errors exceed the correction capability of the code
+    Uncorrectable,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength => write!(f, "Invalid codeword length"),
+            DecodeError::InvalidErasure => write!(f, "Invalid or duplicate erasure position"),
+            DecodeError::TooManyErasures => write!(f, "More erasures than parity symbols"),
+            DecodeError::Uncorrectable => write!(f, "Too many errors to correct"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Reed-Solomon code with a fixed number of parity symbols
+///
+/// Codewords are stored message first: `codeword[i]` is the coefficient of x^(n-1-i) for a
+/// codeword of length n. The message length can vary, as long as the codeword is not longer than
+/// the order of the generator, which is 2^p - 1 for a primitive generator.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReedSolomonCode<F> {
+    parity: usize,
+    first_root: usize,
+    generator: F,
+    order: usize,
+    generator_poly: Poly<F>,
+}
+
+impl<F: GaloisField> ReedSolomonCode<F> {
+    /// Create a code with `parity` parity symbols
+    ///
+    /// Uses [`GaloisField::GENERATOR`] and the first consecutive root a^0 = 1.
+    pub fn new(parity: usize) -> Self {
+        Self::with_params(parity, 0, F::GENERATOR)
+    }
+
+    /// Create a code with a custom first consecutive root and generator
+    ///
+    /// The roots of the generator polynomial are `generator^(first_root + i)` for
+    /// `i in 0..parity`.
+    ///
+    /// # Panics
+    /// Panics if `generator` is zero.
+    pub fn with_params(parity: usize, first_root: usize, generator: F) -> Self {
+        assert!(!generator.is_zero(), "Generator must not be zero");
+        let order = multiplicative_order(generator);
+
+        let mut generator_poly = Poly::one();
+        let mut root = generator.pow(first_root);
+        for _ in 0..parity {
+            generator_poly = &generator_poly * &Poly::from_roots(&[root]);
+            root *= generator;
+        }
+
+        ReedSolomonCode {
+            parity,
+            first_root,
+            generator,
+            order,
+            generator_poly,
+        }
+    }
+
+    /// Number of parity symbols
+    pub fn parity_len(&self) -> usize {
+        self.parity
+    }
+
+    /// Maximum length of a codeword
+    pub fn max_len(&self) -> usize {
+        self.order
+    }
+
+    /// The generator polynomial of the code
+    pub fn generator_poly(&self) -> &Poly<F> {
+        &self.generator_poly
+    }
+
+    /// Calculate the parity symbols for a message
+    ///
+    /// # Panics
+    /// Panics if the codeword would be longer than [`max_len`](Self::max_len).
+    pub fn parity(&self, message: &[F]) -> Vec<F> {
+        assert!(
+            message.len() + self.parity <= self.order,
+            "Codeword too long for the generator"
+        );
+        let g = self.generator_poly.coeffs();
+
+        // Long division of message * x^r by the monic generator polynomial, highest degree first
+        let mut rem = vec![F::ZERO; self.parity];
+        for &m in message {
+            let factor = m + rem.first().copied().unwrap_or(F::ZERO);
+            rem.rotate_left(1);
+            if let Some(last) = rem.last_mut() {
+                *last = F::ZERO;
+            }
+            for (r, &c) in rem.iter_mut().zip(g[..self.parity].iter().rev()) {
+                *r -= factor * c;
+            }
+        }
+        rem
+    }
+
+    /// Encode a message, returning the message followed by the parity symbols
+    ///
+    /// # Panics
+    /// Panics if the codeword would be longer than [`max_len`](Self::max_len).
+    pub fn encode(&self, message: &[F]) -> Vec<F> {
+        let mut res = Vec::with_capacity(message.len() + self.parity);
+        res.extend_from_slice(message);
+        res.extend(self.parity(message));
+        res
+    }
+
+    /// Calculate the syndromes c(a^(c+j)) for j in 0..parity
+    ///
+    /// All syndromes are zero exactly if `codeword` is a valid codeword.
+    pub fn syndromes(&self, codeword: &[F]) -> Vec<F> {
+        let mut x = self.generator.pow(self.first_root);
+        let mut res = Vec::with_capacity(self.parity);
+        for _ in 0..self.parity {
+            res.push(codeword.iter().fold(F::ZERO, |acc, &c| acc * x + c));
+            x *= self.generator;
+        }
+        res
+    }
+
+    /// Check if `codeword` is a valid codeword
+    pub fn is_valid(&self, codeword: &[F]) -> bool {
+        self.syndromes(codeword).iter().all(|s| s.is_zero())
+    }
+
+    /// Correct errors and erasures in place
+    ///
+    /// `erasures` are the indices of symbols known to be wrong. Returns the number of corrected
+    /// symbols, which excludes erasures that happened to have the right value.
+    pub fn decode(&self, codeword: &mut [F], erasures: &[usize]) -> Result<usize, DecodeError> {
+        let n = codeword.len();
+        if n > self.order || n < self.parity {
+            return Err(DecodeError::InvalidLength);
+        }
+        for (i, &e) in erasures.iter().enumerate() {
+            if e >= n || erasures[..i].contains(&e) {
+                return Err(DecodeError::InvalidErasure);
+            }
+        }
+        if erasures.len() > self.parity {
+            return Err(DecodeError::TooManyErasures);
+        }
+
+        let syndromes = self.syndromes(codeword);
+        if syndromes.iter().all(|s| s.is_zero()) {
+            return Ok(0);
+        }
+        let syndromes = Poly::new(syndromes);
+
+        // Erasure locator with roots at the inverse erasure locations
+        let mut locator = Poly::one();
+        for &e in erasures {
+            let x = self.generator.pow(n - 1 - e);
+            locator = &locator * &Poly::new(vec![F::ONE, x]);
+        }
+
+        // The modified syndromes S(x)·Γ(x) without the first e coefficients satisfy a linear
+        // recurrence given by the error locator.
+        let modified = &syndromes * &locator;
+        let sequence: Vec<F> = (erasures.len()..self.parity)
+            .map(|i| modified.coeff(i))
+            .collect();
+        let (errors, len) = berlekamp_massey(&sequence);
+        if errors.degree() != Some(len) || 2 * len + erasures.len() > self.parity {
+            return Err(DecodeError::Uncorrectable);
+        }
+        let locator = &locator * &errors;
+
+        // Chien search
+        let degree = locator.degree().unwrap_or(0);
+        let mut positions = Vec::with_capacity(degree);
+        let mut x = F::ONE;
+        for power in 0..n {
+            if locator.eval(x.inverse()).is_zero() {
+                positions.push((n - 1 - power, x));
+            }
+            x *= self.generator;
+        }
+        if positions.len() != degree {
+            return Err(DecodeError::Uncorrectable);
+        }
+
+        // Forney: e = X^(1-c) Ω(X^-1) / Ψ'(X^-1)
+        let evaluator = &(&syndromes * &locator) % &Poly::monomial(F::ONE, self.parity);
+        let derivative = locator.derivative();
+        let mut corrected = 0;
+        for &(pos, x) in positions.iter() {
+            let x_inv = x.inverse();
+            let denominator = derivative.eval(x_inv);
+            if denominator.is_zero() {
+                return Err(DecodeError::Uncorrectable);
+            }
+            let value = x * x_inv.pow(self.first_root) * evaluator.eval(x_inv) / denominator;
+            if !value.is_zero() {
+                codeword[pos] -= value;
+                corrected += 1;
+            }
+        }
+
+        if !self.is_valid(codeword) {
+            return Err(DecodeError::Uncorrectable);
+        }
+        Ok(corrected)
+    }
+}
+
+/// Find the shortest linear recurrence generating `sequence`
+///
+/// Returns the connection polynomial C(x) with C(0) = 1 and the length of the recurrence.
+fn berlekamp_massey<F: GaloisField>(sequence: &[F]) -> (Poly<F>, usize) {
+    let mut c = Poly::one();
+    let mut b = Poly::one();
+    let mut len = 0;
+    let mut shift = 1;
+    let mut last_discrepancy = F::ONE;
+
+    for n in 0..sequence.len() {
+        let discrepancy = (0..=len)
+            .filter(|&i| i <= n)
+            .fold(F::ZERO, |acc, i| acc + c.coeff(i) * sequence[n - i]);
+        if discrepancy.is_zero() {
+            shift += 1;
+            continue;
+        }
+
+        let update = Poly::monomial(discrepancy / last_discrepancy, shift);
+        let next = &c - &(&update * &b);
+        if 2 * len <= n {
+            b = core::mem::replace(&mut c, next);
+            len = n + 1 - len;
+            last_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            c = next;
+            shift += 1;
+        }
+    }
+    (c, len)
+}
+
+/// The smallest k > 0 with x^k = 1
+fn multiplicative_order<F: GaloisField>(x: F) -> usize {
+    let group = F::SIZE - 1;
+    let mut order = group;
+    let mut rest = group;
+    let mut factor = 2;
+    while rest > 1 {
+        if factor * factor > rest {
+            factor = rest;
+        }
+        if rest % factor == 0 {
+            while rest % factor == 0 {
+                rest /= factor;
+            }
+            while order % factor == 0 && x.pow(order / factor) == F::ONE {
+                order /= factor;
+            }
+        }
+        factor += 1;
+    }
+    order
+}
@@ -0,0 +1,397 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Random linear network coding
+//!
+//! A generation of g source symbols is sent as coded packets. Each packet carries a random linear
+//! combination of the symbols together with its coefficient vector. Any g packets with linearly
+//! independent coefficient vectors recover the generation.
+//!
+//! * [`Encoder`] creates packets from the source symbols.
+//! * [`Recoder`] creates new packets from received ones without decoding them, e.g. on relays in
+//!   a mesh network.
+//! * [`Decoder`] performs Gauss-Jordan elimination as packets arrive. It reports the rank after
+//!   each packet, and the generation as soon as the rank is full. Symbols that are already known
+//!   before that, e.g. from systematic packets, are available through [`Decoder::symbol`].
+//!
+//! Random coefficients can be drawn from a `rand` RNG with the `rand` feature, or from any
+//! function. Requires the `alloc` feature.
+//!
+//! ```rust
+//! use g2p::{g2p, rlnc::{Decoder, Encoder, Recoder}};
+//!
+//! g2p!(GF256, 8);
+//! # fn main() {
+//! let mut rng = rand::thread_rng();
+//! let symbols: Vec<Vec<GF256>> = (0..4_u8)
+//!     .map(|i| (0..8_u8).map(|j| GF256::from(i * 8 + j)).collect())
+//!     .collect();
+//! let encoder = Encoder::new(symbols.clone()).unwrap();
+//! let mut relay = Recoder::new(4, 8);
+//! let mut decoder = Decoder::new(4, 8);
+//! while !decoder.is_complete() {
+//!     relay.add(encoder.encode(&mut rng)).unwrap();
+//!     decoder.add(&relay.recode(&mut rng).unwrap()).unwrap();
+//! }
+//! assert_eq!(decoder.generation(), Some(symbols));
+//! # }
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::GaloisField;
+
+/// Errors returned by the RLNC codec
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RlncError {
+    /// The generation has no symbols
+    EmptyGeneration,
+    /// The symbols have length zero
+    EmptySymbol,
+    /// The symbols have different lengths
+    SymbolSizeMismatch,
+    /// The number of coefficients does not match the generation size
+    CoefficientCountMismatch,
+    /// The payload length does not match the symbol length
+    PayloadSizeMismatch,
+}
+
+impl fmt::Display for RlncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RlncError::EmptyGeneration => write!(f, "Generation has no symbols"),
+            RlncError::EmptySymbol => write!(f, "Symbols must not be empty"),
+            RlncError::SymbolSizeMismatch => write!(f, "Symbols have different lengths"),
+            RlncError::CoefficientCountMismatch => {
+                write!(f, "Coefficient count does not match the generation size")
+            }
+            RlncError::PayloadSizeMismatch => {
+                write!(f, "Payload length does not match the symbol length")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RlncError {}
+
+/// A coded packet: a linear combination of the source symbols
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CodedPacket<F> {
+    /// Coefficient of each source symbol
+    pub coefficients: Vec<F>,
+    /// The linear combination of the source symbols
+    pub payload: Vec<F>,
+}
+
+impl<F: GaloisField> CodedPacket<F> {
+    /// Check that the packet fits a generation of the given size and symbol length
+    fn check(&self, generation_size: usize, symbol_len: usize) -> Result<(), RlncError> {
+        if self.coefficients.len() != generation_size {
+            return Err(RlncError::CoefficientCountMismatch);
+        }
+        if self.payload.len() != symbol_len {
+            return Err(RlncError::PayloadSizeMismatch);
+        }
+        Ok(())
+    }
+
+    /// self += other * c
+    fn mul_add(&mut self, other: &Self, c: F) {
+        F::mul_add_slice(&mut self.coefficients, &other.coefficients, c);
+        F::mul_add_slice(&mut self.payload, &other.payload, c);
+    }
+
+    /// self *= c
+    fn scale(&mut self, c: F) {
+        self.coefficients.iter_mut().for_each(|x| *x *= c);
+        self.payload.iter_mut().for_each(|x| *x *= c);
+    }
+}
+
+/// Create a linear combination of packets with the given coefficients
+fn combine<'a, F, I>(packets: I, generation_size: usize, symbol_len: usize) -> CodedPacket<F>
+where
+    F: GaloisField + 'a,
+    I: Iterator<Item = (&'a CodedPacket<F>, F)>,
+{
+    let mut res = CodedPacket {
+        coefficients: vec![F::ZERO; generation_size],
+        payload: vec![F::ZERO; symbol_len],
+    };
+    for (packet, c) in packets {
+        if !c.is_zero() {
+            res.mul_add(packet, c);
+        }
+    }
+    res
+}
+
+/// Encoder for one generation of source symbols
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Encoder<F> {
+    // The source symbols as systematic packets
+    symbols: Vec<CodedPacket<F>>,
+}
+
+impl<F: GaloisField> Encoder<F> {
+    /// Create an encoder for the given source symbols, which must have the same length
+    pub fn new(symbols: Vec<Vec<F>>) -> Result<Self, RlncError> {
+        let len = symbols.first().ok_or(RlncError::EmptyGeneration)?.len();
+        if len == 0 {
+            return Err(RlncError::EmptySymbol);
+        }
+        if symbols.iter().any(|s| s.len() != len) {
+            return Err(RlncError::SymbolSizeMismatch);
+        }
+
+        let g = symbols.len();
+        let symbols = symbols
+            .into_iter()
+            .enumerate()
+            .map(|(i, payload)| {
+                let mut coefficients = vec![F::ZERO; g];
+                coefficients[i] = F::ONE;
+                CodedPacket {
+                    coefficients,
+                    payload,
+                }
+            })
+            .collect();
+        Ok(Encoder { symbols })
+    }
+
+    /// Number of source symbols g
+    pub fn generation_size(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Length of each symbol
+    pub fn symbol_len(&self) -> usize {
+        self.symbols[0].payload.len()
+    }
+
+    /// Get the uncoded packet for symbol `i`
+    ///
+    /// Sending the systematic packets first lets receivers use symbols before the generation is
+    /// complete.
+    ///
+    /// # Panics
+    /// Panics if `i` is not smaller than the generation size.
+    pub fn systematic(&self, i: usize) -> CodedPacket<F> {
+        self.symbols[i].clone()
+    }
+
+    /// Create a packet with the given coefficients
+    pub fn encode_coefficients(&self, coefficients: &[F]) -> Result<CodedPacket<F>, RlncError> {
+        if coefficients.len() != self.generation_size() {
+            return Err(RlncError::CoefficientCountMismatch);
+        }
+        Ok(combine(
+            self.symbols.iter().zip(coefficients.iter().copied()),
+            self.generation_size(),
+            self.symbol_len(),
+        ))
+    }
+
+    /// Create a packet with random coefficients
+    #[cfg(feature = "rand")]
+    pub fn encode<R>(&self, rng: &mut R) -> CodedPacket<F>
+    where
+        R: rand::Rng + ?Sized,
+        rand::distributions::Standard: rand::distributions::Distribution<F>,
+    {
+        self.encode_with(|| rng.gen())
+    }
+
+    /// Create a packet using `random` to generate the coefficients
+    pub fn encode_with<G: FnMut() -> F>(&self, mut random: G) -> CodedPacket<F> {
+        combine(
+            self.symbols.iter().map(|s| (s, random())),
+            self.generation_size(),
+            self.symbol_len(),
+        )
+    }
+}
+
+/// Recoder creating new packets from received ones without decoding
+///
+/// Only innovative packets are kept, so the storage is bounded by the generation size.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Recoder<F> {
+    // Used only to check for linear independence, the received packets are kept unchanged
+    decoder: Decoder<F>,
+    packets: Vec<CodedPacket<F>>,
+}
+
+impl<F: GaloisField> Recoder<F> {
+    /// Create a recoder for generations of `generation_size` symbols of length `symbol_len`
+    pub fn new(generation_size: usize, symbol_len: usize) -> Self {
+        Recoder {
+            decoder: Decoder::new(generation_size, symbol_len),
+            packets: Vec::new(),
+        }
+    }
+
+    /// Add a received packet
+    ///
+    /// Returns whether the packet was linearly independent of the packets received before.
+    pub fn add(&mut self, packet: CodedPacket<F>) -> Result<bool, RlncError> {
+        let innovative = self.decoder.add(&packet)?;
+        if innovative {
+            self.packets.push(packet);
+        }
+        Ok(innovative)
+    }
+
+    /// Rank of the received packets
+    pub fn rank(&self) -> usize {
+        self.packets.len()
+    }
+
+    /// Create a packet with random coefficients
+    ///
+    /// Returns `None` if no packets were received.
+    #[cfg(feature = "rand")]
+    pub fn recode<R>(&self, rng: &mut R) -> Option<CodedPacket<F>>
+    where
+        R: rand::Rng + ?Sized,
+        rand::distributions::Standard: rand::distributions::Distribution<F>,
+    {
+        self.recode_with(|| rng.gen())
+    }
+
+    /// Create a packet using `random` to generate the coefficients
+    ///
+    /// Returns `None` if no packets were received.
+    pub fn recode_with<G: FnMut() -> F>(&self, mut random: G) -> Option<CodedPacket<F>> {
+        if self.packets.is_empty() {
+            return None;
+        }
+        Some(combine(
+            self.packets.iter().map(|p| (p, random())),
+            self.decoder.generation_size(),
+            self.decoder.symbol_len(),
+        ))
+    }
+}
+
+/// Progressive Gauss-Jordan decoder
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Decoder<F> {
+    generation_size: usize,
+    symbol_len: usize,
+    // Rows of the reduced row echelon form, indexed by their pivot column
+    rows: Vec<Option<CodedPacket<F>>>,
+    rank: usize,
+}
+
+impl<F: GaloisField> Decoder<F> {
+    /// Create a decoder for generations of `generation_size` symbols of length `symbol_len`
+    pub fn new(generation_size: usize, symbol_len: usize) -> Self {
+        Decoder {
+            generation_size,
+            symbol_len,
+            rows: vec![None; generation_size],
+            rank: 0,
+        }
+    }
+
+    /// Number of source symbols g
+    pub fn generation_size(&self) -> usize {
+        self.generation_size
+    }
+
+    /// Length of each symbol
+    pub fn symbol_len(&self) -> usize {
+        self.symbol_len
+    }
+
+    /// Number of linearly independent packets received
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Check if the generation is decoded
+    pub fn is_complete(&self) -> bool {
+        self.rank == self.generation_size
+    }
+
+    /// Add a received packet
+    ///
+    /// Returns whether the packet increased the rank. Packets that are linear combinations of
+    /// earlier ones are discarded.
+    pub fn add(&mut self, packet: &CodedPacket<F>) -> Result<bool, RlncError> {
+        packet.check(self.generation_size, self.symbol_len)?;
+        if self.is_complete() {
+            return Ok(false);
+        }
+
+        // Eliminate all known pivots. The rows are fully reduced, so the order does not matter.
+        let mut packet = packet.clone();
+        for (col, row) in self.rows.iter().enumerate() {
+            if let Some(row) = row {
+                let c = packet.coefficients[col];
+                if !c.is_zero() {
+                    packet.mul_add(row, c);
+                }
+            }
+        }
+
+        let pivot = match packet.coefficients.iter().position(|c| !c.is_zero()) {
+            Some(pivot) => pivot,
+            None => return Ok(false),
+        };
+        packet.scale(packet.coefficients[pivot].inverse());
+
+        // Remove the new pivot from the other rows
+        for row in self.rows.iter_mut().flatten() {
+            let c = row.coefficients[pivot];
+            if !c.is_zero() {
+                row.mul_add(&packet, c);
+            }
+        }
+        self.rows[pivot] = Some(packet);
+        self.rank += 1;
+        Ok(true)
+    }
+
+    /// Get source symbol `i` if it is already decoded
+    ///
+    /// A symbol is known once its row contains no other unknown symbols, which can happen before
+    /// the rank is full.
+    ///
+    /// # Panics
+    /// Panics if `i` is not smaller than the generation size.
+    pub fn symbol(&self, i: usize) -> Option<&[F]> {
+        let row = self.rows[i].as_ref()?;
+        let unit = row
+            .coefficients
+            .iter()
+            .enumerate()
+            .all(|(j, c)| j == i || c.is_zero());
+        if unit {
+            Some(&row.payload)
+        } else {
+            None
+        }
+    }
+
+    /// Get the decoded source symbols, or `None` if the rank is not full yet
+    pub fn generation(&self) -> Option<Vec<Vec<F>>> {
+        if !self.is_complete() {
+            return None;
+        }
+        Some(
+            self.rows
+                .iter()
+                .flatten()
+                .map(|row| row.payload.clone())
+                .collect(),
+        )
+    }
+}
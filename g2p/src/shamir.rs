@@ -0,0 +1,185 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Shamir secret sharing
+//!
+//! Each symbol of the secret is the constant term of a random polynomial of degree t - 1. A share
+//! consists of the evaluations of these polynomials at a non-zero point. Any t shares determine
+//! the polynomials and thus the secret, while fewer shares reveal nothing about it.
+//!
+//! Splitting needs a source of random elements: [`split`] uses a `rand` RNG with the `rand`
+//! feature, and [`split_with`] takes any function. Requires the `alloc` feature.
+//!
+//! ```rust
+//! use g2p::{g2p, shamir::{combine, split}};
+//!
+//! g2p!(GF256, 8, modulus: 0b_1_0001_1011);
+//! # fn main() {
+//! let secret: Vec<GF256> = b"secret".iter().map(|&b| b.into()).collect();
+//! let shares = split(&secret, 3, 5, &mut rand::thread_rng()).unwrap();
+//! assert_eq!(combine(&shares[1..4]).unwrap(), secret);
+//! assert_eq!(combine(&[shares[4].clone(), shares[0].clone(), shares[2].clone()]).unwrap(), secret);
+//! # }
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{erasure::element, GaloisField};
+
+/// Errors returned by [`split`] and [`combine`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ShamirError {
+    /// The threshold is zero or larger than the number of shares
+    InvalidThreshold,
+    /// More shares requested than there are non-zero elements in the field
+    TooManyShares,
+    /// No shares were passed to [`combine`]
+    NoShares,
+    /// A share has the index zero, which would reveal the secret
+    InvalidIndex,
+    /// Two shares have the same index
+    DuplicateShare,
+    /// The shares have different lengths
+    ShareLengthMismatch,
+}
+
+impl fmt::Display for ShamirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShamirError::InvalidThreshold => write!(f, "Invalid threshold"),
+            ShamirError::TooManyShares => write!(f, "Too many shares for the field size"),
+            ShamirError::NoShares => write!(f, "No shares to combine"),
+            ShamirError::InvalidIndex => write!(f, "Share index must not be zero"),
+            ShamirError::DuplicateShare => write!(f, "Duplicate share index"),
+            ShamirError::ShareLengthMismatch => write!(f, "Shares have different lengths"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ShamirError {}
+
+/// A share of a secret
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Share<F> {
+    /// The point the polynomials were evaluated at, never zero
+    pub x: F,
+    /// The evaluation for each symbol of the secret
+    pub y: Vec<F>,
+}
+
+/// Split a secret into `count` shares, any `threshold` of which recover it
+///
+/// The shares are evaluated at the points represented by 1, 2, ..., count.
+#[cfg(feature = "rand")]
+pub fn split<F, R>(
+    secret: &[F],
+    threshold: usize,
+    count: usize,
+    rng: &mut R,
+) -> Result<Vec<Share<F>>, ShamirError>
+where
+    F: GaloisField,
+    R: rand::Rng + ?Sized,
+    rand::distributions::Standard: rand::distributions::Distribution<F>,
+{
+    split_with(secret, threshold, count, || rng.gen())
+}
+
+/// Split a secret using `random` to generate the polynomial coefficients
+///
+/// `random` must return uniformly distributed elements from a cryptographically secure source,
+/// otherwise the shares leak information about the secret.
+pub fn split_with<F, G>(
+    secret: &[F],
+    threshold: usize,
+    count: usize,
+    mut random: G,
+) -> Result<Vec<Share<F>>, ShamirError>
+where
+    F: GaloisField,
+    G: FnMut() -> F,
+{
+    if threshold == 0 || threshold > count {
+        return Err(ShamirError::InvalidThreshold);
+    }
+    if count >= F::SIZE {
+        return Err(ShamirError::TooManyShares);
+    }
+
+    let mut shares: Vec<Share<F>> = (1..=count)
+        .map(|i| Share {
+            x: element(i),
+            y: Vec::with_capacity(secret.len()),
+        })
+        .collect();
+
+    let mut coeffs = Vec::with_capacity(threshold);
+    for &s in secret {
+        coeffs.clear();
+        coeffs.push(s);
+        coeffs.extend((1..threshold).map(|_| random()));
+        for share in shares.iter_mut() {
+            let y = coeffs
+                .iter()
+                .rev()
+                .fold(F::ZERO, |acc, &c| acc * share.x + c);
+            share.y.push(y);
+        }
+    }
+    Ok(shares)
+}
+
+/// Recover the secret from shares
+///
+/// Uses Lagrange interpolation at zero. All shares are used, so passing fewer than the threshold
+/// returns a wrong secret instead of an error.
+pub fn combine<F: GaloisField>(shares: &[Share<F>]) -> Result<Vec<F>, ShamirError> {
+    let first = shares.first().ok_or(ShamirError::NoShares)?;
+    for (i, share) in shares.iter().enumerate() {
+        if share.x.is_zero() {
+            return Err(ShamirError::InvalidIndex);
+        }
+        if shares[..i]
+            .iter()
+            .any(|other| (other.x - share.x).is_zero())
+        {
+            return Err(ShamirError::DuplicateShare);
+        }
+        if share.y.len() != first.y.len() {
+            return Err(ShamirError::ShareLengthMismatch);
+        }
+    }
+
+    // Lagrange basis polynomials evaluated at zero: prod x_j / (x_j - x_i)
+    let weights: Vec<F> = shares
+        .iter()
+        .enumerate()
+        .map(|(i, share)| {
+            let (num, den) = shares
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold((F::ONE, F::ONE), |(num, den), (_, other)| {
+                    (num * other.x, den * (other.x - share.x))
+                });
+            num / den
+        })
+        .collect();
+
+    let mut secret = Vec::with_capacity(first.y.len());
+    for k in 0..first.y.len() {
+        secret.push(
+            shares
+                .iter()
+                .zip(weights.iter())
+                .map(|(share, &w)| share.y[k] * w)
+                .sum(),
+        );
+    }
+    Ok(secret)
+}
@@ -0,0 +1,327 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Vectorized multiplication of slices with a constant
+//!
+//! Multiplying with a constant `c` is linear, so `c * x` can be split into the product of the
+//! lower and the upper 4 bits of `x`: `c * x = c * (x & 0xf) ^ c * (x & 0xf0)`. Both parts can be
+//! looked up in 16 entry tables, which fit into a single SIMD register. The lookup itself is
+//! done with a byte shuffle (`pshufb` on x86, `tbl` on ARM), processing 16 or 32 elements at
+//! once.
+//!
+//! This is used by the slice operations of [`GaloisField`](crate::GaloisField) for all
+//! types generated by `g2p!` with p <= 8. The implementation is picked at runtime based on the
+//! available CPU features: AVX2, SSSE3, NEON or a portable fallback. Without the `std` feature,
+//! only the features enabled at compile time are used.
+
+/// Elements that are represented by a single byte
+///
+/// Implemented by `u8` and all types generated by `g2p!` with p <= 8. Slices of elements are
+/// processed as slices of their bytes.
+///
+/// # Safety
+/// Implementors must be `u8` or a `#[repr(transparent)]` wrapper around `u8`, and every byte
+/// value must be a valid element.
+pub unsafe trait ByteElement: Copy {}
+
+// SAFETY: u8 is a byte
+unsafe impl ByteElement for u8 {}
+
+fn as_bytes<F: ByteElement>(elements: &[F]) -> &[u8] {
+    // SAFETY: F has the layout of u8, see the safety requirements of ByteElement
+    unsafe { core::slice::from_raw_parts(elements.as_ptr() as *const u8, elements.len()) }
+}
+
+fn as_bytes_mut<F: ByteElement>(elements: &mut [F]) -> &mut [u8] {
+    // SAFETY: F has the layout of u8 and every byte is a valid element, see the safety
+    // requirements of ByteElement
+    unsafe { core::slice::from_raw_parts_mut(elements.as_mut_ptr() as *mut u8, elements.len()) }
+}
+
+/// Lookup tables for multiplication with a constant
+///
+/// `lo[n]` is the product of the constant and `n`, `hi[n]` the product of the constant and
+/// `n << 4`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct NibbleTables {
+    /// Products with the lower 4 bits
+    pub lo: [u8; 16],
+    /// Products with the upper 4 bits
+    pub hi: [u8; 16],
+}
+
+impl NibbleTables {
+    /// Build the tables from a function calculating the product with the constant
+    pub fn from_fn<M: Fn(u8) -> u8>(mul: M) -> Self {
+        let mut lo = [0; 16];
+        let mut hi = [0; 16];
+        for n in 0..16 {
+            lo[n as usize] = mul(n);
+            hi[n as usize] = mul(n << 4);
+        }
+        NibbleTables { lo, hi }
+    }
+
+    #[inline(always)]
+    fn mul(&self, x: u8) -> u8 {
+        self.lo[(x & 0xf) as usize] ^ self.hi[(x >> 4) as usize]
+    }
+}
+
+/// Calculate `dst[i] = c * src[i]`, where `tables` were created for `c`
+///
+/// # Panics
+/// Panics if `dst` and `src` have different lengths.
+pub fn mul_slice<F: ByteElement>(tables: &NibbleTables, dst: &mut [F], src: &[F]) {
+    assert_eq!(dst.len(), src.len(), "Slices must have the same length");
+    dispatch::<false>(tables, as_bytes_mut(dst), as_bytes(src))
+}
+
+/// Calculate `dst[i] = dst[i] + c * src[i]`, where `tables` were created for `c`
+///
+/// # Panics
+/// Panics if `dst` and `src` have different lengths.
+pub fn mul_add_slice<F: ByteElement>(tables: &NibbleTables, dst: &mut [F], src: &[F]) {
+    assert_eq!(dst.len(), src.len(), "Slices must have the same length");
+    dispatch::<true>(tables, as_bytes_mut(dst), as_bytes(src))
+}
+
+fn dispatch<const ADD: bool>(tables: &NibbleTables, dst: &mut [u8], src: &[u8]) {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if x86::has_avx2() {
+            // SAFETY: AVX2 support was checked above
+            return unsafe { x86::mul_avx2::<ADD>(tables, dst, src) };
+        }
+        if x86::has_ssse3() {
+            // SAFETY: SSSE3 support was checked above
+            return unsafe { x86::mul_ssse3::<ADD>(tables, dst, src) };
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        // SAFETY: NEON is enabled at compile time
+        return unsafe { neon::mul_neon::<ADD>(tables, dst, src) };
+    }
+
+    #[allow(unreachable_code)]
+    mul_portable::<ADD>(tables, dst, src)
+}
+
+#[inline(always)]
+fn mul_portable<const ADD: bool>(tables: &NibbleTables, dst: &mut [u8], src: &[u8]) {
+    for (d, &s) in dst.iter_mut().zip(src) {
+        let prod = tables.mul(s);
+        *d = if ADD { *d ^ prod } else { prod };
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    use super::{mul_portable, NibbleTables};
+
+    #[inline]
+    pub(super) fn has_avx2() -> bool {
+        #[cfg(feature = "std")]
+        {
+            std::is_x86_feature_detected!("avx2")
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            cfg!(target_feature = "avx2")
+        }
+    }
+
+    #[inline]
+    pub(super) fn has_ssse3() -> bool {
+        #[cfg(feature = "std")]
+        {
+            std::is_x86_feature_detected!("ssse3")
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            cfg!(target_feature = "ssse3")
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn mul_avx2<const ADD: bool>(
+        tables: &NibbleTables,
+        dst: &mut [u8],
+        src: &[u8],
+    ) {
+        let lo = _mm256_broadcastsi128_si256(_mm_loadu_si128(tables.lo.as_ptr() as *const __m128i));
+        let hi = _mm256_broadcastsi128_si256(_mm_loadu_si128(tables.hi.as_ptr() as *const __m128i));
+        let nibble = _mm256_set1_epi8(0x0f);
+
+        let mut dst_chunks = dst.chunks_exact_mut(32);
+        let mut src_chunks = src.chunks_exact(32);
+        for (d, s) in (&mut dst_chunks).zip(&mut src_chunks) {
+            let x = _mm256_loadu_si256(s.as_ptr() as *const __m256i);
+            let x_lo = _mm256_and_si256(x, nibble);
+            let x_hi = _mm256_and_si256(_mm256_srli_epi64(x, 4), nibble);
+            let mut prod =
+                _mm256_xor_si256(_mm256_shuffle_epi8(lo, x_lo), _mm256_shuffle_epi8(hi, x_hi));
+            if ADD {
+                prod = _mm256_xor_si256(prod, _mm256_loadu_si256(d.as_ptr() as *const __m256i));
+            }
+
+            _mm256_storeu_si256(d.as_mut_ptr() as *mut __m256i, prod);
+        }
+        mul_portable::<ADD>(tables, dst_chunks.into_remainder(), src_chunks.remainder());
+    }
+
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn mul_ssse3<const ADD: bool>(
+        tables: &NibbleTables,
+        dst: &mut [u8],
+        src: &[u8],
+    ) {
+        let lo = _mm_loadu_si128(tables.lo.as_ptr() as *const __m128i);
+        let hi = _mm_loadu_si128(tables.hi.as_ptr() as *const __m128i);
+        let nibble = _mm_set1_epi8(0x0f);
+
+        let mut dst_chunks = dst.chunks_exact_mut(16);
+        let mut src_chunks = src.chunks_exact(16);
+        for (d, s) in (&mut dst_chunks).zip(&mut src_chunks) {
+            let x = _mm_loadu_si128(s.as_ptr() as *const __m128i);
+            let x_lo = _mm_and_si128(x, nibble);
+            let x_hi = _mm_and_si128(_mm_srli_epi64(x, 4), nibble);
+            let mut prod = _mm_xor_si128(_mm_shuffle_epi8(lo, x_lo), _mm_shuffle_epi8(hi, x_hi));
+            if ADD {
+                prod = _mm_xor_si128(prod, _mm_loadu_si128(d.as_ptr() as *const __m128i));
+            }
+
+            _mm_storeu_si128(d.as_mut_ptr() as *mut __m128i, prod);
+        }
+        mul_portable::<ADD>(tables, dst_chunks.into_remainder(), src_chunks.remainder());
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon {
+    use core::arch::aarch64::*;
+
+    use super::{mul_portable, NibbleTables};
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn mul_neon<const ADD: bool>(
+        tables: &NibbleTables,
+        dst: &mut [u8],
+        src: &[u8],
+    ) {
+        let lo = vld1q_u8(tables.lo.as_ptr());
+        let hi = vld1q_u8(tables.hi.as_ptr());
+        let nibble = vdupq_n_u8(0x0f);
+
+        let mut dst_chunks = dst.chunks_exact_mut(16);
+        let mut src_chunks = src.chunks_exact(16);
+        for (d, s) in (&mut dst_chunks).zip(&mut src_chunks) {
+            let x = vld1q_u8(s.as_ptr());
+            let x_lo = vandq_u8(x, nibble);
+            let x_hi = vshrq_n_u8(x, 4);
+            let mut prod = veorq_u8(vqtbl1q_u8(lo, x_lo), vqtbl1q_u8(hi, x_hi));
+            if ADD {
+                prod = veorq_u8(prod, vld1q_u8(d.as_ptr()));
+            }
+
+            vst1q_u8(d.as_mut_ptr(), prod);
+        }
+        mul_portable::<ADD>(tables, dst_chunks.into_remainder(), src_chunks.remainder());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Multiplication in GF(2^8) with the Rijndael modulus
+    fn gf_mul(a: u8, b: u8) -> u8 {
+        let (mut a, mut b, mut res) = (a, b, 0);
+        while b != 0 {
+            if b & 1 != 0 {
+                res ^= a;
+            }
+            a = (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 };
+            b >>= 1;
+        }
+        res
+    }
+
+    fn check<K: Fn(&NibbleTables, &mut [u8], &[u8], bool)>(kernel: K) {
+        let mut src = [0; 100];
+        for (i, s) in src.iter_mut().enumerate() {
+            *s = (i * 37 + 11) as u8;
+        }
+        for &c in &[0, 1, 2, 0x53, 0xca, 0xff] {
+            let tables = NibbleTables::from_fn(|x| gf_mul(c, x));
+            for &len in &[0, 1, 15, 16, 17, 31, 32, 33, 100] {
+                let mut dst = [0x5a; 100];
+                kernel(&tables, &mut dst[..len], &src[..len], false);
+                for i in 0..len {
+                    assert_eq!(dst[i], gf_mul(c, src[i]));
+                }
+
+                let mut dst = [0x5a; 100];
+                kernel(&tables, &mut dst[..len], &src[..len], true);
+                for i in 0..len {
+                    assert_eq!(dst[i], 0x5a ^ gf_mul(c, src[i]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        check(|t, d, s, add| {
+            if add {
+                mul_add_slice(t, d, s)
+            } else {
+                mul_slice(t, d, s)
+            }
+        });
+    }
+
+    #[test]
+    fn test_portable() {
+        check(|t, d, s, add| {
+            if add {
+                mul_portable::<true>(t, d, s)
+            } else {
+                mul_portable::<false>(t, d, s)
+            }
+        });
+    }
+
+    #[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+    #[test]
+    fn test_x86() {
+        if x86::has_ssse3() {
+            check(|t, d, s, add| unsafe {
+                if add {
+                    x86::mul_ssse3::<true>(t, d, s)
+                } else {
+                    x86::mul_ssse3::<false>(t, d, s)
+                }
+            });
+        }
+        if x86::has_avx2() {
+            check(|t, d, s, add| unsafe {
+                if add {
+                    x86::mul_avx2::<true>(t, d, s)
+                } else {
+                    x86::mul_avx2::<false>(t, d, s)
+                }
+            });
+        }
+    }
+}
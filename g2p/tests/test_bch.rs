@@ -0,0 +1,116 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{bch::Bch, g2p, poly::Poly, reed_solomon::DecodeError, G2Poly, GaloisField, GF2};
+
+g2p!(GF16, 4, modulus: 0b10011);
+g2p!(GF64, 6);
+g2p!(GF8192, 13);
+
+fn bits(n: usize, seed: u64) -> Vec<GF2> {
+    let mut state = seed;
+    (0..n)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            if (state >> 40) & 1 == 1 {
+                GF2::ONE
+            } else {
+                GF2::ZERO
+            }
+        })
+        .collect()
+}
+
+// Codeword as polynomial, reversing the message first layout
+fn as_poly(codeword: &[GF2]) -> Poly<GF2> {
+    Poly::new(codeword.iter().rev().copied().collect())
+}
+
+#[test]
+fn test_known_generators() {
+    // Standard generators of the (15, k) codes for x^4 + x + 1
+    let expected = [(3, 0b1_0011), (5, 0b1_1101_0001), (7, 0b101_0011_0111)];
+    for &(distance, generator) in expected.iter() {
+        let code = Bch::<GF16>::new(distance).unwrap();
+        assert_eq!(code.generator_g2poly(), Some(G2Poly(generator)));
+        assert_eq!(code.max_len(), 15);
+        assert_eq!(code.correction_capability(), distance / 2);
+    }
+    let code = Bch::<GF16>::new(7).unwrap();
+    assert_eq!(
+        code.minimal_polynomials(),
+        &[G2Poly(0b1_0011), G2Poly(0b1_1111), G2Poly(0b111)]
+    );
+    assert_eq!(code.max_message_len(), 5);
+
+    assert!(Bch::<GF16>::new(1).is_none());
+    assert!(Bch::<GF16>::new(16).is_none());
+}
+
+#[test]
+fn test_encode_divisible() {
+    let code = Bch::<GF64>::new(11).unwrap();
+    let generator = code.generator();
+    assert_eq!(generator.degree(), Some(code.parity_len()));
+    for seed in 0..10 {
+        let message = bits(code.max_message_len() - seed as usize, seed);
+        let codeword = code.encode(&message);
+        assert_eq!(&codeword[..message.len()], &message[..]);
+        assert!((&as_poly(&codeword) % &generator).is_zero());
+        assert!(code.is_valid(&codeword));
+    }
+}
+
+#[test]
+fn test_correct_errors() {
+    let code = Bch::<GF64>::new(9).unwrap();
+    let t = code.correction_capability();
+    for seed in 0..20 {
+        let codeword = code.encode(&bits(code.max_message_len(), seed));
+        let mut received = codeword.clone();
+        let errors = seed as usize % (t + 1);
+        for i in 0..errors {
+            received[(i * 13 + seed as usize) % codeword.len()] += GF2::ONE;
+        }
+        assert_eq!(code.decode(&mut received), Ok(errors));
+        assert_eq!(received, codeword);
+    }
+}
+
+#[test]
+fn test_beyond_capability() {
+    let code = Bch::<GF16>::new(5).unwrap();
+    let codeword = code.encode(&bits(7, 1));
+    let mut received = codeword.clone();
+    for &i in [0, 4, 9].iter() {
+        received[i] += GF2::ONE;
+    }
+    match code.decode(&mut received) {
+        Ok(_) => assert!(code.is_valid(&received)),
+        Err(e) => assert_eq!(e, DecodeError::Uncorrectable),
+    }
+}
+
+#[test]
+fn test_nand_gf8192() {
+    // Shortened code for a 512 byte sector correcting 8 bit errors
+    let code = Bch::<GF8192>::new(17).unwrap();
+    assert_eq!(code.parity_len(), 8 * 13);
+    assert_eq!(code.generator_g2poly(), None);
+
+    let message = bits(512 * 8, 3);
+    let codeword = code.encode(&message);
+    assert!((&as_poly(&codeword) % &code.generator()).is_zero());
+
+    let mut received = codeword.clone();
+    for i in 0..8 {
+        received[i * 517 + 3] += GF2::ONE;
+    }
+    assert_eq!(code.decode(&mut received), Ok(8));
+    assert_eq!(received, codeword);
+}
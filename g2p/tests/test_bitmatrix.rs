@@ -0,0 +1,182 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    bitmatrix::{BitMatrix, Slot, XorOp, XorSchedule},
+    g2p,
+    matrix::Matrix,
+    GaloisField,
+};
+
+g2p!(GF16, 4);
+g2p!(GF256, 8);
+
+fn cauchy256(k: u8, m: u8) -> Matrix<GF256> {
+    let xs: Vec<GF256> = (0..m).map(GF256::from).collect();
+    let ys: Vec<GF256> = (m..m + k).map(GF256::from).collect();
+    Matrix::cauchy(&xs, &ys).unwrap()
+}
+
+/// Pseudo random packets
+fn packets(count: usize, len: usize) -> Vec<Vec<u8>> {
+    let mut state = 0x2545_f491_u32;
+    (0..count)
+        .map(|_| {
+            (0..len)
+                .map(|_| {
+                    state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    (state >> 16) as u8
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn run(schedule: &XorSchedule, inputs: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let len = inputs[0].len();
+    let inputs: Vec<&[u8]> = inputs.iter().map(|p| &p[..]).collect();
+    let mut outputs = vec![vec![0xff; len]; schedule.outputs()];
+    let mut refs: Vec<&mut [u8]> = outputs.iter_mut().map(|p| &mut p[..]).collect();
+    schedule.apply(&inputs, &mut refs);
+    outputs
+}
+
+/// Bit `t` of the packets of shard `shard` as element of GF(2^8)
+fn element(packets: &[Vec<u8>], shard: usize, t: usize) -> GF256 {
+    let mut v = 0;
+    for a in 0..8 {
+        v |= ((packets[shard * 8 + a][t / 8] >> (t % 8)) & 1) << a;
+    }
+    GF256::from(v)
+}
+
+#[test]
+fn test_element_matrix() {
+    for a in 0..16 {
+        let ma = BitMatrix::from_element(GF16::from(a));
+        assert_eq!((ma.rows(), ma.cols()), (4, 4));
+        for b in 0..16 {
+            let mb = BitMatrix::from_element(GF16::from(b));
+            let prod = BitMatrix::from_element(GF16::from(a) * GF16::from(b));
+            assert_eq!(&ma * &mb, prod);
+        }
+    }
+    assert_eq!(BitMatrix::from_element(GF256::ONE), BitMatrix::identity(8));
+    assert_eq!(BitMatrix::from_element(GF256::ZERO), BitMatrix::zero(8, 8));
+
+    // Multiplication with x shifts up and reduces with x^4 = x + 1
+    let x = BitMatrix::from_element(GF16::from(2));
+    assert_eq!(format!("{:?}", x), "[0001, 1001, 0100, 0010]");
+}
+
+#[test]
+fn test_expand_matrix() {
+    let a = cauchy256(4, 3);
+    let b = cauchy256(2, 4);
+    let ba = BitMatrix::from_matrix(&a);
+    assert_eq!((ba.rows(), ba.cols()), (24, 32));
+    assert_eq!(
+        BitMatrix::from_matrix(&(&a * &b)),
+        &ba * &BitMatrix::from_matrix(&b)
+    );
+
+    let block = BitMatrix::from_element(a[(1, 2)]);
+    for i in 0..8 {
+        for j in 0..8 {
+            assert_eq!(ba.get(8 + i, 16 + j), block.get(i, j));
+        }
+    }
+    assert_eq!(ba.ones(), (0..ba.rows()).map(|r| ba.row_ones(r)).sum());
+}
+
+#[test]
+fn test_schedules() {
+    let m = cauchy256(6, 3);
+    let bits = BitMatrix::from_matrix(&m);
+    let direct = XorSchedule::direct(&bits);
+    let cse = XorSchedule::with_cse(&bits);
+    assert_eq!(direct.xor_count(), bits.ones() - bits.rows());
+    assert!(cse.xor_count() < direct.xor_count());
+    assert_eq!(direct.temporaries(), 0);
+    assert!(cse.temporaries() > 0);
+    assert_eq!((cse.inputs(), cse.outputs()), (48, 24));
+
+    let data = packets(48, 16);
+    let out = run(&cse, &data);
+    assert_eq!(out, run(&direct, &data));
+
+    // Bit t of all packets forms a codeword over GF(2^8)
+    for t in 0..16 * 8 {
+        let shards: Vec<GF256> = (0..6).map(|j| element(&data, j, t)).collect();
+        let parity = m.mul_vec(&shards);
+        for (i, &p) in parity.iter().enumerate() {
+            assert_eq!(element(&out, i, t), p);
+        }
+    }
+}
+
+#[test]
+fn test_schedule_edge_cases() {
+    // Zero rows are cleared, identity rows copied
+    let mut bits = BitMatrix::identity(3);
+    bits.set(1, 1, false);
+    let schedule = XorSchedule::with_cse(&bits);
+    assert_eq!(
+        schedule.ops(),
+        [
+            XorOp::Copy {
+                src: Slot::Input(0),
+                dst: Slot::Output(0)
+            },
+            XorOp::Zero {
+                dst: Slot::Output(1)
+            },
+            XorOp::Copy {
+                src: Slot::Input(2),
+                dst: Slot::Output(2)
+            },
+        ]
+    );
+    let out = run(&schedule, &packets(3, 4));
+    assert_eq!(out[1], [0; 4]);
+    assert_eq!(out[2], packets(3, 4)[2]);
+
+    // The shared pair is computed once
+    let mut bits = BitMatrix::zero(2, 3);
+    for &(r, c) in &[(0, 0), (0, 1), (0, 2), (1, 0), (1, 1)] {
+        bits.set(r, c, true);
+    }
+    let schedule = XorSchedule::with_cse(&bits);
+    assert_eq!(schedule.xor_count(), 2);
+    assert_eq!(schedule.temporaries(), 1);
+    assert_eq!(
+        schedule.ops()[..2],
+        [
+            XorOp::Copy {
+                src: Slot::Input(0),
+                dst: Slot::Temp(0)
+            },
+            XorOp::Xor {
+                src: Slot::Input(1),
+                dst: Slot::Temp(0)
+            },
+        ]
+    );
+    let data = packets(3, 5);
+    let out = run(&schedule, &data);
+    let expected: Vec<u8> = (0..5).map(|i| data[0][i] ^ data[1][i]).collect();
+    assert_eq!(out[1], expected);
+
+    assert_eq!(XorSchedule::with_cse(&BitMatrix::zero(2, 0)).ops().len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Packets have different lengths")]
+fn test_apply_length_mismatch() {
+    let schedule = XorSchedule::direct(&BitMatrix::identity(2));
+    let mut out = [0; 3];
+    schedule.apply(&[&[1, 2, 3], &[4, 5]], &mut [&mut out, &mut [0; 3]]);
+}
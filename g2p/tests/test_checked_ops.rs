@@ -0,0 +1,138 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{g2p, GaloisField};
+
+g2p!(GF2, 1);
+g2p!(GF16, 4);
+g2p!(GF256, 8);
+g2p!(CtGF256, 8, constant_time: true);
+g2p!(GF1024, 10);
+g2p!(GF1M, 20, tables: lazy);
+
+fn check_checked_ops<F: GaloisField + std::fmt::Debug>(elements: impl Iterator<Item = F>) {
+    assert!(F::ZERO.is_zero());
+    assert!(!F::ONE.is_zero());
+    assert_eq!(F::ZERO, F::ZERO.inverse());
+    assert_eq!(None, F::ZERO.checked_inv());
+    assert_eq!(None, F::ONE.checked_div(F::ZERO));
+    assert_eq!(Some(F::ZERO), F::ZERO.checked_div(F::ONE));
+
+    for x in elements.filter(|x| !x.is_zero()) {
+        assert_eq!(F::ONE, x * x.inverse());
+        assert_eq!(Some(x.inverse()), x.checked_inv());
+        assert_eq!(Some(F::ONE / x), x.checked_inv());
+        assert_eq!(Some(F::GENERATOR / x), F::GENERATOR.checked_div(x));
+        assert_eq!(None, x.checked_div(F::ZERO));
+    }
+}
+
+#[test]
+fn test_checked_ops() {
+    check_checked_ops((0..2).map(GF2::from));
+    check_checked_ops((0..16).map(GF16::from));
+    check_checked_ops((0..=255).map(GF256::from));
+    check_checked_ops((0..=255).map(CtGF256::from));
+    check_checked_ops((0..1024).map(GF1024::from));
+    check_checked_ops((0..1 << 20).step_by(997).map(GF1M::from));
+}
+
+#[test]
+fn test_unreduced_zero() {
+    // Bits outside of the field are ignored, like in the arithmetic operations
+    assert!(GF16(0x30).is_zero());
+    assert_eq!(None, GF16(0x30).checked_inv());
+    assert_eq!(None, GF16::ONE.checked_div(GF16(0x10)));
+    assert_eq!(GF16(0x13).inverse(), GF16(0x03).inverse());
+}
+
+#[test]
+fn test_default_impls() {
+    // Uses the provided trait methods instead of the generated ones
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Wrapper(GF256);
+
+    impl std::ops::Add for Wrapper {
+        type Output = Self;
+        fn add(self, rhs: Self) -> Self {
+            Wrapper(self.0 + rhs.0)
+        }
+    }
+    impl std::ops::AddAssign for Wrapper {
+        fn add_assign(&mut self, rhs: Self) {
+            self.0 += rhs.0
+        }
+    }
+    impl std::iter::Sum for Wrapper {
+        fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+            Wrapper(iter.map(|w| w.0).sum())
+        }
+    }
+    impl std::ops::Sub for Wrapper {
+        type Output = Self;
+        fn sub(self, rhs: Self) -> Self {
+            Wrapper(self.0 - rhs.0)
+        }
+    }
+    impl std::ops::SubAssign for Wrapper {
+        fn sub_assign(&mut self, rhs: Self) {
+            self.0 -= rhs.0
+        }
+    }
+    impl std::ops::Neg for Wrapper {
+        type Output = Self;
+        fn neg(self) -> Self {
+            self
+        }
+    }
+    impl std::ops::Mul for Wrapper {
+        type Output = Self;
+        fn mul(self, rhs: Self) -> Self {
+            Wrapper(self.0 * rhs.0)
+        }
+    }
+    impl std::ops::MulAssign for Wrapper {
+        fn mul_assign(&mut self, rhs: Self) {
+            self.0 *= rhs.0
+        }
+    }
+    impl std::iter::Product for Wrapper {
+        fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+            Wrapper(iter.map(|w| w.0).product())
+        }
+    }
+    impl std::ops::Div for Wrapper {
+        type Output = Self;
+        fn div(self, rhs: Self) -> Self {
+            Wrapper(self.0 / rhs.0)
+        }
+    }
+    impl std::ops::DivAssign for Wrapper {
+        fn div_assign(&mut self, rhs: Self) {
+            self.0 /= rhs.0
+        }
+    }
+    impl GaloisField for Wrapper {
+        const SIZE: usize = GF256::SIZE;
+        const ZERO: Self = Wrapper(GF256::ZERO);
+        const ONE: Self = Wrapper(GF256::ONE);
+        const GENERATOR: Self = Wrapper(GF256::GENERATOR);
+        const MODULUS: g2p::G2Poly = GF256::MODULUS;
+
+        type Repr = u8;
+        const DEGREE: u32 = 8;
+
+        fn to_repr(self) -> u8 {
+            self.0.to_repr()
+        }
+
+        fn from_repr(repr: u8) -> Option<Self> {
+            GF256::from_repr(repr).map(Wrapper)
+        }
+    }
+
+    check_checked_ops((0..=255).map(|v| Wrapper(GF256::from(v))));
+}
@@ -0,0 +1,91 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    g2p,
+    subtle::{Choice, ConditionallySelectable, ConstantTimeEq},
+    GaloisField,
+};
+
+g2p!(GF256, 8, modulus: 0b1_0001_1011, constant_time: true);
+g2p!(Table256, 8, modulus: 0b1_0001_1011);
+g2p!(GF2, 1, constant_time: true);
+g2p!(GF1024, 10, constant_time: true);
+g2p!(Table1024, 10);
+g2p!(GF1M, 20, constant_time: true);
+
+#[test]
+fn test_rijndael() {
+    let b = GF256::from(1);
+    let c = GF256::from(0x53);
+    let d = GF256::from(0xca);
+
+    assert_eq!(c * d, b);
+    assert_eq!(b / c, d);
+    assert_eq!(b / d, c);
+}
+
+#[test]
+fn test_same_as_tables() {
+    for i in 0..=255 {
+        for j in 0..=255 {
+            assert_eq!((GF256(i) * GF256(j)).0, (Table256(i) * Table256(j)).0);
+        }
+        if i != 0 {
+            assert_eq!((GF256::ONE / GF256(i)).0, (Table256::ONE / Table256(i)).0);
+        }
+    }
+
+    for i in (0..1024).step_by(3) {
+        for j in (1..1024).step_by(5) {
+            assert_eq!((GF1024(i) / GF1024(j)).0, (Table1024(i) / Table1024(j)).0);
+        }
+    }
+}
+
+#[test]
+fn test_small_and_large() {
+    assert_eq!(GF2::ONE * GF2::ONE, GF2::ONE);
+    assert_eq!(GF2::ONE / GF2::ONE, GF2::ONE);
+    assert_eq!(GF2::ZERO * GF2::ONE, GF2::ZERO);
+
+    let a = GF1M::from(123_456);
+    let b = GF1M::from(654_321);
+    assert_eq!(a * b / b, a);
+    assert_eq!(GF1M::GENERATOR.pow(GF1M::SIZE - 1), GF1M::ONE);
+}
+
+#[test]
+fn test_linear_maps() {
+    for i in 0..=255 {
+        assert_eq!(GF256(i).sqrt().0, Table256(i).sqrt().0);
+        assert_eq!(GF256(i).trace(), Table256(i).trace());
+    }
+    for i in 0..1024 {
+        assert_eq!(GF1024(i).sqrt().0, Table1024(i).sqrt().0);
+        assert_eq!(GF1024(i).trace(), Table1024(i).trace());
+    }
+    let a = GF1M::from(123_456);
+    assert_eq!(a.sqrt().square(), a);
+    assert_eq!(GF2::ONE.trace(), g2p::GF2::ONE);
+}
+
+#[test]
+fn test_subtle_traits() {
+    let a = GF256::from(3);
+    let b = GF256::from(5);
+
+    assert!(bool::from(a.ct_eq(&a)));
+    assert!(!bool::from(a.ct_eq(&b)));
+    assert_eq!(GF256::conditional_select(&a, &b, Choice::from(0)), a);
+    assert_eq!(GF256::conditional_select(&a, &b, Choice::from(1)), b);
+}
+
+#[test]
+#[should_panic]
+fn test_div_panic() {
+    let _ = GF256::ONE / GF256::ZERO;
+}
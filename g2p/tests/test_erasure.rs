@@ -0,0 +1,168 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    erasure::{ErasureError, ReedSolomon},
+    g2p, GaloisField,
+};
+
+g2p!(GF16, 4);
+g2p!(GF256, 8);
+g2p!(GF65536, 16);
+
+fn make_shards<F: GaloisField>(
+    rs: &ReedSolomon<F>,
+    len: usize,
+    from: fn(usize) -> F,
+) -> Vec<Vec<F>> {
+    let mut shards: Vec<Vec<F>> = (0..rs.total_shards())
+        .map(|i| {
+            if i < rs.data_shards() {
+                (0..len).map(|j| from(i * 31 + j * 7 + 1)).collect()
+            } else {
+                vec![F::ZERO; len]
+            }
+        })
+        .collect();
+    rs.encode(&mut shards).unwrap();
+    shards
+}
+
+// Drop every combination of `m` shards from a small code and recover them
+fn check_all_erasures<F: GaloisField + std::fmt::Debug>(rs: ReedSolomon<F>, from: fn(usize) -> F) {
+    let shards = make_shards(&rs, 5, from);
+    assert!(rs.verify(&shards).unwrap());
+    let n = rs.total_shards();
+
+    for mask in 0_u32..(1 << n) {
+        if mask.count_ones() as usize > rs.parity_shards() {
+            continue;
+        }
+        let mut received: Vec<Option<Vec<F>>> = shards.iter().cloned().map(Some).collect();
+        for (i, r) in received.iter_mut().enumerate() {
+            if (mask >> i) & 1 == 1 {
+                *r = None;
+            }
+        }
+
+        let mut data_only = received.clone();
+        rs.reconstruct_data(&mut data_only).unwrap();
+        for (i, r) in data_only.iter().enumerate() {
+            if i < rs.data_shards() || (mask >> i) & 1 == 0 {
+                assert_eq!(r.as_ref(), Some(&shards[i]), "mask {:b}", mask);
+            } else {
+                assert_eq!(r, &None);
+            }
+        }
+
+        rs.reconstruct(&mut received).unwrap();
+        let received: Vec<Vec<F>> = received.into_iter().map(Option::unwrap).collect();
+        assert_eq!(received, shards, "mask {:b}", mask);
+    }
+}
+
+#[test]
+fn test_vandermonde_all_erasures() {
+    check_all_erasures(ReedSolomon::<GF256>::new(4, 3).unwrap(), |x| {
+        GF256::from(x as u8)
+    });
+    check_all_erasures(ReedSolomon::<GF16>::new(5, 4).unwrap(), |x| {
+        GF16::from((x % 16) as u8)
+    });
+}
+
+#[test]
+fn test_cauchy_all_erasures() {
+    check_all_erasures(ReedSolomon::<GF256>::cauchy(4, 3).unwrap(), |x| {
+        GF256::from(x as u8)
+    });
+    check_all_erasures(ReedSolomon::<GF16>::cauchy(5, 4).unwrap(), |x| {
+        GF16::from((x % 16) as u8)
+    });
+}
+
+#[test]
+fn test_vandermonde_is_systematic_rs() {
+    let rs = ReedSolomon::<GF256>::new(3, 2).unwrap();
+    // Encoding is the same as evaluating the interpolating polynomial at points k, k + 1, ...
+    let data = [GF256::from(9), GF256::from(200), GF256::from(17)];
+    let mut shards: Vec<Vec<GF256>> = data
+        .iter()
+        .map(|&d| vec![d])
+        .chain((0..2).map(|_| vec![GF256::ZERO]))
+        .collect();
+    rs.encode(&mut shards).unwrap();
+    let points: Vec<_> = data
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| (GF256::from(i as u8), d))
+        .collect();
+    let poly = g2p::poly::Poly::interpolate(&points).unwrap();
+    assert_eq!(shards[3][0], poly.eval(GF256::from(3)));
+    assert_eq!(shards[4][0], poly.eval(GF256::from(4)));
+}
+
+#[test]
+fn test_verify_detects_corruption() {
+    let rs = ReedSolomon::<GF256>::new(4, 2).unwrap();
+    let mut shards = make_shards(&rs, 16, |x| GF256::from(x as u8));
+    assert!(rs.verify(&shards).unwrap());
+    shards[1][3] += GF256::ONE;
+    assert!(!rs.verify(&shards).unwrap());
+}
+
+#[test]
+fn test_wide_gf65536() {
+    // More shards than GF(256) can handle
+    let rs = ReedSolomon::<GF65536>::cauchy(260, 40).unwrap();
+    let shards = make_shards(&rs, 4, |x| GF65536::from(x as u16));
+    assert!(rs.verify(&shards).unwrap());
+
+    let mut received: Vec<Option<Vec<GF65536>>> = shards.iter().cloned().map(Some).collect();
+    for i in (0..300).step_by(8).take(40) {
+        received[i] = None;
+    }
+    rs.reconstruct(&mut received).unwrap();
+    let received: Vec<Vec<GF65536>> = received.into_iter().map(Option::unwrap).collect();
+    assert_eq!(received, shards);
+
+    assert_eq!(
+        ReedSolomon::<GF256>::new(200, 57).unwrap_err(),
+        ErasureError::TooManyShards
+    );
+    assert!(ReedSolomon::<GF256>::new(200, 56).is_ok());
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        ReedSolomon::<GF256>::new(0, 2).unwrap_err(),
+        ErasureError::ZeroShards
+    );
+    assert_eq!(
+        ReedSolomon::<GF256>::cauchy(2, 0).unwrap_err(),
+        ErasureError::ZeroShards
+    );
+    assert_eq!(
+        ReedSolomon::<GF16>::cauchy(10, 7).unwrap_err(),
+        ErasureError::TooManyShards
+    );
+
+    let rs = ReedSolomon::<GF256>::new(2, 2).unwrap();
+    let mut shards = vec![vec![GF256::ZERO; 4]; 3];
+    assert_eq!(rs.encode(&mut shards), Err(ErasureError::WrongShardCount));
+    let mut shards = vec![vec![GF256::ZERO; 4]; 4];
+    shards[2].pop();
+    assert_eq!(rs.encode(&mut shards), Err(ErasureError::ShardSizeMismatch));
+    let mut shards = vec![Vec::<GF256>::new(); 4];
+    assert_eq!(rs.encode(&mut shards), Err(ErasureError::EmptyShard));
+
+    let mut received = vec![Some(vec![GF256::ONE; 4]), None, None, None];
+    assert_eq!(
+        rs.reconstruct(&mut received),
+        Err(ErasureError::TooFewShards)
+    );
+}
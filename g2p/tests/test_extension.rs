@@ -0,0 +1,174 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    extension::{Extension, ExtensionError, ExtensionModulus},
+    g2p, G2Poly, GaloisField,
+};
+
+g2p!(GF16, 4, modulus: 0b1_0011);
+g2p!(GF256, 8, modulus: 0b1_0001_1011);
+
+// y^2 + y + 8 over GF16
+struct Quadratic16;
+impl ExtensionModulus<GF16, 2> for Quadratic16 {
+    const COEFFS: [GF16; 2] = [GF16(8), GF16(1)];
+    const GENERATOR: [GF16; 2] = [GF16(2), GF16(1)];
+    const MODULUS: G2Poly = G2Poly(0b1_0110_1001);
+}
+type GF16x2 = Extension<GF16, 2, Quadratic16>;
+
+// y^3 + y + 1 over GF16
+struct Cubic16;
+impl ExtensionModulus<GF16, 3> for Cubic16 {
+    const COEFFS: [GF16; 3] = [GF16(1), GF16(1), GF16(0)];
+    const GENERATOR: [GF16; 3] = [GF16(2), GF16(1), GF16(0)];
+    const MODULUS: G2Poly = G2Poly(0b1_0011_0100_1101);
+}
+type GF16x3 = Extension<GF16, 3, Cubic16>;
+
+// y^2 + y + 0x20 over the AES field
+struct Quadratic256;
+impl ExtensionModulus<GF256, 2> for Quadratic256 {
+    const COEFFS: [GF256; 2] = [GF256(0x20), GF256(1)];
+    const GENERATOR: [GF256; 2] = [GF256(8), GF256(1)];
+    const MODULUS: G2Poly = G2Poly(0b1_0111_1110_1110_1101);
+}
+type GF256x2 = Extension<GF256, 2, Quadratic256>;
+
+// y^2 + 1 = (y + 1)^2 is reducible
+struct Reducible;
+impl ExtensionModulus<GF16, 2> for Reducible {
+    const COEFFS: [GF16; 2] = [GF16(1), GF16(0)];
+    const GENERATOR: [GF16; 2] = [GF16(2), GF16(1)];
+    const MODULUS: G2Poly = G2Poly(0b1_0110_1001);
+}
+
+struct WrongModulus;
+impl ExtensionModulus<GF16, 2> for WrongModulus {
+    const COEFFS: [GF16; 2] = [GF16(8), GF16(1)];
+    const GENERATOR: [GF16; 2] = [GF16(2), GF16(1)];
+    const MODULUS: G2Poly = G2Poly(0b1_0001_1011);
+}
+
+fn all_elements<E: GaloisField<Repr = u32>>() -> impl Iterator<Item = E> {
+    (0..E::SIZE as u32).map(|v| E::from_repr(v).unwrap())
+}
+
+fn check_field<E: GaloisField + std::fmt::Debug>(samples: Vec<E>) {
+    for &a in samples.iter() {
+        assert_eq!(a, a * E::ONE);
+        assert_eq!(E::ZERO, a * E::ZERO);
+        assert_eq!(E::ZERO, a + a);
+        if !a.is_zero() {
+            assert_eq!(E::ONE, a * a.inverse());
+            assert_eq!(a, E::ONE / (E::ONE / a));
+        }
+        assert_eq!(Some(a), E::from_repr(a.to_repr()));
+    }
+
+    for &a in samples.iter().step_by(samples.len() / 64) {
+        for &b in samples.iter().step_by(samples.len() / 32) {
+            assert_eq!(a * b, b * a);
+            for &c in samples.iter().step_by(samples.len() / 8) {
+                assert_eq!((a * b) * c, a * (b * c));
+                assert_eq!(a * (b + c), a * b + a * c);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parameters() {
+    assert_eq!(Ok(()), GF16x2::check());
+    assert_eq!(Ok(()), GF16x3::check());
+    assert_eq!(Ok(()), GF256x2::check());
+    assert_eq!(
+        Err(ExtensionError::NotPrimitive),
+        Extension::<GF16, 2, Reducible>::check()
+    );
+    assert_eq!(None, Extension::<GF16, 2, Reducible>::find_parameters());
+    assert_eq!(
+        Err(ExtensionError::WrongMinimalPolynomial),
+        Extension::<GF16, 2, WrongModulus>::check()
+    );
+
+    assert_eq!(
+        Some((Quadratic16::GENERATOR, Quadratic16::MODULUS)),
+        GF16x2::find_parameters()
+    );
+    assert_eq!(
+        Some((Cubic16::GENERATOR, Cubic16::MODULUS)),
+        GF16x3::find_parameters()
+    );
+}
+
+#[test]
+fn test_constants() {
+    assert_eq!((256, 8), (GF16x2::SIZE, GF16x2::DEGREE));
+    assert_eq!((4096, 12), (GF16x3::SIZE, GF16x3::DEGREE));
+    assert_eq!(
+        (65536, 16, 2),
+        (GF256x2::SIZE, GF256x2::DEGREE, GF256x2::BYTES)
+    );
+    assert_eq!([GF16::ONE, GF16::ZERO], GF16x2::ONE.coeffs());
+    assert_eq!(GF16x2::from_base(GF16::ONE), GF16x2::ONE);
+}
+
+#[test]
+fn test_arithmetic() {
+    check_field(all_elements::<GF16x2>().collect());
+    check_field(all_elements::<GF16x3>().collect());
+    check_field(all_elements::<GF256x2>().step_by(101).collect());
+}
+
+#[test]
+fn test_generator() {
+    // The powers of the generator reach every non-zero element exactly once
+    let mut seen = vec![false; GF16x2::SIZE];
+    let mut x = GF16x2::ONE;
+    for _ in 1..GF16x2::SIZE {
+        let idx = x.to_repr() as usize;
+        assert!(!seen[idx]);
+        seen[idx] = true;
+        x *= GF16x2::GENERATOR;
+    }
+    assert_eq!(GF16x2::ONE, x);
+}
+
+#[test]
+fn test_base_field() {
+    let y = GF16x2::new([GF16::ZERO, GF16::ONE]);
+    // y is a root of the modulus
+    assert_eq!(GF16x2::from_base(GF16(8)), y * y + y);
+
+    for a in (0..16).map(GF16) {
+        let ext = GF16x2::from_base(a);
+        for b in (0..16).map(GF16) {
+            assert_eq!(GF16x2::from_base(a * b), ext * GF16x2::from_base(b));
+            assert_eq!(ext.scale(b), ext * GF16x2::from_base(b));
+        }
+    }
+
+    for x in all_elements::<GF16x2>() {
+        let norm = x.relative_norm();
+        assert_eq!(GF16x2::from_base(norm), x * x.pow(16));
+        assert_eq!(norm.is_zero(), x.is_zero());
+    }
+}
+
+#[test]
+fn test_generic_operations() {
+    // The provided trait methods work for extensions as well
+    for x in all_elements::<GF16x2>() {
+        assert_eq!(x, x.sqrt().square());
+        assert_eq!(x.pow(4), x.frobenius(2));
+    }
+    let ones = all_elements::<GF16x3>()
+        .filter(|x| x.trace() == g2p::GF2::ONE)
+        .count();
+    assert_eq!(GF16x3::SIZE / 2, ones);
+}
@@ -0,0 +1,215 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    erasure::ErasureError,
+    fft::{AdditiveFft, FftReedSolomon},
+    g2p,
+    poly::Poly,
+    GaloisField,
+};
+
+g2p!(GF16, 4);
+g2p!(GF256, 8);
+g2p!(GF65536, 16);
+
+// The novel basis X_0 .. X_(2^r) as ordinary polynomials
+fn novel_basis(r: u32) -> Vec<Poly<GF256>> {
+    let w_hat: Vec<Poly<GF256>> = (0..r)
+        .map(|j| {
+            let roots: Vec<GF256> = (0..1_u32 << j).map(|a| GF256::from(a as u8)).collect();
+            let w = Poly::from_roots(&roots);
+            let norm = w.eval(GF256::from((1_u32 << j) as u8));
+            w.scale(norm.inverse())
+        })
+        .collect();
+    (0..1_usize << r)
+        .map(|i| {
+            (0..r as usize)
+                .filter(|&j| (i >> j) & 1 == 1)
+                .fold(Poly::one(), |acc, j| &acc * &w_hat[j])
+        })
+        .collect()
+}
+
+fn to_poly(basis: &[Poly<GF256>], coeffs: &[GF256]) -> Poly<GF256> {
+    basis
+        .iter()
+        .zip(coeffs.iter())
+        .fold(Poly::zero(), |acc, (x, &c)| acc + x.scale(c))
+}
+
+fn sample(len: usize, seed: usize) -> Vec<GF256> {
+    (0..len)
+        .map(|i| GF256::from(((i * 37 + seed * 11 + 5) % 256) as u8))
+        .collect()
+}
+
+#[test]
+fn test_fft_matches_naive() {
+    let fft = AdditiveFft::<GF256>::new(4).unwrap();
+    let basis = novel_basis(4);
+    for &len in [1, 2, 4, 8, 16].iter() {
+        let coeffs = sample(len, len);
+        let p = to_poly(&basis, &coeffs);
+        for shift in (0..256).step_by(len.max(16)) {
+            let mut values = coeffs.clone();
+            fft.fft(&mut values, shift);
+            for (u, &y) in values.iter().enumerate() {
+                assert_eq!(y, p.eval(GF256::from((shift + u) as u8)));
+            }
+            fft.ifft(&mut values, shift);
+            assert_eq!(values, coeffs);
+        }
+    }
+}
+
+#[test]
+fn test_formal_derivative() {
+    let fft = AdditiveFft::<GF256>::new(4).unwrap();
+    let basis = novel_basis(4);
+    let coeffs = sample(16, 3);
+    let mut derivative = coeffs.clone();
+    fft.formal_derivative(&mut derivative);
+    assert_eq!(
+        to_poly(&basis, &derivative),
+        to_poly(&basis, &coeffs).derivative()
+    );
+}
+
+#[test]
+fn test_shards_match_elements() {
+    let fft = AdditiveFft::<GF256>::new(3).unwrap();
+    let shards: Vec<Vec<GF256>> = (0..8).map(|i| sample(5, i)).collect();
+    let mut transformed = shards.clone();
+    fft.fft_shards(&mut transformed, 8);
+    for col in 0..5 {
+        let mut column: Vec<GF256> = shards.iter().map(|s| s[col]).collect();
+        fft.fft(&mut column, 8);
+        assert!(column
+            .iter()
+            .zip(transformed.iter())
+            .all(|(&a, s)| a == s[col]));
+    }
+    fft.ifft_shards(&mut transformed, 8);
+    assert_eq!(transformed, shards);
+    assert!(AdditiveFft::<GF16>::new(5).is_none());
+}
+
+#[test]
+fn test_all_erasures_small() {
+    for &(k, m) in &[(3, 3), (4, 4), (5, 2), (1, 5), (6, 7)] {
+        let rs = FftReedSolomon::<GF256>::new(k, m).unwrap();
+        let mut shards: Vec<Vec<GF256>> = (0..k + m)
+            .map(|i| {
+                (0..3)
+                    .map(|j| GF256::from((i * 31 + j * 7 + 1) as u8))
+                    .collect()
+            })
+            .collect();
+        rs.encode(&mut shards).unwrap();
+        assert!(rs.verify(&shards).unwrap());
+
+        // Drop every combination of at most m shards and recover them
+        for mask in 0_u32..(1 << (k + m)) {
+            if mask.count_ones() as usize > m {
+                continue;
+            }
+            let mut damaged: Vec<Option<Vec<GF256>>> = shards
+                .iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    if mask >> i & 1 == 1 {
+                        None
+                    } else {
+                        Some(s.clone())
+                    }
+                })
+                .collect();
+            rs.reconstruct(&mut damaged).unwrap();
+            let recovered: Vec<Vec<GF256>> = damaged.into_iter().map(Option::unwrap).collect();
+            assert_eq!(recovered, shards, "k = {}, m = {}, mask {:b}", k, m, mask);
+        }
+    }
+}
+
+#[test]
+fn test_parity_is_evaluation() {
+    // With one data shard, the polynomial is constant
+    let rs = FftReedSolomon::<GF256>::new(1, 3).unwrap();
+    let mut shards = vec![vec![GF256::ZERO]; 4];
+    shards[0] = vec![GF256::from(42)];
+    rs.encode(&mut shards).unwrap();
+    assert!(shards.iter().all(|s| s == &[GF256::from(42)]));
+}
+
+#[test]
+fn test_wide_stripe() {
+    let rs = FftReedSolomon::<GF65536>::new(600, 400).unwrap();
+    let mut shards: Vec<Vec<GF65536>> = (0..1000_u64)
+        .map(|i| {
+            (0..4)
+                .map(|j| GF65536::from(((i * 4 + j) * 2654435761 % 65536) as u16))
+                .collect()
+        })
+        .collect();
+    rs.encode(&mut shards).unwrap();
+    assert!(rs.verify(&shards).unwrap());
+
+    // Lose 400 shards spread over data and parity
+    let mut damaged: Vec<Option<Vec<GF65536>>> = shards.iter().cloned().map(Some).collect();
+    for i in (0..1000).filter(|i| i % 5 < 2) {
+        damaged[i] = None;
+    }
+    let mut data_only = damaged.clone();
+    rs.reconstruct(&mut damaged).unwrap();
+    assert_eq!(
+        damaged.into_iter().map(Option::unwrap).collect::<Vec<_>>(),
+        shards
+    );
+
+    rs.reconstruct_data(&mut data_only).unwrap();
+    assert!(data_only[..600]
+        .iter()
+        .zip(shards.iter())
+        .all(|(a, b)| a.as_ref() == Some(b)));
+    assert!(data_only[600..].iter().any(Option::is_none));
+
+    data_only[0] = None;
+    data_only[1] = None;
+    for s in data_only[600..].iter_mut() {
+        *s = None;
+    }
+    assert_eq!(
+        rs.reconstruct(&mut data_only[..]).unwrap_err(),
+        ErasureError::TooFewShards
+    );
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        FftReedSolomon::<GF16>::new(0, 2).unwrap_err(),
+        ErasureError::ZeroShards
+    );
+    assert_eq!(
+        FftReedSolomon::<GF16>::new(9, 2).unwrap_err(),
+        ErasureError::TooManyShards
+    );
+    assert!(FftReedSolomon::<GF16>::new(8, 8).is_ok());
+
+    let rs = FftReedSolomon::<GF16>::new(2, 2).unwrap();
+    let mut shards = vec![vec![GF16::ONE; 2]; 3];
+    assert_eq!(
+        rs.encode(&mut shards).unwrap_err(),
+        ErasureError::WrongShardCount
+    );
+    let mut shards = vec![vec![GF16::ONE; 2], vec![GF16::ONE; 3], vec![], vec![]];
+    assert_eq!(
+        rs.encode(&mut shards).unwrap_err(),
+        ErasureError::ShardSizeMismatch
+    );
+}
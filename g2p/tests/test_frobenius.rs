@@ -0,0 +1,69 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{g2p, GaloisField, GF2};
+
+g2p!(GF4, 2);
+g2p!(GF8, 3);
+g2p!(GF256, 8, modulus: 0b1_0001_1011);
+g2p!(CtGF256, 8, modulus: 0b1_0001_1011, constant_time: true);
+g2p!(GF1024, 10);
+g2p!(GF1M, 20, tables: lazy);
+
+fn check_frobenius<F: GaloisField + std::fmt::Debug>(elements: Vec<F>) {
+    let p = F::DEGREE;
+    let mut ones = 0;
+    for &x in elements.iter() {
+        assert_eq!(x * x, x.square());
+        assert_eq!(x.pow(1 << (p - 1)), x.sqrt());
+        assert_eq!(x, x.sqrt().square());
+
+        assert_eq!(x, x.frobenius(0));
+        assert_eq!(x, x.frobenius(p));
+        for k in 1..p {
+            assert_eq!(x.pow(1 << k), x.frobenius(k));
+        }
+        assert_eq!(x.frobenius(3), x.frobenius(p + 3));
+
+        let trace: F = (0..p).map(|k| x.frobenius(k)).sum();
+        let expected = if trace == F::ZERO {
+            GF2::ZERO
+        } else {
+            assert_eq!(F::ONE, trace);
+            ones += 1;
+            GF2::ONE
+        };
+        assert_eq!(expected, x.trace());
+
+        let norm = if x.is_zero() { GF2::ZERO } else { GF2::ONE };
+        assert_eq!(norm, x.norm());
+    }
+
+    // The trace is linear and onto GF(2), so half of all elements have trace 1
+    if elements.len() == F::SIZE {
+        assert_eq!(F::SIZE / 2, ones);
+    }
+}
+
+#[test]
+fn test_frobenius() {
+    check_frobenius((0..2).map(GF2::from).collect());
+    check_frobenius((0..4).map(GF4::from).collect());
+    check_frobenius((0..8).map(GF8::from).collect());
+    check_frobenius((0..=255).map(GF256::from).collect());
+    check_frobenius((0..=255).map(CtGF256::from).collect());
+    check_frobenius((0..1024).map(GF1024::from).collect());
+    check_frobenius((0..1 << 20).step_by(9973).map(GF1M::from).collect());
+}
+
+#[test]
+fn test_rijndael_trace() {
+    // In the AES field, the trace is the sum of bits 5 and 7
+    for x in 0..=255_u8 {
+        let expected = GF2::from(((x >> 5) ^ (x >> 7)) & 1);
+        assert_eq!(expected, GF256::from(x).trace());
+    }
+}
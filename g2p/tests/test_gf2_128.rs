@@ -0,0 +1,150 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    gf2_128::{Ghash, Polyval, GF2_128},
+    GaloisField, GF2,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn hex(s: &str) -> [u8; 16] {
+    let mut out = [0; 16];
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+    }
+    out
+}
+
+// Shift and add multiplication
+fn mul_reference(a: GF2_128, b: GF2_128) -> GF2_128 {
+    let mut res = GF2_128::ZERO;
+    let mut a = a;
+    for i in 0..128 {
+        if (b.0 >> i) & 1 == 1 {
+            res += a;
+        }
+        a = a.mul_x();
+    }
+    res
+}
+
+#[test]
+fn test_mul_matches_reference() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut values = vec![
+        GF2_128::ZERO,
+        GF2_128::ONE,
+        GF2_128(u128::MAX),
+        GF2_128(1 << 127),
+    ];
+    values.extend((0..20).map(|_| rng.gen::<GF2_128>()));
+    for &a in values.iter() {
+        for &b in values.iter() {
+            assert_eq!(a * b, mul_reference(a, b));
+        }
+    }
+    // x^128 = x^7 + x^2 + x + 1
+    assert_eq!(GF2_128(1 << 127).mul_x(), GF2_128(0x87));
+}
+
+#[test]
+fn test_field_operations() {
+    let mut rng = StdRng::seed_from_u64(2);
+    for _ in 0..10 {
+        let a: GF2_128 = rng.gen();
+        let b: GF2_128 = rng.gen();
+        assert_eq!(a * a.inverse(), GF2_128::ONE);
+        assert_eq!(a / b * b, a);
+        assert_eq!(a.sqrt().square(), a);
+        assert_eq!(a.pow(3), a * a * a);
+        assert_eq!(a.frobenius(128), a);
+        assert_eq!((a + b).trace(), a.trace() + b.trace());
+        assert_eq!(a.checked_div(GF2_128::ZERO), None);
+    }
+    assert_eq!(GF2_128::ZERO.inverse(), GF2_128::ZERO);
+    assert_eq!(GF2_128::ONE.trace(), GF2::ZERO);
+    assert_eq!(GF2_128(1 << 127).trace(), GF2::ONE);
+    assert_eq!(
+        [GF2_128(3), GF2_128(5)].iter().copied().sum::<GF2_128>(),
+        GF2_128(6)
+    );
+}
+
+#[test]
+fn test_generator() {
+    // 2^128 - 1 = 3 · 5 · 17 · 257 · 641 · 65537 · 274177 · 6700417 · 67280421310721
+    let factors: [u128; 9] = [3, 5, 17, 257, 641, 65537, 274177, 6700417, 67280421310721];
+    assert_eq!(factors.iter().product::<u128>(), u128::MAX);
+    let g = GF2_128::GENERATOR;
+    assert_eq!(g.pow(u128::MAX), GF2_128::ONE);
+    for &q in factors.iter() {
+        assert_ne!(g.pow(u128::MAX / q), GF2_128::ONE);
+    }
+}
+
+#[test]
+fn test_conversions() {
+    let block = hex("0388dace60b6a392f328c2b971b2fe78");
+    let a = GF2_128::from_ghash_bytes(&block);
+    assert_eq!(a.to_ghash_bytes(), block);
+    assert_eq!(
+        GF2_128::from_polyval_bytes(&block).to_polyval_bytes(),
+        block
+    );
+
+    // The first bit of a GHASH block is the constant coefficient
+    let mut one = [0; 16];
+    one[0] = 0x80;
+    assert_eq!(GF2_128::from_ghash_bytes(&one), GF2_128::ONE);
+    // POLYVAL blocks are the byte reversed GHASH blocks
+    let mut reversed = block;
+    reversed.reverse();
+    assert_eq!(GF2_128::from_polyval_bytes(&reversed), a);
+}
+
+#[test]
+fn test_ghash_gcm_vector() {
+    // Test case 2 of the GCM specification: zero key, IV and plaintext
+    let h = hex("66e94bd4ef8a2c3b884cfa59ca342b2e");
+    let c = hex("0388dace60b6a392f328c2b971b2fe78");
+    let mut ghash = Ghash::new(&h);
+    ghash.update_padded(&c);
+    // No additional data, 128 bits of ciphertext
+    let mut lengths = [0; 16];
+    lengths[15] = 0x80;
+    ghash.update_block(&lengths);
+    assert_eq!(ghash.finalize(), hex("f38cbb1ad69223dcc3457ae5b6b0f885"));
+}
+
+#[test]
+fn test_rfc8452_vectors() {
+    // RFC 8452, Appendix A
+    let h = hex("25629347589242761d31f826ba4b757b");
+    let x1 = hex("4f4f95668c83dfb6401762bb2d01a262");
+    let x2 = hex("d1a24ddd2721d006bbe45f20d3c9f362");
+
+    let mut polyval = Polyval::new(&h);
+    polyval.update_block(&x1);
+    polyval.update_block(&x2);
+    assert_eq!(polyval.finalize(), hex("f7a3b47b846119fae5b7866cf5e5b77e"));
+
+    let mut ghash = Ghash::new(&h);
+    ghash.update_block(&x1);
+    ghash.update_block(&x2);
+    assert_eq!(ghash.finalize(), hex("bd9b3997046731fb96251b91f9c99d7a"));
+}
+
+#[test]
+fn test_constant_time_traits() {
+    use g2p::subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+    let a = GF2_128(0x1234 << 64 | 5);
+    let b = GF2_128(5);
+    assert!(bool::from(a.ct_eq(&a)));
+    assert!(!bool::from(a.ct_eq(&b)));
+    assert_eq!(GF2_128::conditional_select(&a, &b, Choice::from(0)), a);
+    assert_eq!(GF2_128::conditional_select(&a, &b, Choice::from(1)), b);
+}
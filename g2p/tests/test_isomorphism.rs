@@ -0,0 +1,110 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    extension::{Extension, ExtensionModulus},
+    g2p,
+    isomorphism::{convert, Isomorphism},
+    G2Poly, GaloisField,
+};
+
+g2p!(Rijndael, 8, modulus: 0x11b);
+g2p!(ReedSolomon, 8, modulus: 0x11d);
+g2p!(GF16, 4, modulus: 0b1_0011);
+g2p!(GF1024A, 10, modulus: 0b100_0000_1001);
+g2p!(GF1024B, 10, modulus: 0b100_0110_1111);
+g2p!(GF1M, 20, tables: lazy);
+g2p!(GF1MB, 20, modulus: 0b1_0000_0000_0000_0110_0101, tables: lazy);
+
+struct Quadratic16;
+impl ExtensionModulus<GF16, 2> for Quadratic16 {
+    const COEFFS: [GF16; 2] = [GF16(8), GF16(1)];
+    const GENERATOR: [GF16; 2] = [GF16(2), GF16(1)];
+    const MODULUS: G2Poly = G2Poly(0b1_0110_1001);
+}
+type GF16x2 = Extension<GF16, 2, Quadratic16>;
+
+fn element<F: GaloisField>(v: u32) -> F {
+    F::from_repr(std::convert::TryFrom::try_from(v).ok().unwrap()).unwrap()
+}
+
+fn check_isomorphism<A, B>(samples: impl Iterator<Item = u32> + Clone)
+where
+    A: GaloisField + std::fmt::Debug,
+    B: GaloisField + std::fmt::Debug,
+{
+    let iso = Isomorphism::<A, B>::new().unwrap();
+    let inv = iso.inverse();
+    let mut seen = std::collections::HashSet::new();
+    for v in samples.clone() {
+        let a: A = element(v);
+        let b = iso.map(a);
+        assert_eq!(a, iso.map_back(b));
+        assert_eq!(b, inv.map_back(a));
+        assert_eq!(a, inv.map(b));
+        assert!(seen.insert(b.to_repr()));
+
+        for w in samples.clone().step_by(17) {
+            let c: A = element(w);
+            assert_eq!(iso.map(a * c), b * iso.map(c));
+            assert_eq!(iso.map(a + c), b + iso.map(c));
+        }
+    }
+    assert_eq!(B::ONE, iso.map(A::ONE));
+    assert_eq!(B::ZERO, iso.map(A::ZERO));
+}
+
+#[test]
+fn test_gf256() {
+    check_isomorphism::<Rijndael, ReedSolomon>(0..256);
+    check_isomorphism::<ReedSolomon, Rijndael>(0..256);
+    check_isomorphism::<Rijndael, Rijndael>(0..256);
+}
+
+#[test]
+fn test_extension() {
+    check_isomorphism::<GF16x2, Rijndael>(0..256);
+    check_isomorphism::<Rijndael, GF16x2>(0..256);
+}
+
+#[test]
+fn test_larger_fields() {
+    check_isomorphism::<GF1024A, GF1024B>(0..1024);
+    check_isomorphism::<GF1M, GF1MB>((0..1 << 20).step_by(4099));
+}
+
+#[test]
+fn test_identity_for_same_modulus() {
+    // The generator is mapped to a root of its own minimal polynomial, which is a conjugate
+    let iso = Isomorphism::<Rijndael, Rijndael>::new().unwrap();
+    let g = iso.map(Rijndael::GENERATOR);
+    assert!((0..8).any(|k| Rijndael::GENERATOR.frobenius(k) == g));
+}
+
+#[test]
+fn test_different_sizes() {
+    assert!(Isomorphism::<Rijndael, GF1024A>::new().is_none());
+}
+
+#[test]
+fn test_convert() {
+    let a = Rijndael::from(0x53);
+    let b: ReedSolomon = convert(a);
+    assert_eq!(a, convert(b));
+    assert_eq!(
+        Isomorphism::<Rijndael, ReedSolomon>::new().unwrap().map(a),
+        b
+    );
+}
+
+#[test]
+fn test_matrices() {
+    let iso = Isomorphism::<Rijndael, ReedSolomon>::new().unwrap();
+    assert_eq!(8, iso.forward_matrix().len());
+    // The first column is the image of 1
+    assert_eq!(1, iso.forward_matrix()[0]);
+    assert_eq!(1, iso.backward_matrix()[0]);
+}
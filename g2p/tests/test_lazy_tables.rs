@@ -0,0 +1,91 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{g2p, lazy::InitError, G2Poly, GaloisField};
+
+g2p!(GF256, 8, modulus: 0b1_0001_1011, tables: lazy);
+g2p!(GF1024, 10, tables: lazy);
+g2p!(GF1M, 20, tables: lazy);
+g2p!(GF4096, 12, tables: lazy);
+g2p!(GF8192, 13, tables: lazy);
+
+#[test]
+fn test_rijndael() {
+    let b = GF256::from(1);
+    let c = GF256::from(0x53);
+    let d = GF256::from(0xca);
+
+    assert_eq!(c * d, b);
+    assert_eq!(b / c, d);
+    assert_eq!(b / d, c);
+}
+
+#[test]
+fn test_gf1024() {
+    let a: GF1024 = 555.into();
+    let b: GF1024 = 444.into();
+    let d = a * b;
+    assert_eq!(765, u16::from(d));
+    assert_eq!(d / b, a);
+    assert_eq!(d / a, b);
+}
+
+#[test]
+fn test_gf1m() {
+    let m = GF1M::MODULUS;
+    for &(a, b) in &[
+        (1, 1),
+        (2, 3),
+        (0xfffff, 0xfffff),
+        (123_456, 654_321),
+        (0x80000, 7),
+    ] {
+        let x = GF1M::from(a);
+        let y = GF1M::from(b);
+        let expected = G2Poly(a as u64) * G2Poly(b as u64) % m;
+
+        assert_eq!(u32::from(x * y), expected.0 as u32);
+        assert_eq!(x * y / y, x);
+        assert_eq!(x / x, GF1M::ONE);
+    }
+    assert_eq!(GF1M::GENERATOR.pow(GF1M::SIZE - 1), GF1M::ONE);
+}
+
+#[test]
+fn test_user_buffer() {
+    let buffer = vec![0; GF4096::TABLE_LEN - 1].leak();
+    assert_eq!(
+        GF4096::init_tables(buffer),
+        Err(InitError::BufferSize {
+            expected: GF4096::TABLE_LEN,
+            actual: GF4096::TABLE_LEN - 1,
+        })
+    );
+
+    let buffer = vec![0; GF4096::TABLE_LEN].leak();
+    assert_eq!(GF4096::init_tables(buffer), Ok(()));
+
+    let a = GF4096::from(1234);
+    assert_eq!(a / a, GF4096::ONE);
+    assert_eq!(a * GF4096::ONE, a);
+
+    let buffer = vec![0; GF4096::TABLE_LEN].leak();
+    assert_eq!(
+        GF4096::init_tables(buffer),
+        Err(InitError::AlreadyInitialized)
+    );
+}
+
+#[test]
+fn test_dirty_user_buffer() {
+    let buffer = vec![0x1234; GF8192::TABLE_LEN].leak();
+    assert_eq!(GF8192::init_tables(buffer), Ok(()));
+
+    assert_eq!(GF8192::ZERO.inverse(), GF8192::ZERO);
+    assert_eq!(GF8192::ZERO.checked_inv(), None);
+    let a = GF8192::from(4321);
+    assert_eq!(a * a.inverse(), GF8192::ONE);
+}
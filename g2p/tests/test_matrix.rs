@@ -0,0 +1,148 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{g2p, matrix::Matrix, GaloisField};
+
+g2p!(GF16, 4);
+g2p!(GF256, 8);
+
+fn m16(rows: &[&[u8]]) -> Matrix<GF16> {
+    let rows: Vec<Vec<GF16>> = rows
+        .iter()
+        .map(|r| r.iter().map(|&v| GF16::from(v)).collect())
+        .collect();
+    Matrix::from_rows(&rows)
+}
+
+fn elements(range: core::ops::Range<u8>) -> Vec<GF256> {
+    range.map(GF256::from).collect()
+}
+
+#[test]
+fn test_identity_and_mul() {
+    let a = m16(&[&[1, 2, 3], &[4, 5, 6]]);
+    let b = m16(&[&[7, 8], &[9, 10], &[11, 12]]);
+    assert_eq!(&Matrix::identity(2) * &a, a);
+    assert_eq!(&a * &Matrix::identity(3), a);
+
+    let c = &a * &b;
+    assert_eq!((c.rows(), c.cols()), (2, 2));
+    for r in 0..2 {
+        for col in 0..2 {
+            let expected: GF16 = (0..3).map(|k| a[(r, k)] * b[(k, col)]).sum();
+            assert_eq!(c[(r, col)], expected);
+        }
+    }
+    assert_eq!((&a * &b).transpose(), &b.transpose() * &a.transpose());
+    assert_eq!(&a + &a, Matrix::zero(2, 3));
+}
+
+#[test]
+fn test_mul_vec_blocked() {
+    // Wider than one block
+    let cols = 600;
+    let data: Vec<GF256> = (0..3 * cols)
+        .map(|i| GF256::from((i * 7 % 256) as u8))
+        .collect();
+    let m = Matrix::from_vec(3, cols, data);
+    let x: Vec<GF256> = (0..cols).map(|i| GF256::from((i % 251) as u8)).collect();
+    let y = m.mul_vec(&x);
+    let as_column = Matrix::from_vec(cols, 1, x);
+    assert_eq!(Matrix::from_vec(3, 1, y), &m * &as_column);
+}
+
+#[test]
+fn test_rank_and_null_space() {
+    // Third row is the sum of the first two
+    let m = m16(&[&[1, 2, 3, 4], &[5, 6, 7, 8], &[4, 4, 4, 12]]);
+    assert_eq!(m.rank(), 2);
+    let null = m.null_space();
+    assert_eq!(null.len(), 2);
+    for v in null.iter() {
+        assert!(m.mul_vec(v).iter().all(|x| x.is_zero()));
+    }
+    assert_eq!(Matrix::from_rows(&null).rank(), 2);
+
+    assert_eq!(Matrix::<GF16>::identity(5).null_space().len(), 0);
+    assert_eq!(Matrix::<GF16>::zero(2, 3).rank(), 0);
+    assert_eq!(Matrix::<GF16>::zero(2, 3).null_space().len(), 3);
+}
+
+#[test]
+fn test_row_reduce() {
+    let mut m = m16(&[&[0, 2, 4], &[3, 0, 1], &[3, 2, 5]]);
+    let pivots = m.row_reduce();
+    assert_eq!(pivots, vec![0, 1]);
+    assert_eq!(m.row(0)[0], GF16::ONE);
+    assert_eq!(m.row(1)[1], GF16::ONE);
+    assert_eq!(m.row(0)[1], GF16::ZERO);
+    assert!(m.row(2).iter().all(|x| x.is_zero()));
+}
+
+#[test]
+fn test_determinant() {
+    let m = m16(&[&[1, 2], &[3, 4]]);
+    let expected = GF16::from(1) * GF16::from(4) - GF16::from(2) * GF16::from(3);
+    assert_eq!(m.determinant(), Some(expected));
+    assert_eq!(m16(&[&[1, 2], &[2, 4]]).determinant(), Some(GF16::ZERO));
+    assert_eq!(m16(&[&[1, 2, 3]]).determinant(), None);
+
+    // Vandermonde determinant is the product of all differences
+    let points = elements(3..8);
+    let v = Matrix::vandermonde(&points, 5);
+    let mut expected = GF256::ONE;
+    for i in 0..5 {
+        for j in i + 1..5 {
+            expected *= points[j] - points[i];
+        }
+    }
+    assert_eq!(v.determinant(), Some(expected));
+
+    // Multiplicative
+    let a = Matrix::cauchy(&elements(0..4), &elements(10..14)).unwrap();
+    let w = Matrix::vandermonde(&elements(3..7), 4);
+    assert_eq!(
+        (&a * &w).determinant(),
+        Some(a.determinant().unwrap() * w.determinant().unwrap())
+    );
+}
+
+#[test]
+fn test_inverse() {
+    let c = Matrix::cauchy(&elements(0..6), &elements(6..12)).unwrap();
+    let inv = c.inverse().unwrap();
+    assert_eq!(&c * &inv, Matrix::identity(6));
+    assert_eq!(&inv * &c, Matrix::identity(6));
+
+    assert_eq!(m16(&[&[1, 2], &[2, 4]]).inverse(), None);
+    assert_eq!(m16(&[&[1, 2, 3]]).inverse(), None);
+    assert!(Matrix::cauchy(&elements(0..3), &elements(2..4)).is_none());
+
+    // Every square submatrix of a Cauchy matrix is invertible
+    let wide = Matrix::cauchy(&elements(0..4), &elements(4..12)).unwrap();
+    let t = wide.transpose();
+    for rows in [[0, 1, 2, 3], [4, 5, 6, 7], [0, 2, 5, 7]].iter() {
+        assert!(t.select_rows(rows).inverse().is_some());
+    }
+}
+
+#[test]
+fn test_solve() {
+    let v = Matrix::vandermonde(&elements(1..6), 5);
+    let b = elements(20..25);
+    let x = v.solve(&b).unwrap();
+    assert_eq!(v.mul_vec(&x), b);
+
+    // Underdetermined, but consistent
+    let m = m16(&[&[1, 1, 0], &[0, 1, 1]]);
+    let b = [GF16::from(3), GF16::from(5)];
+    let x = m.solve(&b).unwrap();
+    assert_eq!(m.mul_vec(&x), b);
+
+    // Inconsistent
+    let m = m16(&[&[1, 2], &[2, 4]]);
+    assert_eq!(m.solve(&[GF16::from(1), GF16::from(1)]), None);
+}
@@ -0,0 +1,74 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    g2p,
+    multiplier::{Multiplier, MultiplierTables},
+};
+
+g2p!(GF16, 4);
+g2p!(GF256, 8);
+g2p!(CtGF256, 8, constant_time: true);
+g2p!(GF1024, 10);
+g2p!(GF65536, 16);
+g2p!(GF1M, 20, tables: lazy);
+
+fn check_multiplier<F: MultiplierTables + std::fmt::Debug>(from: fn(u32) -> F) {
+    let elements: Vec<F> = (0..100_u32)
+        .map(|i| from(i.wrapping_mul(0x9e37_79b9) >> 7))
+        .collect();
+    for &c in elements.iter().take(20) {
+        let m = Multiplier::new(c);
+        assert_eq!(c, m.constant());
+        for &x in elements.iter() {
+            assert_eq!(c * x, m.apply(x));
+        }
+
+        let mut dst = elements.clone();
+        m.mul_slice(&mut dst, &elements);
+        let expected: Vec<F> = elements.iter().map(|&x| c * x).collect();
+        assert_eq!(expected, dst);
+
+        let mut dst = elements.clone();
+        m.mul_add_slice(&mut dst, &elements);
+        let expected: Vec<F> = elements.iter().map(|&x| x + c * x).collect();
+        assert_eq!(expected, dst);
+    }
+}
+
+#[test]
+fn test_multiplier() {
+    check_multiplier(|v| GF16::from(v as u8));
+    check_multiplier(|v| GF256::from(v as u8));
+    check_multiplier(|v| CtGF256::from(v as u8));
+    check_multiplier(|v| GF1024::from(v as u16));
+    check_multiplier(|v| GF65536::from(v as u16));
+    check_multiplier(GF1M::from);
+}
+
+#[test]
+fn test_exhaustive_gf256() {
+    for c in 0..=255 {
+        let m = GF256::multiplier(c.into());
+        for x in 0..=255 {
+            assert_eq!(GF256::from(c) * GF256::from(x), m.apply(x.into()));
+        }
+    }
+}
+
+#[test]
+fn test_unreduced_elements() {
+    let m = GF1024::multiplier(GF1024(0x3a5));
+    for x in [0xffff, 0x8000, 0x0400, 0x07ff] {
+        assert_eq!(GF1024(0x3a5) * GF1024(x), m.apply(GF1024(x)));
+    }
+}
+
+#[test]
+fn test_debug() {
+    let m = GF256::multiplier(3.into());
+    assert_eq!("Multiplier { constant: 3_GF256 }", format!("{:?}", m));
+}
@@ -0,0 +1,191 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    g2p,
+    poly::{extended_gcd, gcd, Poly},
+    GaloisField,
+};
+
+g2p!(GF16, 4);
+g2p!(GF256, 8);
+g2p!(GF65536, 16);
+
+fn p16(coeffs: &[u8]) -> Poly<GF16> {
+    Poly::new(coeffs.iter().map(|&c| GF16::from(c)).collect())
+}
+
+fn p256(coeffs: &[u8]) -> Poly<GF256> {
+    Poly::new(coeffs.iter().map(|&c| GF256::from(c)).collect())
+}
+
+#[test]
+fn test_normalized() {
+    let p = p16(&[1, 2, 0, 0]);
+    assert_eq!(p.coeffs(), &[GF16::from(1), GF16::from(2)]);
+    assert_eq!(p.degree(), Some(1));
+    assert_eq!(p16(&[0, 0]), Poly::zero());
+    assert_eq!(Poly::<GF16>::zero().degree(), None);
+    assert_eq!(Poly::<GF16>::zero().leading_coeff(), None);
+    assert_eq!(p.coeff(5), GF16::ZERO);
+    assert_eq!(Poly::monomial(GF16::from(3), 2), p16(&[0, 0, 3]));
+    assert_eq!(Poly::monomial(GF16::ZERO, 2), Poly::zero());
+}
+
+#[test]
+fn test_add_mul() {
+    let a = p16(&[1, 2, 3]);
+    let b = p16(&[4, 5]);
+    assert_eq!(&a + &b, p16(&[5, 7, 3]));
+    assert_eq!(&a - &b, &a + &b);
+    assert_eq!(&a + &a, Poly::zero());
+    assert_eq!(-a.clone(), a);
+
+    let product = &a * &b;
+    assert_eq!(product.degree(), Some(3));
+    for x in 0..16 {
+        let x = GF16::from(x);
+        assert_eq!(product.eval(x), a.eval(x) * b.eval(x));
+    }
+    assert_eq!(&a * &Poly::zero(), Poly::zero());
+    assert_eq!(&a * &Poly::one(), a);
+}
+
+#[test]
+fn test_divrem() {
+    let elements: Vec<Poly<GF256>> = vec![
+        p256(&[1]),
+        p256(&[7, 1]),
+        p256(&[0x53, 0xca, 0x11]),
+        p256(&[1, 2, 3, 4, 5, 6, 7]),
+        p256(&[0xff, 0, 0, 0, 0, 0, 0, 0, 0x80]),
+    ];
+    for a in elements.iter() {
+        for b in elements.iter() {
+            let (q, r) = a.divrem(b);
+            assert_eq!(&(&q * b) + &r, *a);
+            assert!(r.degree() < b.degree() || r.is_zero());
+            assert_eq!(a / b, q);
+            assert_eq!(a % b, r);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_div_by_zero() {
+    let _ = p16(&[1, 2]).divrem(&Poly::zero());
+}
+
+#[test]
+fn test_eval() {
+    let p = p256(&[3, 0, 1]);
+    for x in 0..=255 {
+        let x = GF256::from(x);
+        assert_eq!(p.eval(x), x * x + GF256::from(3));
+    }
+    assert_eq!(Poly::<GF256>::zero().eval(GF256::from(5)), GF256::ZERO);
+}
+
+#[test]
+fn test_from_roots() {
+    let roots = [GF256::from(1), GF256::from(0x20), GF256::from(0xfe)];
+    let p = Poly::from_roots(&roots);
+    assert_eq!(p.degree(), Some(3));
+    assert_eq!(p.leading_coeff(), Some(GF256::ONE));
+    for x in 0..=255 {
+        let x = GF256::from(x);
+        assert_eq!(p.eval(x) == GF256::ZERO, roots.contains(&x));
+    }
+}
+
+#[test]
+fn test_derivative() {
+    // (x^3 + 5x^2 + 2x + 1)' = 3x^2 + 10x + 2 = x^2 + 2
+    let p = p16(&[1, 2, 5, 1]);
+    assert_eq!(p.derivative(), p16(&[2, 0, 1]));
+    assert_eq!(p16(&[7]).derivative(), Poly::zero());
+
+    // Product rule
+    let a = p16(&[3, 1, 4, 1, 5]);
+    let b = p16(&[9, 2, 6]);
+    assert_eq!(
+        (&a * &b).derivative(),
+        &(&a.derivative() * &b) + &(&a * &b.derivative())
+    );
+}
+
+#[test]
+fn test_compose() {
+    let a = p16(&[1, 2, 3]);
+    let b = p16(&[4, 0, 5]);
+    let c = a.compose(&b);
+    assert_eq!(c.degree(), Some(4));
+    for x in 0..16 {
+        let x = GF16::from(x);
+        assert_eq!(c.eval(x), a.eval(b.eval(x)));
+    }
+    assert_eq!(a.compose(&Poly::x()), a);
+}
+
+#[test]
+fn test_monic() {
+    let p = p16(&[1, 2, 7]);
+    let m = p.monic();
+    assert_eq!(m.leading_coeff(), Some(GF16::ONE));
+    assert_eq!(m.scale(GF16::from(7)), p);
+    assert_eq!(Poly::<GF16>::zero().monic(), Poly::zero());
+}
+
+#[test]
+fn test_gcd() {
+    let common = Poly::from_roots(&[GF256::from(3), GF256::from(9)]);
+    let a = &common * &p256(&[5, 1, 1]);
+    let b = &common * &Poly::from_roots(&[GF256::from(0x44)]);
+    assert_eq!(gcd(&a, &b), common);
+    assert_eq!(gcd(&a, &Poly::zero()), a.monic());
+    assert_eq!(gcd(&Poly::<GF256>::zero(), &Poly::zero()), Poly::zero());
+
+    let (g, s, t) = extended_gcd(&a, &b);
+    assert_eq!(g, common);
+    assert_eq!(&(&a * &s) + &(&b * &t), g);
+
+    // Coprime
+    let (g, s, t) = extended_gcd(&p256(&[1, 1]), &p256(&[2, 1]));
+    assert_eq!(g, Poly::one());
+    assert_eq!(&(&p256(&[1, 1]) * &s) + &(&p256(&[2, 1]) * &t), g);
+}
+
+#[test]
+fn test_interpolate() {
+    let p = Poly::new(
+        [0x1234_u16, 0xbeef, 0, 7, 0xffff]
+            .iter()
+            .map(|&c| GF65536::from(c))
+            .collect(),
+    );
+    let points: Vec<_> = (100..105_u16)
+        .map(|x| (GF65536::from(x), p.eval(GF65536::from(x))))
+        .collect();
+    assert_eq!(Poly::interpolate(&points), Some(p));
+
+    assert_eq!(Poly::<GF65536>::interpolate(&[]), Some(Poly::zero()));
+    let dup = [
+        (GF65536::from(1), GF65536::from(2)),
+        (GF65536::from(1), GF65536::from(3)),
+    ];
+    assert_eq!(Poly::interpolate(&dup), None);
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(
+        format!("{}", p16(&[1, 0, 3, 1])),
+        "Poly { x^3 + 3_GF16*x^2 + 1_GF16 }"
+    );
+    assert_eq!(format!("{}", p16(&[0, 1])), "Poly { x }");
+    assert_eq!(format!("{}", Poly::<GF16>::zero()), "Poly { 0 }");
+}
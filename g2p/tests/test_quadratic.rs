@@ -0,0 +1,79 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    g2p,
+    quadratic::{solve_artin_schreier, solve_quadratic, QuadraticRoots},
+    GaloisField, GF2,
+};
+
+g2p!(GF8, 3);
+g2p!(GF16, 4);
+g2p!(GF32, 5);
+g2p!(GF256, 8);
+g2p!(GF1024, 10);
+g2p!(GF2048, 11);
+
+// Compare against a brute force search for all coefficients
+fn check_exhaustive<F: GaloisField + std::fmt::Debug>(elements: Vec<F>) {
+    for &a in elements.iter() {
+        for &b in elements.iter() {
+            let expected: Vec<F> = elements
+                .iter()
+                .copied()
+                .filter(|&x| x * x + a * x + b == F::ZERO)
+                .collect();
+            let roots = solve_quadratic(a, b);
+            assert_eq!(expected.len(), roots.count(), "a = {:?}, b = {:?}", a, b);
+            for i in 0..roots.count() {
+                assert!(expected.contains(&roots.get(i).unwrap()));
+            }
+            assert_eq!(None, roots.get(roots.count()));
+            if let QuadraticRoots::Two(x, y) = roots {
+                assert_ne!(x, y);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_exhaustive() {
+    check_exhaustive((0..2).map(GF2::from).collect());
+    check_exhaustive((0..8).map(GF8::from).collect());
+    check_exhaustive((0..16).map(GF16::from).collect());
+    check_exhaustive((0..32).map(GF32::from).collect());
+    check_exhaustive((0..=255).map(GF256::from).collect());
+}
+
+fn check_artin_schreier<F: GaloisField + std::fmt::Debug>(elements: Vec<F>) {
+    for c in elements {
+        match solve_artin_schreier(c) {
+            Some(y) => assert_eq!(c, y * y + y),
+            None => assert_eq!(GF2::ONE, c.trace()),
+        }
+    }
+}
+
+#[test]
+fn test_artin_schreier() {
+    check_artin_schreier((0..1024).map(GF1024::from).collect());
+    check_artin_schreier((0..2048).map(GF2048::from).collect());
+}
+
+#[test]
+fn test_roots() {
+    let (r1, r2) = (GF1024::from(0x123), GF1024::from(0x2f0));
+    match solve_quadratic(r1 + r2, r1 * r2) {
+        QuadraticRoots::Two(x, y) => assert!((x, y) == (r1, r2) || (x, y) == (r2, r1)),
+        r => panic!("Unexpected roots {:?}", r),
+    }
+
+    // Double root
+    assert_eq!(
+        QuadraticRoots::One(r1),
+        solve_quadratic(GF1024::ZERO, r1 * r1)
+    );
+}
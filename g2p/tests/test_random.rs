@@ -0,0 +1,70 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    g2p,
+    random::{random_nonzero, NonZero},
+    GaloisField, GF2,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+g2p!(GF16, 4);
+g2p!(GF1024, 10);
+g2p!(CtGF256, 8, constant_time: true);
+g2p!(GF1M, 20, tables: lazy);
+
+// Count how often each value is sampled and check that all counts are close to the mean
+fn check_uniform(samples: impl Iterator<Item = usize>, size: usize, offset: usize) {
+    let mut counts = vec![0_usize; size];
+    let mut n = 0;
+    for s in samples {
+        counts[s] += 1;
+        n += 1;
+    }
+    assert!(counts[..offset].iter().all(|&c| c == 0));
+    let mean = n / (size - offset);
+    for &c in counts[offset..].iter() {
+        assert!(c > mean * 3 / 4 && c < mean * 5 / 4, "{} vs {}", c, mean);
+    }
+}
+
+#[test]
+fn test_standard() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    check_uniform((0..16_000).map(|_| rng.gen::<GF16>().0 as usize), 16, 0);
+    check_uniform(
+        (0..256_000).map(|_| rng.gen::<CtGF256>().0 as usize),
+        256,
+        0,
+    );
+    check_uniform((0..2_000).map(|_| rng.gen::<GF2>().0 as usize), 2, 0);
+
+    // Values are always inside the field
+    for _ in 0..10_000 {
+        assert!(rng.gen::<GF1024>().0 < 1024);
+        assert!(rng.gen::<GF1M>().0 < 1 << 20);
+    }
+}
+
+#[test]
+fn test_nonzero() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    check_uniform(
+        (0..15_000).map(|_| random_nonzero::<GF16, _>(&mut rng).0 as usize),
+        16,
+        1,
+    );
+    check_uniform(
+        (0..1_000).map(|_| rng.sample::<GF2, _>(NonZero).0 as usize),
+        2,
+        1,
+    );
+
+    for _ in 0..10_000 {
+        let x: GF1024 = rng.sample(NonZero);
+        assert_eq!(GF1024::ONE, x * x.inverse());
+    }
+}
@@ -0,0 +1,165 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    g2p,
+    reed_solomon::{DecodeError, ReedSolomonCode},
+    GaloisField,
+};
+
+g2p!(QrField, 8, modulus: 0b1_0001_1101);
+g2p!(CcsdsField, 8, modulus: 0b1_1000_0111);
+g2p!(GF16, 4);
+g2p!(GF65536, 16);
+
+fn qr(bytes: &[u8]) -> Vec<QrField> {
+    bytes.iter().map(|&b| QrField::from(b)).collect()
+}
+
+// Small deterministic generator for error patterns
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % bound
+    }
+}
+
+#[test]
+fn test_qr_code_vector() {
+    // Version 1-M "HELLO WORLD", data and error correction codewords
+    let data = qr(&[
+        32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+    ]);
+    let ec = qr(&[196, 35, 39, 119, 235, 215, 231, 226, 93, 23]);
+    let code = ReedSolomonCode::with_params(10, 0, QrField::from(2));
+    assert_eq!(code.parity(&data), ec);
+    assert_eq!(code.generator_poly().degree(), Some(10));
+    assert_eq!(code.max_len(), 255);
+}
+
+#[test]
+fn test_errors_and_erasures() {
+    let code = ReedSolomonCode::with_params(10, 0, QrField::from(2));
+    let mut rng = Lcg(42);
+    let message: Vec<QrField> = (0..40)
+        .map(|i| QrField::from((i as u8).wrapping_mul(7)))
+        .collect();
+    let codeword = code.encode(&message);
+    assert!(code.is_valid(&codeword));
+
+    for errors in 0..=5 {
+        for erasures in 0..=(10 - 2 * errors) {
+            for _ in 0..10 {
+                let mut received = codeword.clone();
+                let mut positions: Vec<usize> = Vec::new();
+                while positions.len() < errors + erasures {
+                    let p = rng.next(received.len());
+                    if !positions.contains(&p) {
+                        positions.push(p);
+                    }
+                }
+                for &p in positions.iter().take(errors) {
+                    received[p] += QrField::from(1 + rng.next(255) as u8);
+                }
+                for &p in positions.iter().skip(errors) {
+                    received[p] = QrField::from(rng.next(256) as u8);
+                }
+
+                let corrected = code.decode(&mut received, &positions[errors..]).unwrap();
+                assert_eq!(received, codeword);
+                assert!(corrected >= errors && corrected <= errors + erasures);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_ccsds_parameters() {
+    // CCSDS uses a = x^11 and the roots a^112 .. a^143 in the conventional representation
+    let alpha = CcsdsField::from(2).pow(11);
+    let code = ReedSolomonCode::with_params(32, 112, alpha);
+    assert_eq!(code.max_len(), 255);
+
+    // The generator polynomial is symmetric
+    let g = code.generator_poly().coeffs();
+    for i in 0..=32 {
+        assert_eq!(g[i], g[32 - i]);
+    }
+
+    let message: Vec<CcsdsField> = (0..223).map(|i| CcsdsField::from(i as u8)).collect();
+    let codeword = code.encode(&message);
+    let mut received = codeword.clone();
+    for i in 0..16 {
+        received[i * 15] += CcsdsField::from(0x5a);
+    }
+    assert_eq!(code.decode(&mut received, &[]), Ok(16));
+    assert_eq!(received, codeword);
+}
+
+#[test]
+fn test_too_many_errors() {
+    let code = ReedSolomonCode::<GF16>::new(4);
+    let message: Vec<GF16> = (0..8).map(|i| GF16::from(i as u8)).collect();
+    let codeword = code.encode(&message);
+
+    // Beyond the capability the decoder either fails or finds another codeword
+    let mut rng = Lcg(7);
+    for _ in 0..100 {
+        let mut received = codeword.clone();
+        for _ in 0..3 {
+            let p = rng.next(received.len());
+            received[p] += GF16::from(1 + rng.next(15) as u8);
+        }
+        match code.decode(&mut received, &[]) {
+            Ok(_) => assert!(code.is_valid(&received)),
+            Err(e) => assert_eq!(e, DecodeError::Uncorrectable),
+        }
+    }
+}
+
+#[test]
+fn test_wide_field() {
+    let code = ReedSolomonCode::<GF65536>::new(16);
+    let message: Vec<GF65536> = (0..1000_u16).map(|i| GF65536::from(i * 37)).collect();
+    let codeword = code.encode(&message);
+    let mut received = codeword.clone();
+    for &p in [3, 500, 999, 1010].iter() {
+        received[p] = GF65536::from(0xdead);
+    }
+    assert_eq!(code.decode(&mut received, &[1, 2]), Ok(4));
+    assert_eq!(received, codeword);
+}
+
+#[test]
+fn test_invalid_input() {
+    let code = ReedSolomonCode::<GF16>::new(4);
+    let mut long = vec![GF16::ZERO; 16];
+    assert_eq!(code.decode(&mut long, &[]), Err(DecodeError::InvalidLength));
+
+    let mut codeword = code.encode(&[GF16::ONE; 5]);
+    assert_eq!(
+        code.decode(&mut codeword, &[1, 1]),
+        Err(DecodeError::InvalidErasure)
+    );
+    assert_eq!(
+        code.decode(&mut codeword, &[9]),
+        Err(DecodeError::InvalidErasure)
+    );
+    assert_eq!(
+        code.decode(&mut codeword, &[0, 1, 2, 3, 4]),
+        Err(DecodeError::TooManyErasures)
+    );
+    assert_eq!(code.decode(&mut codeword, &[0, 1, 2, 3]), Ok(0));
+
+    // Generator with order 5 limits the length
+    let code = ReedSolomonCode::with_params(2, 1, GF16::GENERATOR.pow(3));
+    assert_eq!(code.max_len(), 5);
+}
@@ -0,0 +1,108 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{g2p, GaloisField};
+
+g2p!(GF16, 4);
+g2p!(GF256, 8);
+g2p!(GF1024, 10);
+g2p!(GF65536, 16);
+g2p!(GF1M, 20, tables: lazy);
+
+#[test]
+fn test_constants() {
+    assert_eq!((4, 8, 1), (GF16::DEGREE, GF16::BITS, GF16::BYTES));
+    assert_eq!((8, 8, 1), (GF256::DEGREE, GF256::BITS, GF256::BYTES));
+    assert_eq!((10, 16, 2), (GF1024::DEGREE, GF1024::BITS, GF1024::BYTES));
+    assert_eq!(
+        (16, 16, 2),
+        (GF65536::DEGREE, GF65536::BITS, GF65536::BYTES)
+    );
+    assert_eq!((20, 32, 3), (GF1M::DEGREE, GF1M::BITS, GF1M::BYTES));
+}
+
+#[test]
+fn test_repr() {
+    assert_eq!(0x3ff_u16, GF1024::from(0x3ff).to_repr());
+    assert_eq!(Some(GF1024::from(0x3ff)), GF1024::from_repr(0x3ff));
+    assert_eq!(None, GF1024::from_repr(0x400));
+    assert_eq!(Some(GF65536::from(0xffff)), GF65536::from_repr(0xffff));
+
+    // Bits outside of the field are not part of the representation
+    assert_eq!(0x3, GF16(0xf3).to_repr());
+}
+
+// Serialisation written only against the trait, as used by a codec layer
+fn encode<F: GaloisField>(values: &[F], big_endian: bool) -> Vec<u8> {
+    let mut out = vec![0; values.len() * F::BYTES];
+    for (v, chunk) in values.iter().zip(out.chunks_exact_mut(F::BYTES)) {
+        if big_endian {
+            v.write_be_bytes(chunk);
+        } else {
+            v.write_le_bytes(chunk);
+        }
+    }
+    out
+}
+
+fn decode<F: GaloisField>(bytes: &[u8], big_endian: bool) -> Option<Vec<F>> {
+    bytes
+        .chunks(F::BYTES)
+        .map(|chunk| {
+            if big_endian {
+                F::from_be_bytes(chunk)
+            } else {
+                F::from_le_bytes(chunk)
+            }
+        })
+        .collect()
+}
+
+fn check_roundtrip<F: GaloisField + std::fmt::Debug>(values: Vec<F>) {
+    for &big_endian in [false, true].iter() {
+        let bytes = encode(&values, big_endian);
+        assert_eq!(values.len() * F::BYTES, bytes.len());
+        assert_eq!(Some(values.clone()), decode(&bytes, big_endian));
+    }
+    for &v in values.iter() {
+        assert_eq!(Some(v), F::from_repr(v.to_repr()));
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    check_roundtrip((0..16).map(GF16::from).collect());
+    check_roundtrip((0..=255).map(GF256::from).collect());
+    check_roundtrip((0..1024).map(GF1024::from).collect());
+    check_roundtrip((0..=0xffff).step_by(7).map(GF65536::from).collect());
+    check_roundtrip((0..1 << 20).step_by(997).map(GF1M::from).collect());
+}
+
+#[test]
+fn test_byte_order() {
+    let v = GF1M::from(0x0a_bc_de);
+    let mut buf = [0; 3];
+    v.write_le_bytes(&mut buf);
+    assert_eq!([0xde, 0xbc, 0x0a], buf);
+    v.write_be_bytes(&mut buf);
+    assert_eq!([0x0a, 0xbc, 0xde], buf);
+}
+
+#[test]
+fn test_invalid_bytes() {
+    assert_eq!(None, GF1024::from_le_bytes(&[0x00, 0x04]));
+    assert_eq!(None, GF1024::from_be_bytes(&[0x04, 0x00]));
+    assert_eq!(None, GF1024::from_le_bytes(&[0x00]));
+    assert_eq!(None, GF1024::from_le_bytes(&[0x00, 0x00, 0x00]));
+    assert_eq!(None, GF16::from_le_bytes(&[0x10]));
+    assert_eq!(None, GF1M::from_be_bytes(&[0x10, 0x00, 0x00]));
+}
+
+#[test]
+#[should_panic]
+fn test_write_wrong_length() {
+    GF1024::ONE.write_le_bytes(&mut [0; 4]);
+}
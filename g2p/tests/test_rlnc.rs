@@ -0,0 +1,186 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    g2p,
+    rlnc::{CodedPacket, Decoder, Encoder, Recoder, RlncError},
+    GaloisField,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+g2p!(GF256, 8);
+g2p!(GF16, 4);
+
+fn generation(g: usize, len: usize) -> Vec<Vec<GF256>> {
+    (0..g)
+        .map(|i| {
+            (0..len)
+                .map(|j| GF256::from((i * 41 + j * 13 + 7) as u8))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_random_packets_decode() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let symbols = generation(16, 100);
+    let encoder = Encoder::new(symbols.clone()).unwrap();
+    let mut decoder = Decoder::new(16, 100);
+
+    let mut sent = 0;
+    while !decoder.is_complete() {
+        let rank = decoder.rank();
+        assert_eq!(decoder.generation(), None);
+        let innovative = decoder.add(&encoder.encode(&mut rng)).unwrap();
+        assert_eq!(decoder.rank(), rank + innovative as usize);
+        sent += 1;
+    }
+    // Over GF(256), dependent packets are rare
+    assert!(sent < 20);
+    assert_eq!(decoder.generation(), Some(symbols.clone()));
+    for (i, s) in symbols.iter().enumerate() {
+        assert_eq!(decoder.symbol(i), Some(&s[..]));
+    }
+    // Further packets are not innovative
+    assert!(!decoder.add(&encoder.encode(&mut rng)).unwrap());
+}
+
+#[test]
+fn test_dependent_packets() {
+    let symbols = generation(3, 5);
+    let encoder = Encoder::new(symbols.clone()).unwrap();
+    let mut decoder = Decoder::new(3, 5);
+
+    let c = |v: [u8; 3]| v.iter().map(|&x| GF256::from(x)).collect::<Vec<_>>();
+    let a = encoder.encode_coefficients(&c([1, 2, 3])).unwrap();
+    let b = encoder.encode_coefficients(&c([4, 5, 6])).unwrap();
+    assert!(decoder.add(&a).unwrap());
+    assert!(!decoder.add(&a).unwrap());
+    assert!(decoder.add(&b).unwrap());
+
+    // a + b is in the span
+    let sum = CodedPacket {
+        coefficients: a
+            .coefficients
+            .iter()
+            .zip(b.coefficients.iter())
+            .map(|(&x, &y)| x + y)
+            .collect(),
+        payload: a
+            .payload
+            .iter()
+            .zip(b.payload.iter())
+            .map(|(&x, &y)| x + y)
+            .collect(),
+    };
+    assert!(!decoder.add(&sum).unwrap());
+    assert_eq!(decoder.rank(), 2);
+
+    assert!(decoder.add(&encoder.systematic(1)).unwrap());
+    assert_eq!(decoder.generation(), Some(symbols));
+}
+
+#[test]
+fn test_systematic_symbols_available_early() {
+    let symbols = generation(4, 3);
+    let encoder = Encoder::new(symbols.clone()).unwrap();
+    let mut decoder = Decoder::new(4, 3);
+
+    decoder.add(&encoder.systematic(2)).unwrap();
+    assert_eq!(decoder.symbol(2), Some(&symbols[2][..]));
+    assert_eq!(decoder.symbol(0), None);
+
+    // A coded packet involving symbols 0 and 1 does not reveal either yet
+    let coded = encoder
+        .encode_coefficients(&[GF256::ONE, GF256::from(3), GF256::from(9), GF256::ZERO])
+        .unwrap();
+    decoder.add(&coded).unwrap();
+    assert_eq!(decoder.symbol(0), None);
+    assert_eq!(decoder.symbol(1), None);
+
+    decoder.add(&encoder.systematic(1)).unwrap();
+    assert_eq!(decoder.symbol(0), Some(&symbols[0][..]));
+    assert_eq!(decoder.symbol(1), Some(&symbols[1][..]));
+    assert_eq!(decoder.symbol(3), None);
+    assert_eq!(decoder.rank(), 3);
+}
+
+#[test]
+fn test_recoding_chain() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let symbols: Vec<Vec<GF16>> = (0..8)
+        .map(|i| (0..10).map(|j| GF16::from(((i + j) % 16) as u8)).collect())
+        .collect();
+    let encoder = Encoder::new(symbols.clone()).unwrap();
+    let mut first = Recoder::new(8, 10);
+    let mut second = Recoder::new(8, 10);
+    let mut decoder = Decoder::new(8, 10);
+    assert_eq!(first.recode(&mut rng), None);
+
+    let mut rounds = 0;
+    while !decoder.is_complete() {
+        first.add(encoder.encode(&mut rng)).unwrap();
+        second.add(first.recode(&mut rng).unwrap()).unwrap();
+        decoder.add(&second.recode(&mut rng).unwrap()).unwrap();
+        assert!(decoder.rank() <= second.rank() && second.rank() <= first.rank());
+        rounds += 1;
+        assert!(rounds < 100);
+    }
+    assert_eq!(decoder.generation(), Some(symbols));
+}
+
+#[test]
+fn test_recoder_keeps_innovative_packets() {
+    let symbols = generation(3, 2);
+    let encoder = Encoder::new(symbols.clone()).unwrap();
+    let mut recoder = Recoder::new(3, 2);
+    assert!(recoder.add(encoder.systematic(0)).unwrap());
+    assert!(!recoder.add(encoder.systematic(0)).unwrap());
+    assert_eq!(recoder.rank(), 1);
+
+    // Recoded packets stay in the span of the received ones
+    let c = GF256::from(7);
+    let packet = recoder.recode_with(|| c).unwrap();
+    assert_eq!(packet.coefficients, vec![c, GF256::ZERO, GF256::ZERO]);
+    assert_eq!(
+        packet.payload,
+        symbols[0].iter().map(|&x| x * c).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        Encoder::<GF256>::new(vec![]).unwrap_err(),
+        RlncError::EmptyGeneration
+    );
+    assert_eq!(
+        Encoder::<GF256>::new(vec![vec![]]).unwrap_err(),
+        RlncError::EmptySymbol
+    );
+    assert_eq!(
+        Encoder::new(vec![vec![GF256::ONE], vec![GF256::ONE; 2]]).unwrap_err(),
+        RlncError::SymbolSizeMismatch
+    );
+
+    let encoder = Encoder::new(generation(3, 4)).unwrap();
+    assert_eq!(
+        encoder.encode_coefficients(&[GF256::ONE]).unwrap_err(),
+        RlncError::CoefficientCountMismatch
+    );
+
+    let mut decoder = Decoder::new(4, 4);
+    assert_eq!(
+        decoder.add(&encoder.systematic(0)).unwrap_err(),
+        RlncError::CoefficientCountMismatch
+    );
+    let mut decoder = Decoder::new(3, 5);
+    assert_eq!(
+        decoder.add(&encoder.systematic(0)).unwrap_err(),
+        RlncError::PayloadSizeMismatch
+    );
+}
@@ -0,0 +1,141 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{
+    g2p,
+    shamir::{combine, split, split_with, ShamirError, Share},
+    GaloisField,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+g2p!(
+    GF256,
+    8,
+    modulus: 0b_1_0001_1011,
+);
+g2p!(GF16, 4);
+g2p!(GF65536, 16);
+
+fn secret() -> Vec<GF256> {
+    b"correct horse battery staple"
+        .iter()
+        .map(|&b| GF256::from(b))
+        .collect()
+}
+
+#[test]
+fn test_any_subset_recovers() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let secret = secret();
+    let shares = split(&secret, 3, 6, &mut rng).unwrap();
+    assert_eq!(shares.len(), 6);
+
+    for a in 0..6 {
+        for b in 0..6 {
+            for c in 0..6 {
+                if a == b || b == c || a == c {
+                    continue;
+                }
+                let subset = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                assert_eq!(combine(&subset).unwrap(), secret);
+            }
+        }
+    }
+    // More than the threshold works as well
+    assert_eq!(combine(&shares).unwrap(), secret);
+    // Fewer does not
+    assert_ne!(combine(&shares[..2]).unwrap(), secret);
+}
+
+#[test]
+fn test_deterministic_coefficients() {
+    // With all random coefficients one, the polynomial is s + x + x^2
+    let secret = [GF16::from(5)];
+    let shares = split_with(&secret, 3, 4, || GF16::ONE).unwrap();
+    for share in shares.iter() {
+        let x = share.x;
+        assert_eq!(share.y, vec![GF16::from(5) + x + x * x]);
+    }
+    assert_eq!(
+        shares.iter().map(|s| s.x).collect::<Vec<_>>(),
+        vec![GF16::from(1), GF16::from(2), GF16::from(3), GF16::from(4)]
+    );
+}
+
+#[test]
+fn test_threshold_one() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let shares = split(&secret(), 1, 3, &mut rng).unwrap();
+    for share in shares.iter() {
+        assert_eq!(share.y, secret());
+    }
+}
+
+#[test]
+fn test_max_shares() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let secret = [GF16::from(9), GF16::from(3)];
+    let shares = split(&secret, 15, 15, &mut rng).unwrap();
+    assert_eq!(combine(&shares).unwrap(), secret);
+    assert_eq!(
+        split(&secret, 2, 16, &mut rng).unwrap_err(),
+        ShamirError::TooManyShares
+    );
+
+    let secret = [GF65536::from(0xbeef)];
+    let shares = split(&secret, 300, 1000, &mut rng).unwrap();
+    assert_eq!(combine(&shares[500..800]).unwrap(), secret);
+}
+
+#[test]
+fn test_errors() {
+    let mut rng = StdRng::seed_from_u64(4);
+    let secret = secret();
+    assert_eq!(
+        split(&secret, 0, 3, &mut rng).unwrap_err(),
+        ShamirError::InvalidThreshold
+    );
+    assert_eq!(
+        split(&secret, 4, 3, &mut rng).unwrap_err(),
+        ShamirError::InvalidThreshold
+    );
+
+    let shares = split(&secret, 2, 3, &mut rng).unwrap();
+    assert_eq!(combine::<GF256>(&[]).unwrap_err(), ShamirError::NoShares);
+    assert_eq!(
+        combine(&[shares[0].clone(), shares[0].clone()]).unwrap_err(),
+        ShamirError::DuplicateShare
+    );
+    // Bits above the field degree are ignored, so these are the same point
+    let unreduced = [
+        Share {
+            x: GF16(0x13),
+            y: vec![GF16(1)],
+        },
+        Share {
+            x: GF16(0x03),
+            y: vec![GF16(2)],
+        },
+    ];
+    assert_eq!(
+        combine(&unreduced).unwrap_err(),
+        ShamirError::DuplicateShare
+    );
+    let zero = Share {
+        x: GF256::ZERO,
+        y: secret.clone(),
+    };
+    assert_eq!(
+        combine(&[shares[0].clone(), zero]).unwrap_err(),
+        ShamirError::InvalidIndex
+    );
+    let mut short = shares[1].clone();
+    short.y.pop();
+    assert_eq!(
+        combine(&[shares[0].clone(), short]).unwrap_err(),
+        ShamirError::ShareLengthMismatch
+    );
+}
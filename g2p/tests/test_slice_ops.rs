@@ -0,0 +1,96 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use g2p::{g2p, GaloisField};
+
+g2p!(GF16, 4);
+g2p!(GF256, 8);
+g2p!(Lazy256, 8, tables: lazy);
+g2p!(CtGF256, 8, constant_time: true);
+g2p!(GF65536, 16);
+
+const LENGTHS: [usize; 9] = [0, 1, 15, 16, 17, 31, 32, 33, 100];
+
+fn elements<F: GaloisField>(from: fn(u8) -> F, len: usize, seed: usize) -> Vec<F> {
+    (0..len)
+        .map(|i| from(((i * 37 + seed * 11) % 251) as u8))
+        .collect()
+}
+
+fn check_slice_ops<F: GaloisField + std::fmt::Debug>(from: fn(u8) -> F) {
+    for &len in LENGTHS.iter() {
+        let src = elements(from, len, 1);
+        let init = elements(from, len, 2);
+        for c in elements(from, 20, 3) {
+            let mut dst = init.clone();
+            F::mul_slice(&mut dst, &src, c);
+            let expected: Vec<F> = src.iter().map(|&s| s * c).collect();
+            assert_eq!(expected, dst);
+
+            let mut dst = init.clone();
+            F::mul_add_slice(&mut dst, &src, c);
+            let expected: Vec<F> = src.iter().zip(&init).map(|(&s, &d)| d + s * c).collect();
+            assert_eq!(expected, dst);
+
+            if c != F::ZERO {
+                let mut dst = init.clone();
+                F::div_slice(&mut dst, &src, c);
+                let expected: Vec<F> = src.iter().map(|&s| s / c).collect();
+                assert_eq!(expected, dst);
+            }
+        }
+
+        let mut dst = init.clone();
+        F::add_slice(&mut dst, &src);
+        let expected: Vec<F> = src.iter().zip(&init).map(|(&s, &d)| d + s).collect();
+        assert_eq!(expected, dst);
+    }
+}
+
+#[test]
+fn test_slice_ops() {
+    check_slice_ops(GF16::from);
+    check_slice_ops(GF256::from);
+    check_slice_ops(Lazy256::from);
+    check_slice_ops(CtGF256::from);
+    check_slice_ops(|v| GF65536::from(u16::from(v) * 257));
+}
+
+#[test]
+fn test_unreduced_elements() {
+    // The public field allows values outside of GF16, they are reduced like in scalar operations
+    let src: Vec<GF16> = (0..=255).map(GF16).collect();
+    let mut dst = vec![GF16::ZERO; src.len()];
+    GF16::mul_slice(&mut dst, &src, GF16(7));
+    let expected: Vec<GF16> = src.iter().map(|&s| s * GF16(7)).collect();
+    assert_eq!(expected, dst);
+}
+
+#[test]
+fn test_byte_slices() {
+    let c = GF256::from(0x53);
+    let tables = g2p::simd::NibbleTables::from_fn(|x| (c * GF256::from(x)).into());
+    let src: Vec<u8> = (0..=255).collect();
+    let mut dst = vec![0; src.len()];
+    g2p::simd::mul_slice(&tables, &mut dst, &src);
+    for (&s, &d) in src.iter().zip(&dst) {
+        assert_eq!(GF256::from(d), c * GF256::from(s));
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_length_mismatch() {
+    let mut dst = [GF256::ZERO; 3];
+    GF256::mul_slice(&mut dst, &[GF256::ONE; 4], GF256::ONE);
+}
+
+#[test]
+#[should_panic]
+fn test_div_slice_by_zero() {
+    let mut dst = [GF256::ZERO; 3];
+    GF256::div_slice(&mut dst, &[GF256::ONE; 3], GF256::ZERO);
+}
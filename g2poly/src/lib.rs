@@ -30,6 +30,11 @@
 //! let s = a * a % g2poly::G2Poly(0b1000000);
 //! assert_eq!(s, g2poly::G2Poly(0b101));
 //! ```
+//!
+//! # `no_std`
+//! This crate only depends on `core`, so it can be used on targets without the standard library.
+
+#![no_std]
 
 use core::{cmp, fmt, ops};
 
@@ -478,7 +483,10 @@ impl G2Poly {
 
 #[cfg(test)]
 mod tests {
+    extern crate std;
+
     use super::*;
+    use std::format;
 
     #[test]
     fn test_debug_format() {
@@ -0,0 +1,31 @@
+use g2build::{Field, TableStorage};
+use g2poly::G2Poly;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    Field::with_modulus("GF256", 8, G2Poly(0b1_0001_1011))
+        .unwrap()
+        .table_storage(TableStorage::Binary)
+        .write_to_out_dir("gf256.rs")
+        .unwrap();
+    Field::new("GF1024", 10)
+        .unwrap()
+        .write_to_out_dir("gf1024.rs")
+        .unwrap();
+    Field::new("GF65536", 16)
+        .unwrap()
+        .table_storage(TableStorage::Binary)
+        .write_to_out_dir("gf65536.rs")
+        .unwrap();
+    Field::new("GF131072", 17)
+        .unwrap()
+        .table_storage(TableStorage::Binary)
+        .write_to_out_dir("gf131072.rs")
+        .unwrap();
+    Field::new("GF1M", 20)
+        .unwrap()
+        .table_storage(TableStorage::Lazy)
+        .write_to_out_dir("gf1m.rs")
+        .unwrap();
+}
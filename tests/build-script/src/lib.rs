@@ -0,0 +1,7 @@
+//! Field types generated by a build script instead of the `g2p!` macro
+
+include!(concat!(env!("OUT_DIR"), "/gf256.rs"));
+include!(concat!(env!("OUT_DIR"), "/gf1024.rs"));
+include!(concat!(env!("OUT_DIR"), "/gf65536.rs"));
+include!(concat!(env!("OUT_DIR"), "/gf131072.rs"));
+include!(concat!(env!("OUT_DIR"), "/gf1m.rs"));
@@ -0,0 +1,70 @@
+use g2p::{g2p, GaloisField};
+use g2p_build_script_test::{GF1024, GF131072, GF1M, GF256, GF65536};
+
+mod from_macro {
+    g2p::g2p!(GF1024, 10);
+    g2p::g2p!(GF65536, 16);
+}
+
+g2p!(Rijndael, 8, modulus: 0b_1_0001_1011);
+
+#[test]
+fn test_rijndael() {
+    let b = GF256::from(1);
+    let c = GF256::from(0x53);
+    let d = GF256::from(0xca);
+
+    assert_eq!(GF256::MODULUS, Rijndael::MODULUS);
+    assert_eq!(c * d, b);
+    assert_eq!(b / c, d);
+
+    for i in 0..=255 {
+        for j in 0..=255 {
+            assert_eq!(
+                u8::from(GF256(i) * GF256(j)),
+                u8::from(Rijndael(i) * Rijndael(j))
+            );
+        }
+    }
+}
+
+#[test]
+fn test_same_as_macro() {
+    assert_eq!(GF1024::GENERATOR.0, from_macro::GF1024::GENERATOR.0);
+    assert_eq!(GF65536::GENERATOR.0, from_macro::GF65536::GENERATOR.0);
+
+    for i in (0..1024).step_by(7) {
+        for j in (1..1024).step_by(11) {
+            let a = GF1024(i) * GF1024(j) / GF1024(3);
+            let b = from_macro::GF1024(i) * from_macro::GF1024(j) / from_macro::GF1024(3);
+            assert_eq!(a.0, b.0);
+        }
+    }
+
+    for i in (0..=65535).step_by(251) {
+        for j in (1..=65535).step_by(257) {
+            let a = GF65536(i) / GF65536(j);
+            let b = from_macro::GF65536(i) / from_macro::GF65536(j);
+            assert_eq!(a.0, b.0);
+        }
+    }
+}
+
+#[test]
+fn test_gf131072() {
+    let e: GF131072 = 1.into();
+    let a: GF131072 = 131071.into();
+    let b: GF131072 = 30000.into();
+
+    assert_eq!(e, a * (e / a));
+    assert_eq!(a * b, b * a);
+}
+
+#[test]
+fn test_lazy() {
+    let a: GF1M = 123_456.into();
+    let b: GF1M = 654_321.into();
+
+    assert_eq!(a * b / b, a);
+    assert_eq!(GF1M::GENERATOR.pow(GF1M::SIZE - 1), GF1M::ONE);
+}
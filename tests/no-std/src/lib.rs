@@ -0,0 +1,29 @@
+//! Uses fields generated by `g2p!` in a `#![no_std]` crate
+//!
+//! Build for a target without the standard library to check that neither g2p nor the generated
+//! code depend on `std`:
+//! ```text
+//! cargo build -p g2p-no-std-test --target thumbv7em-none-eabihf
+//! ```
+
+#![no_std]
+
+use g2p::{g2p, GaloisField};
+
+g2p!(GF256, 8, modulus: 0b1_0001_1011);
+g2p!(GF65536, 16, tables: lazy);
+
+/// Evaluate the polynomial with the given coefficients at `x`, highest degree first
+pub fn eval(coefficients: &[GF256], x: GF256) -> GF256 {
+    coefficients.iter().fold(GF256::ZERO, |acc, &c| acc * x + c)
+}
+
+/// Provide the storage for the lazy tables of `GF65536`
+pub fn init_gf65536(buffer: &'static mut [u16]) -> Result<(), g2p::lazy::InitError> {
+    GF65536::init_tables(buffer)
+}
+
+/// Calculate `a / b` in GF(2^16)
+pub fn div_gf65536(a: u16, b: u16) -> u16 {
+    (GF65536::from(a) / GF65536::from(b)).into()
+}
@@ -0,0 +1,20 @@
+use g2p::GaloisField;
+use g2p_no_std_test::{div_gf65536, eval, init_gf65536, GF256, GF65536};
+
+#[test]
+fn test_eval() {
+    let coefficients = [GF256::from(1), GF256::from(0), GF256::from(3)];
+    let x = GF256::from(0x53);
+
+    assert_eq!(eval(&coefficients, x), x * x + GF256::from(3));
+    assert_eq!(eval(&[], x), GF256::ZERO);
+}
+
+#[test]
+fn test_lazy_tables() {
+    let buffer = vec![0; GF65536::TABLE_LEN].leak();
+    init_gf65536(buffer).unwrap();
+
+    assert_eq!(div_gf65536(12345, 12345), 1);
+    assert_eq!(div_gf65536(0, 12345), 0);
+}